@@ -6,9 +6,13 @@ use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString};
 
 use crate::data::environment::EnvironmentItemValue;
-use crate::data::http::{Header, LockWith};
+use crate::data::http::{Header, LockWith, QueryParam};
+use crate::data::oauth2::OAuth2;
+use crate::data::signing::{HmacAuth, JwtAuth};
 use crate::utils;
 
+pub const API_KEY_DESC: &str = "auto gen api key";
+
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct Auth {
@@ -16,6 +20,37 @@ pub struct Auth {
     pub basic_username: String,
     pub basic_password: String,
     pub bearer_token: String,
+    pub oauth2: OAuth2,
+    pub aws: AwsSigV4Auth,
+    pub digest: DigestAuth,
+    pub api_key: ApiKeyAuth,
+    pub jwt: JwtAuth,
+    pub hmac: HmacAuth,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AwsSigV4Auth {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: String,
+    pub region: String,
+    pub service: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DigestAuth {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ApiKeyAuth {
+    pub name: String,
+    pub value: String,
+    pub location: ApiKeyLocation,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, EnumIter, EnumString, Display)]
@@ -24,13 +59,35 @@ pub enum AuthType {
     NoAuth,
     BearerToken,
     BasicAuth,
+    OAuth2,
+    AwsSigV4,
+    DigestAuth,
+    ApiKey,
+    Jwt,
+    HmacSignature,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, EnumIter, EnumString, Display)]
+pub enum ApiKeyLocation {
+    Header,
+    QueryParam,
+    Cookie,
 }
 
 impl Auth {
     pub fn compute_signature(&self) -> String {
         format!(
-            "Type:{} BasicUsername:{} BasicPassword:{} BearerToken:{}",
-            self.auth_type, self.basic_username, self.basic_password, self.bearer_token
+            "Type:{} BasicUsername:{} BasicPassword:{} BearerToken:{} OAuth2:[{}] Aws:[{}] Digest:[{}] ApiKey:[{}] Jwt:[{}] Hmac:[{}]",
+            self.auth_type,
+            self.basic_username,
+            self.basic_password,
+            self.bearer_token,
+            self.oauth2.compute_signature(),
+            self.aws.compute_signature(),
+            self.digest.compute_signature(),
+            self.api_key.compute_signature(),
+            self.jwt.compute_signature(),
+            self.hmac.compute_signature()
         )
     }
     pub fn get_final_type(&self, auth: Auth) -> AuthType {
@@ -38,14 +95,27 @@ impl Auth {
             AuthType::NoAuth => AuthType::NoAuth,
             AuthType::BearerToken => AuthType::BearerToken,
             AuthType::BasicAuth => AuthType::BasicAuth,
+            AuthType::OAuth2 => AuthType::OAuth2,
+            AuthType::AwsSigV4 => AuthType::AwsSigV4,
+            AuthType::DigestAuth => AuthType::DigestAuth,
+            AuthType::ApiKey => AuthType::ApiKey,
+            AuthType::Jwt => AuthType::Jwt,
+            AuthType::HmacSignature => AuthType::HmacSignature,
             AuthType::InheritAuthFromParent => auth.get_final_type(Auth {
                 auth_type: AuthType::NoAuth,
-                basic_username: "".to_string(),
-                basic_password: "".to_string(),
-                bearer_token: "".to_string(),
+                ..Default::default()
             }),
         }
     }
+    pub fn get_final_auth(&self, auth: Auth) -> Auth {
+        match self.auth_type {
+            AuthType::InheritAuthFromParent => auth.get_final_auth(Auth {
+                auth_type: AuthType::NoAuth,
+                ..Default::default()
+            }),
+            _ => self.clone(),
+        }
+    }
     pub fn build_head(
         &self,
         headers: &mut Vec<Header>,
@@ -61,6 +131,7 @@ impl Auth {
         };
         headers.retain(|h| {
             !(h.key.to_lowercase() == "authorization" && h.lock_with != LockWith::NoLock)
+                && !(h.desc == API_KEY_DESC && h.lock_with == LockWith::LockWithAuto)
         });
         match self.auth_type {
             AuthType::NoAuth => {}
@@ -78,18 +149,119 @@ impl Auth {
                 header.value = "Basic ".to_string() + encoded_credentials.as_str();
                 headers.push(header)
             }
+            AuthType::OAuth2 => {
+                if let Some(token) = self.oauth2.with_cache_key(&envs).get_token() {
+                    header.value = token.build_value();
+                    headers.push(header)
+                }
+            }
+            AuthType::AwsSigV4 => {}
+            AuthType::DigestAuth => {}
+            AuthType::Jwt => {}
+            AuthType::HmacSignature => {}
+            AuthType::ApiKey => {
+                let name = utils::replace_variable(self.api_key.name.clone(), envs.clone());
+                let value = utils::replace_variable(self.api_key.value.clone(), envs.clone());
+                if name.trim().is_empty() {
+                    return;
+                }
+                header.desc = API_KEY_DESC.to_string();
+                match self.api_key.location {
+                    ApiKeyLocation::Header => {
+                        header.key = name;
+                        header.value = value;
+                        headers.push(header)
+                    }
+                    ApiKeyLocation::QueryParam => {}
+                    ApiKeyLocation::Cookie => {
+                        header.key = "Cookie".to_string();
+                        header.value = format!("{}={}", name, value);
+                        headers.push(header)
+                    }
+                }
+            }
             AuthType::InheritAuthFromParent => auth.build_head(
                 headers,
                 envs,
                 Auth {
                     auth_type: AuthType::NoAuth,
-                    basic_username: "".to_string(),
-                    basic_password: "".to_string(),
-                    bearer_token: "".to_string(),
+                    ..Default::default()
                 },
             ),
         }
     }
+    pub fn build_params(
+        &self,
+        params: &mut Vec<QueryParam>,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        auth: Auth,
+    ) {
+        params.retain(|p| !(p.desc == API_KEY_DESC && p.lock_with == LockWith::LockWithAuto));
+        match self.auth_type {
+            AuthType::ApiKey => {
+                let name = utils::replace_variable(self.api_key.name.clone(), envs.clone());
+                if self.api_key.location == ApiKeyLocation::QueryParam && !name.trim().is_empty() {
+                    params.push(QueryParam {
+                        key: name,
+                        value: utils::replace_variable(self.api_key.value.clone(), envs.clone()),
+                        desc: API_KEY_DESC.to_string(),
+                        lock_with: LockWith::LockWithAuto,
+                        enable: true,
+                    })
+                }
+            }
+            AuthType::InheritAuthFromParent => auth.build_params(
+                params,
+                envs,
+                Auth {
+                    auth_type: AuthType::NoAuth,
+                    ..Default::default()
+                },
+            ),
+            _ => {}
+        }
+    }
+}
+
+impl AwsSigV4Auth {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "AccessKey:{} SecretKey:{} SessionToken:{} Region:{} Service:{}",
+            self.access_key, self.secret_key, self.session_token, self.region, self.service
+        )
+    }
+
+    pub fn build(&self, envs: &BTreeMap<String, EnvironmentItemValue>) -> AwsSigV4Auth {
+        AwsSigV4Auth {
+            access_key: utils::replace_variable(self.access_key.clone(), envs.clone()),
+            secret_key: utils::replace_variable(self.secret_key.clone(), envs.clone()),
+            session_token: utils::replace_variable(self.session_token.clone(), envs.clone()),
+            region: utils::replace_variable(self.region.clone(), envs.clone()),
+            service: utils::replace_variable(self.service.clone(), envs.clone()),
+        }
+    }
+}
+
+impl DigestAuth {
+    pub fn compute_signature(&self) -> String {
+        format!("Username:{} Password:{}", self.username, self.password)
+    }
+
+    pub fn build(&self, envs: &BTreeMap<String, EnvironmentItemValue>) -> DigestAuth {
+        DigestAuth {
+            username: utils::replace_variable(self.username.clone(), envs.clone()),
+            password: utils::replace_variable(self.password.clone(), envs.clone()),
+        }
+    }
+}
+
+impl ApiKeyAuth {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Name:{} Value:{} Location:{}",
+            self.name, self.value, self.location
+        )
+    }
 }
 
 impl Default for AuthType {
@@ -97,3 +269,82 @@ impl Default for AuthType {
         AuthType::InheritAuthFromParent
     }
 }
+
+impl Default for ApiKeyLocation {
+    fn default() -> Self {
+        ApiKeyLocation::Header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::data::http::{Header, QueryParam};
+
+    use super::{ApiKeyAuth, ApiKeyLocation, Auth, AuthType};
+
+    fn api_key_auth(location: ApiKeyLocation) -> Auth {
+        Auth {
+            auth_type: AuthType::ApiKey,
+            api_key: ApiKeyAuth {
+                name: "X-Api-Key".to_string(),
+                value: "secret".to_string(),
+                location,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn place(auth: &Auth) -> (Vec<(String, String)>, Vec<(String, String)>) {
+        let mut headers: Vec<Header> = vec![];
+        let mut params: Vec<QueryParam> = vec![];
+        auth.build_head(&mut headers, BTreeMap::new(), Auth::default());
+        auth.build_params(&mut params, BTreeMap::new(), Auth::default());
+        (
+            headers.into_iter().map(|h| (h.key, h.value)).collect(),
+            params.into_iter().map(|p| (p.key, p.value)).collect(),
+        )
+    }
+
+    #[test]
+    fn api_key_is_placed_by_location() {
+        let pair = |key: &str, value: &str| vec![(key.to_string(), value.to_string())];
+        assert_eq!(
+            place(&api_key_auth(ApiKeyLocation::Header)),
+            (pair("X-Api-Key", "secret"), vec![])
+        );
+        assert_eq!(
+            place(&api_key_auth(ApiKeyLocation::QueryParam)),
+            (vec![], pair("X-Api-Key", "secret"))
+        );
+        assert_eq!(
+            place(&api_key_auth(ApiKeyLocation::Cookie)),
+            (pair("Cookie", "X-Api-Key=secret"), vec![])
+        );
+        let inherit = Auth {
+            auth_type: AuthType::InheritAuthFromParent,
+            ..Default::default()
+        };
+        let mut params: Vec<QueryParam> = vec![];
+        inherit.build_params(
+            &mut params,
+            BTreeMap::new(),
+            api_key_auth(ApiKeyLocation::QueryParam),
+        );
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn sub_structs_default_missing_fields() {
+        let auth: Auth = serde_json::from_str(
+            r#"{"auth_type":"AwsSigV4","aws":{"region":"eu-west-1"},"api_key":{"name":"k"}}"#,
+        )
+        .unwrap();
+        assert_eq!(auth.aws.region, "eu-west-1");
+        assert_eq!(auth.aws.service, "");
+        assert_eq!(auth.api_key.name, "k");
+        assert_eq!(auth.api_key.location, ApiKeyLocation::Header);
+        assert_eq!(auth.digest, Default::default());
+    }
+}
@@ -152,6 +152,8 @@ impl CentralRequestItem {
             .trim()
             .trim_start_matches("https://")
             .trim_start_matches("http://")
+            .trim_start_matches("wss://")
+            .trim_start_matches("ws://")
             == ""
         {
             "Untitled Request".to_string()
@@ -169,10 +171,14 @@ impl CentralRequestItem {
 
     fn compute_signature(&self) -> String {
         format!(
-            "Request:[{}] TestScript:[{}] PreRequestScript:[{}]",
+            "Request:[{}] TestScript:[{}] PreRequestScript:[{}] RecordType:{} WebSocket:[{}] Grpc:[{}] Settings:[{}]",
             &self.rest.request.compute_signature(),
             self.rest.test_script.clone(),
-            self.rest.pre_request_script.clone()
+            self.rest.pre_request_script.clone(),
+            self.rest.record_type,
+            self.rest.websocket.compute_signature(),
+            self.rest.grpc.compute_signature(),
+            self.rest.settings.compute_signature()
         )
     }
 
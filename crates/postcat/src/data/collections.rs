@@ -18,6 +18,9 @@ pub struct Collections {
 }
 
 impl Collections {
+    pub fn get_workspace_dir(&self) -> PathBuf {
+        self.persistence.get_workspace_dir()
+    }
     pub fn load_all(&mut self, workspace: String) {
         self.persistence.set_workspace(workspace);
         for collection_file in self
@@ -49,6 +52,17 @@ impl Collections {
         );
     }
 
+    pub fn update_envs(&mut self, collection_name: String, envs: EnvironmentConfig) {
+        if let Some(collection) = self.data.get_mut(collection_name.as_str()) {
+            collection.envs = envs;
+            self.persistence.save(
+                Path::new("collections").to_path_buf(),
+                collection_name + "@info",
+                &collection.to_save_data(),
+            );
+        }
+    }
+
     pub fn update_folder(&self, folder: Rc<RefCell<CollectionFolder>>) {
         folder.borrow().save(
             self.persistence.clone(),
@@ -61,8 +75,10 @@ impl Collections {
     pub fn insert_http_record(
         &mut self,
         folder: Rc<RefCell<CollectionFolder>>,
-        record: HttpRecord,
+        mut record: HttpRecord,
     ) {
+        let order = folder.borrow().order_of(record.name.as_str(), false);
+        record.order = order;
         folder
             .borrow_mut()
             .requests
@@ -91,6 +107,10 @@ impl Collections {
         parent_folder: Rc<RefCell<CollectionFolder>>,
         folder: Rc<RefCell<CollectionFolder>>,
     ) {
+        let order = parent_folder
+            .borrow()
+            .order_of(folder.borrow().name.as_str(), true);
+        folder.borrow_mut().order = order;
         parent_folder
             .borrow_mut()
             .folders
@@ -150,17 +170,12 @@ impl Collections {
         let (_, of) = self.get_folder_with_path(path.clone());
         let binding = path.clone();
         let paths: Vec<&str> = binding.split("/").collect();
-        let mut auth;
-        match of {
-            None => {
-                auth = Auth {
-                    auth_type: Default::default(),
-                    basic_username: "".to_string(),
-                    basic_password: "".to_string(),
-                    bearer_token: "".to_string(),
-                }
-            }
-            Some(a) => auth = a.borrow().auth.clone(),
+        let auth = match of {
+            None => Auth {
+                auth_type: Default::default(),
+                ..Default::default()
+            },
+            Some(a) => a.borrow().auth.clone(),
         };
         if paths.len() == 1 || auth.auth_type != AuthType::InheritAuthFromParent {
             auth
@@ -213,15 +228,14 @@ impl Default for Collection {
                 desc: "".to_string(),
                 auth: Auth {
                     auth_type: AuthType::NoAuth,
-                    basic_username: "".to_string(),
-                    basic_password: "".to_string(),
-                    bearer_token: "".to_string(),
+                    ..Default::default()
                 },
                 is_root: true,
                 requests: Default::default(),
                 folders: Default::default(),
                 pre_request_script: "".to_string(),
                 test_script: "".to_string(),
+                order: 0,
             })),
         }
     }
@@ -293,6 +307,15 @@ pub struct CollectionFolder {
     pub folders: BTreeMap<String, Rc<RefCell<CollectionFolder>>>,
     pub pre_request_script: String,
     pub test_script: String,
+    /// Position among the requests and folders of the parent, 0 when never set.
+    pub order: usize,
+}
+
+/// A request or a child folder, by name.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CollectionItem {
+    Request(String),
+    Folder(String),
 }
 
 impl CollectionFolder {
@@ -309,8 +332,51 @@ impl CollectionFolder {
             is_root: self.is_root.clone(),
             pre_request_script: self.pre_request_script.clone(),
             test_script: self.test_script.clone(),
+            order: self.order,
         }
     }
+
+    /// Requests and child folders in the order the collection defines them. Items
+    /// with the same order, as in collections saved before the order was kept, fall
+    /// back to requests before folders, each by name.
+    pub fn ordered_items(&self) -> Vec<CollectionItem> {
+        let mut items: Vec<(usize, bool, String)> = self
+            .requests
+            .values()
+            .map(|r| (r.order, false, r.name.clone()))
+            .chain(
+                self.folders
+                    .iter()
+                    .map(|(name, f)| (f.borrow().order, true, name.clone())),
+            )
+            .collect();
+        items.sort();
+        items
+            .into_iter()
+            .map(|(_, is_folder, name)| match is_folder {
+                true => CollectionItem::Folder(name),
+                false => CollectionItem::Request(name),
+            })
+            .collect()
+    }
+
+    /// Keeps the order of an existing request or folder with this name, and places a
+    /// new one after every other item.
+    pub fn order_of(&self, name: &str, is_folder: bool) -> usize {
+        let existing = match is_folder {
+            true => self.folders.get(name).map(|f| f.borrow().order),
+            false => self.requests.get(name).map(|r| r.order),
+        };
+        existing.unwrap_or(
+            self.requests
+                .values()
+                .map(|r| r.order)
+                .chain(self.folders.values().map(|f| f.borrow().order))
+                .max()
+                .unwrap_or(0)
+                + 1,
+        )
+    }
     pub fn load(&mut self, persistence: Persistence, path: PathBuf) {
         let collection_folder: Option<CollectionFolder> =
             persistence.load(path.join("folder@info.json").to_path_buf());
@@ -328,6 +394,7 @@ impl CollectionFolder {
             self.is_root = cf.is_root;
             self.pre_request_script = cf.pre_request_script;
             self.test_script = cf.test_script;
+            self.order = cf.order;
         });
         for item in persistence.load_list(path.clone()).iter() {
             if item.is_file() {
@@ -388,4 +455,5 @@ pub struct SaveCollectionFolder {
     pub is_root: bool,
     pub pre_request_script: String,
     pub test_script: String,
+    pub order: usize,
 }
@@ -4,10 +4,11 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use strum_macros::Display;
+use strum_macros::{Display, EnumString};
 
 use crate::data::collections::Collection;
 use crate::data::environment_function::EnvFunction;
+use crate::data::network_settings::{ProxyConfig, SslVerification};
 use crate::persistence::{Persistence, PersistenceItem};
 
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
@@ -33,7 +34,14 @@ impl Environment {
         &self,
         collection: Option<Collection>,
     ) -> BTreeMap<String, EnvironmentItemValue> {
-        self.status.select.clone().map_or_else(
+        self.get_variable_hash_map_with_select(self.status.select.clone(), collection)
+    }
+    pub(crate) fn get_variable_hash_map_with_select(
+        &self,
+        select: Option<String>,
+        collection: Option<Collection>,
+    ) -> BTreeMap<String, EnvironmentItemValue> {
+        select.map_or_else(
             || {
                 let mut result = BTreeMap::default();
                 self.get(ENVIRONMENT_GLOBALS.to_string()).map(|e| {
@@ -192,6 +200,30 @@ pub struct EnvironmentItemValue {
 #[serde(default)]
 pub struct EnvironmentConfig {
     pub items: Vec<EnvironmentItem>,
+    pub ssl_verification: SslVerification,
+    pub proxy: ProxyConfig,
+}
+
+impl EnvironmentConfig {
+    /// Sets or, when `value` is `None`, removes `key`. A disabled item is enabled again.
+    pub fn apply(&mut self, key: &str, value: Option<String>) {
+        match value {
+            None => self.items.retain(|item| item.key != key),
+            Some(value) => match self.items.iter_mut().find(|item| item.key == key) {
+                Some(item) => {
+                    item.enable = true;
+                    item.value = value;
+                    item.value_type = EnvironmentValueType::String;
+                }
+                None => self.items.push(EnvironmentItem {
+                    enable: true,
+                    key: key.to_string(),
+                    value,
+                    value_type: EnvironmentValueType::String,
+                }),
+            },
+        }
+    }
 }
 
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -202,3 +234,39 @@ pub struct EnvironmentItem {
     pub value: String,
     pub value_type: EnvironmentValueType,
 }
+
+#[derive(Clone, PartialEq, Eq, Debug, Display, EnumString)]
+pub enum VariableScope {
+    Environment,
+    Collection,
+    Globals,
+}
+
+impl Default for VariableScope {
+    fn default() -> Self {
+        VariableScope::Environment
+    }
+}
+
+impl VariableScope {
+    /// Whether a variable built with the given `EnvironmentItemValue::scope` belongs here.
+    pub fn contains(&self, scope: &str) -> bool {
+        let is_globals = scope == ENVIRONMENT_GLOBALS || scope == "Globals";
+        let is_collection = scope == "Collection" || scope.ends_with(" Collection");
+        match self {
+            VariableScope::Globals => is_globals,
+            VariableScope::Collection => is_collection,
+            VariableScope::Environment => {
+                !is_globals && !is_collection && scope != "Global" && scope != "Script"
+            }
+        }
+    }
+}
+
+/// A variable set or unset by a script, saved once the request finishes.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct VariableChange {
+    pub scope: VariableScope,
+    pub key: String,
+    pub value: Option<String>,
+}
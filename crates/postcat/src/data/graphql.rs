@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::data::json::get_str;
+
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      enumValues(includeDeprecated: true) { name description }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } } }
+}"#;
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct GraphQLSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: BTreeMap<String, GraphQLType>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct GraphQLType {
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+    pub fields: Vec<GraphQLField>,
+    pub input_fields: Vec<GraphQLField>,
+    pub enum_values: Vec<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct GraphQLField {
+    pub name: String,
+    pub description: String,
+    pub type_name: String,
+    pub named_type: String,
+    pub args: Vec<GraphQLField>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct GraphQLError {
+    pub line: usize,
+    pub msg: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Name(String),
+    Punct(String),
+    Value,
+}
+
+struct PendingField {
+    name: String,
+    named_type: String,
+    line: usize,
+}
+
+impl GraphQLSchema {
+    pub fn from_introspection(value: &Value) -> Result<Self, String> {
+        if let Some(errors) = value.get("errors").and_then(Value::as_array) {
+            let msgs: Vec<String> = errors
+                .iter()
+                .map(|e| get_str(e, "message").to_string())
+                .collect();
+            return Err(msgs.join("; "));
+        }
+        let schema = value
+            .get("data")
+            .and_then(|d| d.get("__schema"))
+            .ok_or("the response is not an introspection result".to_string())?;
+        let root_name = |key: &str| {
+            schema
+                .get(key)
+                .and_then(|t| t.get("name"))
+                .and_then(Value::as_str)
+                .map(|s| s.to_string())
+        };
+        let mut result = GraphQLSchema {
+            query_type: root_name("queryType"),
+            mutation_type: root_name("mutationType"),
+            subscription_type: root_name("subscriptionType"),
+            types: BTreeMap::new(),
+        };
+        for t in schema
+            .get("types")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+        {
+            let name = get_str(t, "name").to_string();
+            result.types.insert(
+                name.clone(),
+                GraphQLType {
+                    kind: get_str(t, "kind").to_string(),
+                    name,
+                    description: get_str(t, "description").to_string(),
+                    fields: Self::convert_fields(t.get("fields")),
+                    input_fields: Self::convert_fields(t.get("inputFields")),
+                    enum_values: t
+                        .get("enumValues")
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|e| get_str(e, "name").to_string())
+                        .collect(),
+                },
+            );
+        }
+        Ok(result)
+    }
+
+    fn convert_fields(fields: Option<&Value>) -> Vec<GraphQLField> {
+        fields
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|f| {
+                let (type_name, named_type) =
+                    Self::convert_type_ref(f.get("type").unwrap_or(&Value::Null));
+                GraphQLField {
+                    name: get_str(f, "name").to_string(),
+                    description: get_str(f, "description").to_string(),
+                    type_name,
+                    named_type,
+                    args: Self::convert_fields(f.get("args")),
+                }
+            })
+            .collect()
+    }
+
+    fn convert_type_ref(type_ref: &Value) -> (String, String) {
+        let of_type = type_ref.get("ofType").unwrap_or(&Value::Null);
+        match get_str(type_ref, "kind") {
+            "NON_NULL" => {
+                let (type_name, named_type) = Self::convert_type_ref(of_type);
+                (format!("{}!", type_name), named_type)
+            }
+            "LIST" => {
+                let (type_name, named_type) = Self::convert_type_ref(of_type);
+                (format!("[{}]", type_name), named_type)
+            }
+            _ => (
+                get_str(type_ref, "name").to_string(),
+                get_str(type_ref, "name").to_string(),
+            ),
+        }
+    }
+
+    pub fn root_types(&self) -> Vec<String> {
+        vec![
+            self.query_type.clone(),
+            self.mutation_type.clone(),
+            self.subscription_type.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn is_composite(&self, type_name: &str) -> bool {
+        self.types.get(type_name).map_or(false, |t| {
+            t.kind == "OBJECT" || t.kind == "INTERFACE" || t.kind == "UNION"
+        })
+    }
+
+    fn get_field(&self, type_name: &str, field_name: &str) -> Option<GraphQLField> {
+        self.types
+            .get(type_name)
+            .and_then(|t| t.fields.iter().find(|f| f.name == field_name).cloned())
+    }
+
+    pub fn validate(&self, query: &str) -> Vec<GraphQLError> {
+        let mut errors = vec![];
+        self.walk(query, &mut errors);
+        errors
+    }
+
+    pub fn complete(&self, query: &str, cursor: usize) -> (String, Vec<GraphQLField>) {
+        let before: String = query.chars().take(cursor).collect();
+        let prefix: String = before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<Vec<char>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let context: String = before
+            .chars()
+            .take(cursor - prefix.chars().count())
+            .collect();
+        let mut errors = vec![];
+        let current_type = match self.walk(context.as_str(), &mut errors) {
+            None => return (prefix, vec![]),
+            Some(current_type) => current_type,
+        };
+        let fields = self
+            .types
+            .get(current_type.as_str())
+            .map(|t| {
+                t.fields
+                    .iter()
+                    .filter(|f| f.name.starts_with(prefix.as_str()) && f.name != prefix)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        (prefix, fields)
+    }
+
+    fn walk(&self, query: &str, errors: &mut Vec<GraphQLError>) -> Option<String> {
+        let tokens = tokenize(query);
+        let mut stack: Vec<Option<String>> = vec![];
+        let mut pending_type: Option<Option<String>> = None;
+        let mut pending_field: Option<PendingField> = None;
+        let mut paren_depth = 0;
+        let mut index = 0;
+        while index < tokens.len() {
+            let (token, line) = &tokens[index];
+            index += 1;
+            if paren_depth > 0 {
+                match token {
+                    Token::Punct(p) if p == "(" => paren_depth += 1,
+                    Token::Punct(p) if p == ")" => paren_depth -= 1,
+                    _ => {}
+                }
+                continue;
+            }
+            match token {
+                Token::Punct(p) if p == "(" => paren_depth += 1,
+                Token::Punct(p) if p == "{" => {
+                    let next_type = match pending_field.take() {
+                        Some(field) => {
+                            if self.types.contains_key(field.named_type.as_str())
+                                && !self.is_composite(field.named_type.as_str())
+                            {
+                                errors.push(GraphQLError {
+                                    line: field.line,
+                                    msg: format!(
+                                        "Field `{}` of type `{}` must not have a selection",
+                                        field.name, field.named_type
+                                    ),
+                                });
+                            }
+                            Some(field.named_type)
+                        }
+                        None => match pending_type.take() {
+                            Some(t) => t,
+                            None if stack.is_empty() => self.query_type.clone(),
+                            None => None,
+                        },
+                    };
+                    stack.push(next_type);
+                }
+                Token::Punct(p) if p == "}" => {
+                    self.check_pending_field(pending_field.take(), errors);
+                    stack.pop();
+                }
+                Token::Punct(p) if p == "@" => index += 1,
+                Token::Punct(p) if p == "..." => {
+                    self.check_pending_field(pending_field.take(), errors);
+                    match tokens.get(index) {
+                        Some((Token::Name(on), _)) if on == "on" => {
+                            pending_type = Some(self.check_type(tokens.get(index + 1), errors));
+                            index += 2;
+                        }
+                        Some((Token::Name(_), _)) => index += 1,
+                        _ => {}
+                    }
+                }
+                Token::Name(name) if stack.is_empty() => match name.as_str() {
+                    "query" => pending_type = Some(self.query_type.clone()),
+                    "mutation" => pending_type = Some(self.mutation_type.clone()),
+                    "subscription" => pending_type = Some(self.subscription_type.clone()),
+                    "fragment" => {
+                        if let Some((Token::Name(on), _)) = tokens.get(index + 1) {
+                            if on == "on" {
+                                pending_type = Some(self.check_type(tokens.get(index + 2), errors));
+                                index += 3;
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Token::Name(name) => {
+                    self.check_pending_field(pending_field.take(), errors);
+                    let mut field_name = name.clone();
+                    if let Some((Token::Punct(colon), _)) = tokens.get(index) {
+                        if colon == ":" {
+                            if let Some((Token::Name(real_name), _)) = tokens.get(index + 1) {
+                                field_name = real_name.clone();
+                            }
+                            index += 2;
+                        }
+                    }
+                    if field_name.starts_with("__") {
+                        continue;
+                    }
+                    if let Some(Some(parent_type)) = stack.last() {
+                        match self.get_field(parent_type.as_str(), field_name.as_str()) {
+                            Some(field) => {
+                                pending_field = Some(PendingField {
+                                    name: field_name,
+                                    named_type: field.named_type,
+                                    line: *line,
+                                })
+                            }
+                            None => {
+                                if self
+                                    .types
+                                    .get(parent_type.as_str())
+                                    .map_or(false, |t| t.kind == "OBJECT" || t.kind == "INTERFACE")
+                                {
+                                    errors.push(GraphQLError {
+                                        line: *line,
+                                        msg: format!(
+                                            "Cannot query field `{}` on type `{}`",
+                                            field_name, parent_type
+                                        ),
+                                    });
+                                }
+                                pending_field = Some(PendingField {
+                                    name: field_name,
+                                    named_type: "".to_string(),
+                                    line: *line,
+                                });
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        stack.last().cloned().flatten()
+    }
+
+    fn check_pending_field(&self, field: Option<PendingField>, errors: &mut Vec<GraphQLError>) {
+        if let Some(field) = field {
+            if self.is_composite(field.named_type.as_str()) {
+                errors.push(GraphQLError {
+                    line: field.line,
+                    msg: format!(
+                        "Field `{}` of type `{}` must have a selection of subfields",
+                        field.name, field.named_type
+                    ),
+                });
+            }
+        }
+    }
+
+    fn check_type(
+        &self,
+        token: Option<&(Token, usize)>,
+        errors: &mut Vec<GraphQLError>,
+    ) -> Option<String> {
+        match token {
+            Some((Token::Name(type_name), line)) => {
+                if self.types.contains_key(type_name.as_str()) {
+                    Some(type_name.clone())
+                } else {
+                    errors.push(GraphQLError {
+                        line: *line,
+                        msg: format!("Unknown type `{}`", type_name),
+                    });
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Vec<(Token, usize)> {
+    let mut tokens = vec![];
+    let chars: Vec<char> = query.chars().collect();
+    let mut line = 1;
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if c == '\n' {
+            line += 1;
+            index += 1;
+        } else if c.is_whitespace() || c == ',' {
+            index += 1;
+        } else if c == '#' {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+        } else if c == '"' {
+            let block = chars.get(index + 1) == Some(&'"') && chars.get(index + 2) == Some(&'"');
+            index += if block { 3 } else { 1 };
+            while index < chars.len() {
+                if chars[index] == '\n' {
+                    line += 1;
+                }
+                if chars[index] == '\\' {
+                    index += 2;
+                    continue;
+                }
+                if block {
+                    if chars[index] == '"'
+                        && chars.get(index + 1) == Some(&'"')
+                        && chars.get(index + 2) == Some(&'"')
+                    {
+                        index += 3;
+                        break;
+                    }
+                } else if chars[index] == '"' {
+                    index += 1;
+                    break;
+                }
+                index += 1;
+            }
+            tokens.push((Token::Value, line));
+        } else if c == '.'
+            && chars.get(index + 1) == Some(&'.')
+            && chars.get(index + 2) == Some(&'.')
+        {
+            tokens.push((Token::Punct("...".to_string()), line));
+            index += 3;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = index;
+            while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            tokens.push((Token::Name(chars[start..index].iter().collect()), line));
+        } else if c.is_ascii_digit() || c == '-' {
+            while index < chars.len()
+                && (chars[index].is_alphanumeric() || chars[index] == '.' || chars[index] == '-')
+            {
+                index += 1;
+            }
+            tokens.push((Token::Value, line));
+        } else {
+            tokens.push((Token::Punct(c.to_string()), line));
+            index += 1;
+        }
+    }
+    tokens
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcRecord {
+    pub source: GrpcSource,
+    pub proto_file: String,
+    pub service: String,
+    pub method: String,
+    pub message: String,
+}
+
+impl GrpcRecord {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Source:{} ProtoFile:{} Service:{} Method:{} Message:{}",
+            self.source, self.proto_file, self.service, self.method, self.message
+        )
+    }
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GrpcSource {
+    Reflection,
+    ProtoFile,
+}
+
+impl Default for GrpcSource {
+    fn default() -> Self {
+        GrpcSource::Reflection
+    }
+}
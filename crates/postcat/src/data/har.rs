@@ -0,0 +1,857 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::data::auth::{Auth, AuthType};
+use crate::data::collections::{Collection, CollectionFolder};
+use crate::data::http;
+use crate::data::http::{
+    BodyRawType, BodyType, FormEncoding, Header, HttpBody, HttpRecord, LockWith, Method,
+    MultipartData, MultipartDataType, QueryParam,
+};
+use crate::utils;
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: BTreeMap<String, Value>,
+    pub timings: HarTimings,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub params: Vec<HarParam>,
+    pub text: String,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarParam {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HarTimings {
+    pub dns: f64,
+    pub connect: f64,
+    pub ssl: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct HarImport {
+    pub collection: Collection,
+    pub warnings: Vec<String>,
+}
+
+impl Har {
+    pub fn new(exchanges: Vec<(DateTime<Utc>, http::Request, http::Response)>) -> Self {
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: "Postcat".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: exchanges
+                    .iter()
+                    .map(|(started, request, response)| HarEntry::new(started, request, response))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl HarEntry {
+    fn new(started: &DateTime<Utc>, request: &http::Request, response: &http::Response) -> Self {
+        let elapsed_time = response.elapsed_time as f64;
+        HarEntry {
+            started_date_time: started.to_rfc3339(),
+            time: elapsed_time,
+            request: HarRequest::new(request),
+            response: HarResponse::new(response),
+            cache: BTreeMap::new(),
+            timings: match response.timing.total {
+                0 => HarTimings {
+                    dns: -1.0,
+                    connect: -1.0,
+                    ssl: -1.0,
+                    send: 0.0,
+                    wait: elapsed_time,
+                    receive: 0.0,
+                },
+                _ => HarTimings {
+                    dns: response.timing.dns as f64 / 1000.0,
+                    connect: (response.timing.tcp + response.timing.tls) as f64 / 1000.0,
+                    ssl: response.timing.tls as f64 / 1000.0,
+                    send: 0.0,
+                    wait: response.timing.ttfb as f64 / 1000.0,
+                    receive: response.timing.download as f64 / 1000.0,
+                },
+            },
+        }
+    }
+}
+
+impl HarRequest {
+    fn new(request: &http::Request) -> Self {
+        let headers: Vec<HarNameValue> = request
+            .headers
+            .iter()
+            .filter(|h| h.enable)
+            .map(|h| HarNameValue {
+                name: h.key.clone(),
+                value: h.value.clone(),
+            })
+            .collect();
+        let query_string: Vec<HarNameValue> = request
+            .params
+            .iter()
+            .filter(|p| p.enable)
+            .map(|p| HarNameValue {
+                name: p.key.clone(),
+                value: p.value.clone(),
+            })
+            .collect();
+        let mut url = request.base_url.clone();
+        if !query_string.is_empty() {
+            let query: Vec<String> = query_string
+                .iter()
+                .map(|q| {
+                    format!(
+                        "{}={}",
+                        urlencoding::encode(q.name.as_str()),
+                        urlencoding::encode(q.value.as_str())
+                    )
+                })
+                .collect();
+            url = format!("{}?{}", url, query.join("&"));
+        }
+        let mime_type = headers
+            .iter()
+            .find(|h| h.name.to_lowercase() == "content-type")
+            .map(|h| h.value.clone())
+            .unwrap_or_default();
+        let post_data = Self::post_data(&request.body, mime_type);
+        HarRequest {
+            method: request.method.to_string(),
+            url,
+            http_version: "HTTP/1.1".to_string(),
+            cookies: vec![],
+            headers,
+            query_string,
+            body_size: post_data.as_ref().map(|p| p.text.len() as i64).unwrap_or(0),
+            post_data,
+            headers_size: -1,
+        }
+    }
+
+    fn post_data(body: &HttpBody, mime_type: String) -> Option<HarPostData> {
+        match body.body_type {
+            BodyType::NONE => None,
+            BodyType::RAW => Some(HarPostData {
+                mime_type,
+                params: vec![],
+                text: body.body_str.clone(),
+            }),
+            BodyType::GRAPHQL => Some(HarPostData {
+                mime_type,
+                params: vec![],
+                text: body.body_graphql.to_payload(),
+            }),
+            BodyType::X_WWW_FROM_URLENCODED => {
+                let fields: Vec<&MultipartData> =
+                    body.body_xxx_form.iter().filter(|md| md.enable).collect();
+                Some(HarPostData {
+                    mime_type,
+                    params: fields
+                        .iter()
+                        .map(|md| HarParam {
+                            name: md.key.clone(),
+                            value: Some(md.value.clone()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    text: body.to_form_urlencoded(),
+                })
+            }
+            BodyType::FROM_DATA => Some(HarPostData {
+                mime_type,
+                params: body
+                    .body_form_data
+                    .iter()
+                    .filter(|md| md.enable)
+                    .map(|md| match md.data_type {
+                        MultipartDataType::File => HarParam {
+                            name: md.key.clone(),
+                            file_name: Some(md.value.clone()),
+                            ..Default::default()
+                        },
+                        MultipartDataType::Text => HarParam {
+                            name: md.key.clone(),
+                            value: Some(md.value.clone()),
+                            ..Default::default()
+                        },
+                    })
+                    .collect(),
+                text: "".to_string(),
+            }),
+            BodyType::BINARY => Some(HarPostData {
+                mime_type,
+                params: vec![],
+                text: "".to_string(),
+            }),
+        }
+    }
+}
+
+impl HarResponse {
+    fn new(response: &http::Response) -> Self {
+        let headers: Vec<HarNameValue> = response
+            .headers
+            .iter()
+            .map(|h| HarNameValue {
+                name: h.key.clone(),
+                value: h.value.clone(),
+            })
+            .collect();
+        let mime_type = headers
+            .iter()
+            .find(|h| h.name.to_lowercase() == "content-type")
+            .map(|h| h.value.clone())
+            .unwrap_or_default();
+        let redirect_url = headers
+            .iter()
+            .find(|h| h.name.to_lowercase() == "location")
+            .map(|h| h.value.clone())
+            .unwrap_or_default();
+        let (text, encoding) = if response.body_truncated {
+            (None, None)
+        } else {
+            match String::from_utf8(response.body.to_vec()) {
+                Ok(text) => (Some(text), None),
+                Err(_) => (
+                    Some(response.body.base64.clone()),
+                    Some("base64".to_string()),
+                ),
+            }
+        };
+        HarResponse {
+            status: response.status,
+            status_text: response
+                .status_text
+                .trim_start_matches(response.status.to_string().as_str())
+                .trim()
+                .to_string(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: vec![],
+            headers,
+            content: HarContent {
+                size: response.body.size as i64,
+                mime_type,
+                text,
+                encoding,
+                comment: response
+                    .body_truncated
+                    .then(|| "body too large, not stored in history".to_string()),
+            },
+            redirect_url,
+            headers_size: -1,
+            body_size: response.body.size as i64,
+        }
+    }
+}
+
+impl HarImport {
+    pub fn is_match(value: &Value) -> bool {
+        value
+            .get("log")
+            .and_then(|log| log.get("entries"))
+            .and_then(Value::as_array)
+            .is_some()
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let har: Har = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+        let mut import = HarImport::default();
+        let mut name = utils::filter_name(format!("HAR {}", har.log.creator.name).as_str());
+        if name == "HAR_" {
+            name = "HAR".to_string();
+        }
+        let mut folder = CollectionFolder {
+            name,
+            parent_path: ".".to_string(),
+            desc: format!("Imported from HAR {}", har.log.version),
+            auth: Auth {
+                auth_type: AuthType::NoAuth,
+                ..Default::default()
+            },
+            is_root: true,
+            ..Default::default()
+        };
+        let mut host_folders: BTreeMap<String, CollectionFolder> = BTreeMap::new();
+        for (index, entry) in har.log.entries.iter().enumerate() {
+            let method = match Method::from_str(entry.request.method.to_uppercase().as_str()) {
+                Ok(method) => method,
+                Err(_) => {
+                    import.warnings.push(format!(
+                        "entry {}: method `{}` is not supported, skipped",
+                        index + 1,
+                        entry.request.method
+                    ));
+                    continue;
+                }
+            };
+            let mut record = Self::convert_entry(entry, method);
+            record.order = index + 1;
+            let host = entry
+                .request
+                .url
+                .split("://")
+                .nth(1)
+                .unwrap_or(entry.request.url.as_str())
+                .split(|c: char| c == '/' || c == '?' || c == '#')
+                .next()
+                .unwrap_or_default()
+                .replace(|c: char| c == '.' || c == ':' || c == '-', "_");
+            let mut host_name = utils::filter_name(host.as_str());
+            if host_name == "" {
+                host_name = "unknown_host".to_string();
+            }
+            let order = host_folders.len() + 1;
+            let host_folder = host_folders
+                .entry(host_name.clone())
+                .or_insert(CollectionFolder {
+                    name: host_name,
+                    order,
+                    ..Default::default()
+                });
+            record.name =
+                utils::build_copy_name(record.name, host_folder.requests.keys().cloned().collect());
+            host_folder.requests.insert(record.name.clone(), record);
+        }
+        for (host_name, host_folder) in host_folders.into_iter() {
+            folder
+                .folders
+                .insert(host_name, Rc::new(RefCell::new(host_folder)));
+        }
+        import.collection = Collection {
+            envs: Default::default(),
+            folder: Rc::new(RefCell::new(folder)),
+        };
+        Ok(import)
+    }
+
+    fn convert_entry(entry: &HarEntry, method: Method) -> HttpRecord {
+        let har_request = &entry.request;
+        let mut split = har_request.url.splitn(2, '?');
+        let base_url = split.next().unwrap_or_default().to_string();
+        let mut request = http::Request {
+            method: method.clone(),
+            base_url: base_url.clone(),
+            auth: Auth {
+                auth_type: AuthType::InheritAuthFromParent,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for query in har_request.query_string.iter() {
+            request.params.push(QueryParam {
+                key: query.name.clone(),
+                value: query.value.clone(),
+                desc: "".to_string(),
+                lock_with: LockWith::NoLock,
+                enable: true,
+            });
+        }
+        for header in har_request.headers.iter().filter(|h| {
+            !h.name.starts_with(':')
+                && h.name.to_lowercase() != "content-length"
+                && h.name.to_lowercase() != "host"
+        }) {
+            request.headers.push(Header {
+                key: header.name.clone(),
+                value: header.value.clone(),
+                desc: "".to_string(),
+                enable: true,
+                lock_with: LockWith::NoLock,
+            });
+        }
+        if let Some(post_data) = &har_request.post_data {
+            request.body = Self::convert_post_data(post_data);
+            if request.body.body_type == BodyType::FROM_DATA {
+                request.remove_request_content_type();
+            }
+        }
+        let path = base_url
+            .split("://")
+            .nth(1)
+            .unwrap_or(base_url.as_str())
+            .splitn(2, '/')
+            .nth(1)
+            .unwrap_or_default()
+            .to_string();
+        let mut name = utils::filter_name(format!("{} {}", method, path).as_str());
+        if name == "" {
+            name = method.to_string();
+        }
+        HttpRecord {
+            name,
+            desc: format!(
+                "{} {} {}",
+                entry.started_date_time, entry.response.status, entry.response.status_text
+            )
+            .trim()
+            .to_string(),
+            request,
+            ..Default::default()
+        }
+    }
+
+    fn convert_post_data(post_data: &HarPostData) -> HttpBody {
+        let mut body = HttpBody::default();
+        let mime_type = post_data.mime_type.to_lowercase();
+        if mime_type.contains("multipart/form-data") {
+            body.body_type = BodyType::FROM_DATA;
+            for param in post_data.params.iter() {
+                body.body_form_data.push(match &param.file_name {
+                    Some(file_name) => MultipartData {
+                        data_type: MultipartDataType::File,
+                        key: param.name.clone(),
+                        value: file_name.clone(),
+                        desc: "".to_string(),
+                        lock_with: LockWith::NoLock,
+                        enable: true,
+                        encoding: FormEncoding::Encoded,
+                    },
+                    None => MultipartData {
+                        data_type: MultipartDataType::Text,
+                        key: param.name.clone(),
+                        value: param.value.clone().unwrap_or_default(),
+                        desc: "".to_string(),
+                        lock_with: LockWith::NoLock,
+                        enable: true,
+                        encoding: FormEncoding::Encoded,
+                    },
+                });
+            }
+        } else if mime_type.contains("x-www-form-urlencoded") {
+            body.body_type = BodyType::X_WWW_FROM_URLENCODED;
+            let params: Vec<(String, String)> = if !post_data.params.is_empty() {
+                post_data
+                    .params
+                    .iter()
+                    .map(|p| (p.name.clone(), p.value.clone().unwrap_or_default()))
+                    .collect()
+            } else {
+                post_data
+                    .text
+                    .split('&')
+                    .filter(|p| *p != "")
+                    .map(|p| {
+                        let (key, value) = p.split_once('=').unwrap_or((p, ""));
+                        (utils::url_decode(key), utils::url_decode(value))
+                    })
+                    .collect()
+            };
+            for (key, value) in params.into_iter() {
+                body.body_xxx_form.push(MultipartData {
+                    data_type: MultipartDataType::Text,
+                    key,
+                    value,
+                    desc: "".to_string(),
+                    lock_with: LockWith::NoLock,
+                    enable: true,
+                    encoding: FormEncoding::Encoded,
+                });
+            }
+        } else {
+            body.body_type = BodyType::RAW;
+            body.body_str = post_data.text.clone();
+            body.body_raw_type = if mime_type.contains("json") {
+                BodyRawType::JSON
+            } else if mime_type.contains("xml") {
+                BodyRawType::XML
+            } else if mime_type.contains("html") {
+                BodyRawType::HTML
+            } else if mime_type.contains("javascript") {
+                BodyRawType::JavaScript
+            } else {
+                BodyRawType::TEXT
+            };
+        }
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{TimeZone, Utc};
+    use serde_json::Value;
+
+    use crate::data::auth::AuthType;
+    use crate::data::http::{
+        BodyRawType, BodyType, Header, HttpBody, Method, MultipartData, MultipartDataType, Request,
+        Response, Timing,
+    };
+
+    use super::{Har, HarImport, HarTimings};
+
+    fn import() -> HarImport {
+        let value: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/chrome_export.har")).unwrap();
+        assert!(HarImport::is_match(&value));
+        HarImport::from_value(&value).unwrap()
+    }
+
+    fn field(key: &str, value: &str, enable: bool) -> MultipartData {
+        MultipartData {
+            data_type: MultipartDataType::Text,
+            key: key.to_string(),
+            value: value.to_string(),
+            enable,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entries_are_grouped_by_host_in_recorded_order() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        assert_eq!(root.name, "HAR_WebInspector");
+        assert_eq!(root.desc, "Imported from HAR 1.2");
+        assert_eq!(root.auth.auth_type, AuthType::NoAuth);
+        assert!(root.requests.is_empty());
+        assert!(import.collection.envs.items.is_empty());
+        let api = root.folders["api_example_com"].borrow();
+        let upload = root.folders["upload_example_com_8443"].borrow();
+        assert_eq!((api.order, upload.order), (1, 2));
+        let records: Vec<(String, usize)> = api
+            .requests
+            .values()
+            .map(|r| (r.name.clone(), r.order))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                ("GET_users".to_string(), 1),
+                ("POST_graphql".to_string(), 4),
+                ("POST_login".to_string(), 2)
+            ]
+        );
+        assert_eq!(upload.requests["POST_files"].order, 3);
+        assert_eq!(
+            api.requests["GET_users"].desc,
+            "2024-01-01T10:00:00.000Z 200 OK"
+        );
+    }
+
+    #[test]
+    fn requests_keep_query_headers_and_body_modes() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        let api = root.folders["api_example_com"].borrow();
+
+        let users = &api.requests["GET_users"].request;
+        assert_eq!(users.method, Method::GET);
+        assert_eq!(users.base_url, "https://api.example.com/users");
+        assert_eq!(users.auth.auth_type, AuthType::InheritAuthFromParent);
+        assert_eq!(users.params.len(), 1);
+        assert_eq!(
+            (users.params[0].key.as_str(), users.params[0].value.as_str()),
+            ("page", "2")
+        );
+        let headers: Vec<&str> = users.headers.iter().map(|h| h.key.as_str()).collect();
+        assert_eq!(headers, vec!["accept"]);
+
+        let login = &api.requests["POST_login"].request.body;
+        assert_eq!(login.body_type, BodyType::X_WWW_FROM_URLENCODED);
+        let fields: Vec<(&str, &str)> = login
+            .body_xxx_form
+            .iter()
+            .map(|f| (f.key.as_str(), f.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![("user", "alice"), ("pass", "p@ss word"), ("user", "bob")]
+        );
+
+        let graphql = &api.requests["POST_graphql"].request.body;
+        assert_eq!(graphql.body_type, BodyType::RAW);
+        assert_eq!(graphql.body_raw_type, BodyRawType::JSON);
+        assert_eq!(graphql.body_str, "{\"query\":\"{ users { id } }\"}");
+
+        let upload = root.folders["upload_example_com_8443"].borrow();
+        let files = &upload.requests["POST_files"].request;
+        assert!(files.headers.is_empty());
+        assert_eq!(files.body.body_type, BodyType::FROM_DATA);
+        let fields: Vec<(&str, MultipartDataType, &str)> = files
+            .body
+            .body_form_data
+            .iter()
+            .map(|f| (f.key.as_str(), f.data_type.clone(), f.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("title", MultipartDataType::Text, "report"),
+                ("file", MultipartDataType::File, "report.pdf")
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_methods_are_reported() {
+        assert_eq!(
+            import().warnings,
+            vec!["entry 5: method `CONNECT` is not supported, skipped"]
+        );
+    }
+
+    #[test]
+    fn export_writes_requests_and_response_bodies() {
+        let started = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let request = Request {
+            method: Method::POST,
+            base_url: "https://api.example.com/login".to_string(),
+            headers: vec![Header {
+                key: "Content-Type".to_string(),
+                value: "application/x-www-form-urlencoded".to_string(),
+                enable: true,
+                ..Default::default()
+            }],
+            body: HttpBody {
+                body_type: BodyType::X_WWW_FROM_URLENCODED,
+                body_xxx_form: vec![
+                    field("user", "alice", true),
+                    field("debug", "1", false),
+                    field("user", "bob", true),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let text = Response {
+            status: 200,
+            status_text: "200 OK".to_string(),
+            headers: vec![Header {
+                key: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+                enable: true,
+                ..Default::default()
+            }],
+            body: Arc::new(HttpBody::new(b"{\"ok\":true}".to_vec())),
+            ..Default::default()
+        };
+        let binary = Response {
+            status: 200,
+            body: Arc::new(HttpBody::new(vec![0xff, 0x00, 0xfe])),
+            ..Default::default()
+        };
+        let truncated = Response {
+            status: 200,
+            body: Arc::new(HttpBody {
+                size: 2 * 1024 * 1024,
+                ..Default::default()
+            }),
+            body_truncated: true,
+            ..Default::default()
+        };
+        let har = Har::new(vec![
+            (started, request.clone(), text),
+            (started, request.clone(), binary),
+            (started, request, truncated),
+        ]);
+        assert_eq!(har.log.version, "1.2");
+        assert_eq!(har.log.creator.name, "Postcat");
+        let entry = &har.log.entries[0];
+        assert_eq!(entry.started_date_time, "2024-01-01T10:00:00+00:00");
+        let post_data = entry.request.post_data.as_ref().unwrap();
+        assert_eq!(post_data.text, "user=alice&user=bob");
+        assert_eq!(post_data.params.len(), 2);
+        assert_eq!(entry.response.status_text, "OK");
+        assert_eq!(entry.response.content.mime_type, "application/json");
+        assert_eq!(
+            entry.response.content.text.as_deref(),
+            Some("{\"ok\":true}")
+        );
+        let binary = &har.log.entries[1].response.content;
+        assert_eq!(binary.text.as_deref(), Some("/wD+"));
+        assert_eq!(binary.encoding.as_deref(), Some("base64"));
+        let truncated = &har.log.entries[2].response.content;
+        assert_eq!(truncated.size, 2 * 1024 * 1024);
+        assert_eq!(truncated.text, None);
+        assert_eq!(
+            truncated.comment.as_deref(),
+            Some("body too large, not stored in history")
+        );
+        let json = serde_json::to_value(&har).unwrap();
+        assert!(json["log"]["entries"][0]["response"]["content"]
+            .get("comment")
+            .is_none());
+    }
+
+    #[test]
+    fn export_writes_connection_timings() {
+        let started = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let response = |timing: Timing| Response {
+            status: 200,
+            elapsed_time: 42,
+            timing,
+            ..Default::default()
+        };
+        let har = Har::new(vec![
+            (
+                started,
+                Request::default(),
+                response(Timing {
+                    dns: 1500,
+                    tcp: 2000,
+                    tls: 3000,
+                    ttfb: 30000,
+                    download: 5500,
+                    total: 42000,
+                }),
+            ),
+            (
+                started,
+                Request::default(),
+                response(Timing {
+                    ttfb: 40000,
+                    download: 2000,
+                    total: 42000,
+                    ..Default::default()
+                }),
+            ),
+            (started, Request::default(), response(Timing::default())),
+        ]);
+        let timings: Vec<HarTimings> = har
+            .log
+            .entries
+            .iter()
+            .map(|entry| entry.timings.clone())
+            .collect();
+        assert_eq!(
+            timings,
+            vec![
+                HarTimings {
+                    dns: 1.5,
+                    connect: 5.0,
+                    ssl: 3.0,
+                    send: 0.0,
+                    wait: 30.0,
+                    receive: 5.5,
+                },
+                HarTimings {
+                    dns: 0.0,
+                    connect: 0.0,
+                    ssl: 0.0,
+                    send: 0.0,
+                    wait: 40.0,
+                    receive: 2.0,
+                },
+                HarTimings {
+                    dns: -1.0,
+                    connect: -1.0,
+                    ssl: -1.0,
+                    send: 0.0,
+                    wait: 42.0,
+                    receive: 0.0,
+                },
+            ]
+        );
+    }
+}
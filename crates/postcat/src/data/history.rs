@@ -2,14 +2,19 @@ use std::collections::BTreeMap;
 use std::io::Error;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use chrono::{DateTime, Local, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::data::http::HttpRecord;
+use crate::data::http;
+use crate::data::http::{HttpBody, HttpRecord};
 use crate::persistence::{Persistence, PersistenceItem};
 
+/// Response bodies above this size are not written to history.
+const BODY_LIMIT: usize = 512 * 1024;
+
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
 pub struct HistoryDataList {
     persistence: Persistence,
@@ -48,7 +53,11 @@ impl HistoryDataList {
     pub fn get_group(&self) -> &BTreeMap<NaiveDate, DateGroupHistoryList> {
         &self.date_group
     }
-    pub fn record(&mut self, mut rest: HttpRecord) {
+    pub fn record(
+        &mut self,
+        mut rest: HttpRecord,
+        exchange: Option<(http::Request, http::Response)>,
+    ) {
         rest.name = "".to_string();
         rest.desc = "".to_string();
         let today = Local::now().naive_local().date();
@@ -64,6 +73,17 @@ impl HistoryDataList {
             id: Uuid::new_v4().to_string(),
             record_date: Local::now().with_timezone(&Utc),
             rest,
+            request: exchange.as_ref().map(|(request, _)| request.clone()),
+            response: exchange.map(|(_, mut response)| {
+                if response.body.size > BODY_LIMIT {
+                    response.body = Arc::new(HttpBody {
+                        size: response.body.size,
+                        ..Default::default()
+                    });
+                    response.body_truncated = true;
+                }
+                response
+            }),
         };
         self.date_group
             .get_mut(&today)
@@ -82,6 +102,8 @@ pub struct HistoryRestItem {
     pub id: String,
     pub record_date: DateTime<Utc>,
     pub rest: HttpRecord,
+    pub request: Option<http::Request>,
+    pub response: Option<http::Response>,
 }
 
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -6,11 +7,16 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use strum_macros::{Display, EnumIter, EnumString};
+use url::form_urlencoded;
 
 use crate::data::auth::Auth;
 use crate::data::environment::EnvironmentItemValue;
+use crate::data::grpc::GrpcRecord;
 use crate::data::logger::Logger;
+use crate::data::network_settings::RequestSettings;
+use crate::data::websocket::WebSocketRecord;
 
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -23,6 +29,25 @@ pub struct HttpRecord {
     pub status: ResponseStatus,
     pub pre_request_script: String,
     pub test_script: String,
+    pub record_type: RecordType,
+    pub websocket: WebSocketRecord,
+    pub grpc: GrpcRecord,
+    pub settings: RequestSettings,
+    /// Position among the requests and folders of the parent, 0 when never set.
+    pub order: usize,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum RecordType {
+    HTTP,
+    WebSocket,
+    GRPC,
+}
+
+impl Default for RecordType {
+    fn default() -> Self {
+        RecordType::HTTP
+    }
 }
 
 impl HttpRecord {
@@ -38,6 +63,9 @@ impl HttpRecord {
     pub fn error(&mut self) {
         self.status = ResponseStatus::Error;
     }
+    pub fn cancelled(&mut self) {
+        self.status = ResponseStatus::Cancelled;
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -46,6 +74,7 @@ pub enum ResponseStatus {
     Pending,
     Ready,
     Error,
+    Cancelled,
 }
 
 impl Default for ResponseStatus {
@@ -60,6 +89,7 @@ pub struct Request {
     pub method: Method,
     pub base_url: String,
     pub params: Vec<QueryParam>,
+    pub path_variables: Vec<PathVariable>,
     pub headers: Vec<Header>,
     pub body: HttpBody,
     pub auth: Auth,
@@ -79,11 +109,17 @@ impl Request {
             .filter(|h| h.lock_with == LockWith::NoLock)
             .map(|h| h.compute_signature())
             .collect();
+        let path_variables: Vec<String> = self
+            .path_variables
+            .iter()
+            .map(|v| v.compute_signature())
+            .collect();
         format!(
-            "Method:{} BaseUrl:{} Params:[{}] Headers:[{}] Body:{} Auth:{}",
+            "Method:{} BaseUrl:{} Params:[{}] PathVariables:[{}] Headers:[{}] Body:{} Auth:{}",
             self.method,
             self.base_url,
             parmas.join(";"),
+            path_variables.join(";"),
             headers.join(";"),
             self.body.compute_signature(),
             self.auth.compute_signature()
@@ -99,6 +135,79 @@ impl Request {
             .body_xxx_form
             .retain(|s| s.lock_with == LockWith::NoLock);
     }
+    /// The text shown in the url bar: `base_url` followed by the enabled params.
+    pub fn display_url(&self) -> String {
+        let query: Vec<String> = self
+            .params
+            .iter()
+            .filter(|p| p.enable && p.lock_with == LockWith::NoLock)
+            .map(|p| {
+                format!(
+                    "{}={}",
+                    escape_query_delimiters(p.key.as_str(), true),
+                    escape_query_delimiters(p.value.as_str(), false)
+                )
+            })
+            .collect();
+        if query.is_empty() {
+            self.base_url.clone()
+        } else {
+            format!("{}?{}", self.base_url, query.join("&"))
+        }
+    }
+    /// Parses the url bar text back into `base_url` and params. Disabled and
+    /// locked params are not part of the text, so they keep their positions.
+    pub fn set_display_url(&mut self, url: &str) {
+        let url = strip_fragment(url);
+        let (base_url, query) = match url.split_once('?') {
+            None => (url, ""),
+            Some((base_url, query)) => (base_url, query),
+        };
+        self.base_url = base_url.to_string();
+        let old_params = std::mem::take(&mut self.params);
+        let mut old_enabled = old_params
+            .iter()
+            .filter(|p| p.enable && p.lock_with == LockWith::NoLock);
+        for (key, value) in parse_query(query) {
+            let desc = old_enabled
+                .next()
+                .filter(|p| p.key == key)
+                .map(|p| p.desc.clone())
+                .unwrap_or_default();
+            self.params.push(QueryParam {
+                key,
+                value,
+                desc,
+                lock_with: LockWith::NoLock,
+                enable: true,
+            });
+        }
+        for (index, param) in old_params.into_iter().enumerate() {
+            if !param.enable || param.lock_with != LockWith::NoLock {
+                self.params.insert(index.min(self.params.len()), param);
+            }
+        }
+    }
+    /// Keeps one row per `:name` path segment of the url, in url order.
+    pub fn sync_path_variables(&mut self) {
+        let mut path_variables = vec![];
+        for key in path_variable_names(self.base_url.as_str()) {
+            if path_variables.iter().any(|v: &PathVariable| v.key == key) {
+                continue;
+            }
+            path_variables.push(
+                self.path_variables
+                    .iter()
+                    .find(|v| v.key == key)
+                    .cloned()
+                    .unwrap_or(PathVariable {
+                        key,
+                        ..Default::default()
+                    }),
+            );
+        }
+        self.path_variables = path_variables;
+    }
     pub fn remove_request_content_type(&mut self) {
         self.headers
             .retain(|h| h.key.to_lowercase() != "content-type" || h.lock_with != LockWith::NoLock);
@@ -127,9 +236,13 @@ impl Request {
 
 impl HttpRecord {
     pub fn sync(&mut self, envs: BTreeMap<String, EnvironmentItemValue>, parent_auth: Auth) {
+        self.request.sync_path_variables();
+        self.request
+            .auth
+            .build_head(&mut self.request.headers, envs.clone(), parent_auth.clone());
         self.request
             .auth
-            .build_head(&mut self.request.headers, envs.clone(), parent_auth);
+            .build_params(&mut self.request.params, envs.clone(), parent_auth);
         match self.request.body.body_type {
             BodyType::NONE => {}
             BodyType::FROM_DATA => {
@@ -147,6 +260,9 @@ impl HttpRecord {
                     self.set_request_content_type("application/javascript".to_string())
                 }
             },
+            BodyType::GRAPHQL => {
+                self.set_request_content_type("application/json".to_string());
+            }
             BodyType::BINARY => {
                 let path = Path::new(&self.request.body.body_file);
                 let content_type = mime_guess::from_path(path);
@@ -192,6 +308,7 @@ pub enum BodyType {
     FROM_DATA,
     X_WWW_FROM_URLENCODED,
     RAW,
+    GRAPHQL,
     BINARY,
 }
 
@@ -220,6 +337,164 @@ impl QueryParam {
     }
 }
 
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathVariable {
+    pub key: String,
+    pub value: String,
+    pub desc: String,
+}
+
+impl PathVariable {
+    pub fn compute_signature(&self) -> String {
+        format!("Key:{} Value:{} Desc:{}", self.key, self.value, self.desc)
+    }
+}
+
+/// Percent-encodes a query key or value for the wire. Existing `%XX` escapes
+/// and `+` are kept as they are, so pasted urls are sent unchanged.
+pub fn encode_query_component(text: &str, is_key: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut result = String::new();
+    for (index, byte) in bytes.iter().enumerate() {
+        let keep = match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => true,
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b'+' => true,
+            b',' | b';' | b':' | b'@' | b'/' | b'?' => true,
+            b'=' => !is_key,
+            b'%' => {
+                bytes.get(index + 1).map_or(false, u8::is_ascii_hexdigit)
+                    && bytes.get(index + 2).map_or(false, u8::is_ascii_hexdigit)
+            }
+            _ => false,
+        };
+        if keep {
+            result.push(*byte as char);
+        } else {
+            result.push_str(format!("%{:02X}", byte).as_str());
+        }
+    }
+    result
+}
+
+/// Escapes only the characters that would split the query, leaving `{{variables}}` as written.
+fn escape_query_delimiters(text: &str, is_key: bool) -> String {
+    let mut result = String::new();
+    let mut depth = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '{' && chars.peek() == Some(&'{') {
+            depth += 1;
+        } else if c == '}' && chars.peek() == Some(&'}') && depth > 0 {
+            depth -= 1;
+        }
+        match (c, depth) {
+            ('&', 0) => result.push_str("%26"),
+            ('#', 0) => result.push_str("%23"),
+            ('=', 0) if is_key => result.push_str("%3D"),
+            (c, _) => result.push(c),
+        }
+        if (c == '{' || c == '}') && chars.peek() == Some(&c) {
+            result.push(chars.next().unwrap());
+        }
+    }
+    result
+}
+
+/// Drops the `#fragment`, which is never sent, ignoring `#` inside `{{variables}}`.
+fn strip_fragment(url: &str) -> &str {
+    let mut depth = 0;
+    let mut chars = url.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        if c == '{' && next == Some('{') {
+            depth += 1;
+            chars.next();
+        } else if c == '}' && next == Some('}') && depth > 0 {
+            depth -= 1;
+            chars.next();
+        } else if c == '#' && depth == 0 {
+            return &url[..index];
+        }
+    }
+    url
+}
+
+/// Splits a query on `&` and `=`, ignoring delimiters inside `{{variables}}`.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    let mut pairs = vec![];
+    let mut key = String::new();
+    let mut value: Option<String> = None;
+    let mut depth = 0;
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '{' && chars.peek() == Some(&'{') {
+            depth += 1;
+        } else if c == '}' && chars.peek() == Some(&'}') && depth > 0 {
+            depth -= 1;
+        }
+        match (c, depth) {
+            ('&', 0) => {
+                if !key.is_empty() || value.is_some() {
+                    pairs.push((std::mem::take(&mut key), value.take().unwrap_or_default()));
+                }
+            }
+            ('=', 0) if value.is_none() => value = Some(String::new()),
+            (c, _) => match value.as_mut() {
+                None => key.push(c),
+                Some(value) => value.push(c),
+            },
+        }
+        if (c == '{' || c == '}') && chars.peek() == Some(&c) {
+            let next = chars.next().unwrap();
+            match value.as_mut() {
+                None => key.push(next),
+                Some(value) => value.push(next),
+            }
+        }
+    }
+    if !key.is_empty() || value.is_some() {
+        pairs.push((key, value.unwrap_or_default()));
+    }
+    pairs
+}
+
+/// Replaces every `:name` path segment with the value returned by `f`.
+pub fn replace_path_variables(url: &str, f: impl Fn(&str) -> Option<String>) -> String {
+    let (prefix, path, suffix) = split_path(url);
+    let path: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix(':')
+                .filter(|key| !key.is_empty())
+                .and_then(|key| f(key))
+                .unwrap_or(segment.to_string())
+        })
+        .collect();
+    format!("{}{}{}", prefix, path.join("/"), suffix)
+}
+
+pub fn path_variable_names(url: &str) -> Vec<String> {
+    let (_, path, _) = split_path(url);
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix(':'))
+        .filter(|key| !key.is_empty())
+        .map(|key| key.to_string())
+        .collect()
+}
+
+/// Splits a url into the scheme and authority, the path, and the query or fragment.
+fn split_path(url: &str) -> (&str, &str, &str) {
+    let end = url.find(|c| c == '?' || c == '#').unwrap_or(url.len());
+    let authority = url.find("://").map(|i| i + 3).unwrap_or(0);
+    let start = url[authority..end]
+        .find('/')
+        .map(|i| authority + i)
+        .unwrap_or(end);
+    (&url[..start], &url[start..end], &url[end..])
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum LockWith {
     LockWithScript,
@@ -242,17 +517,31 @@ pub struct MultipartData {
     pub desc: String,
     pub lock_with: LockWith,
     pub enable: bool,
+    pub encoding: FormEncoding,
 }
 
 impl MultipartData {
     pub fn compute_signature(&self) -> String {
         format!(
-            "Key:{} Value:{} Desc:{} Enable:{}",
-            self.key, self.value, self.desc, self.enable
+            "Key:{} Value:{} Desc:{} Enable:{} Encoding:{}",
+            self.key, self.value, self.desc, self.enable, self.encoding
         )
     }
 }
 
+/// How an x-www-form-urlencoded field is written: `Raw` sends the text as typed.
+#[derive(Clone, PartialEq, Eq, Debug, Display, EnumIter, EnumString, Serialize, Deserialize)]
+pub enum FormEncoding {
+    Encoded,
+    Raw,
+}
+
+impl Default for FormEncoding {
+    fn default() -> Self {
+        FormEncoding::Encoded
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Display, EnumIter, EnumString, Serialize, Deserialize)]
 pub enum MultipartDataType {
     File,
@@ -305,10 +594,45 @@ pub struct Response {
     pub status: u16,
     pub status_text: String,
     pub elapsed_time: u128,
+    pub redirects: Vec<Redirect>,
+    pub timing: Timing,
+    pub size: TransferSize,
+    /// Set when the body was dropped before saving, `body.size` still holds its length.
+    pub body_truncated: bool,
     #[serde(skip)]
     pub logger: Logger,
 }
 
+/// Phase durations in microseconds.
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timing {
+    pub dns: u128,
+    pub tcp: u128,
+    pub tls: u128,
+    pub ttfb: u128,
+    pub download: u128,
+    pub total: u128,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferSize {
+    pub request_headers: u64,
+    pub request_body: u64,
+    pub response_headers: u64,
+    pub response_body: u64,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Redirect {
+    pub status: u16,
+    pub method: String,
+    pub url: String,
+    pub location: String,
+}
+
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct HttpBody {
@@ -320,9 +644,74 @@ pub struct HttpBody {
     pub body_raw_type: BodyRawType,
     pub body_form_data: Vec<MultipartData>,
     pub body_xxx_form: Vec<MultipartData>,
+    pub body_graphql: GraphQLBody,
 }
 
 impl HttpBody {
+    /// Enabled fields in order, duplicates included, each written per its `FormEncoding`.
+    pub fn to_form_urlencoded(&self) -> String {
+        self.body_xxx_form
+            .iter()
+            .filter(|md| md.enable)
+            .map(|md| match md.encoding {
+                FormEncoding::Encoded => format!(
+                    "{}={}",
+                    form_urlencoded::byte_serialize(md.key.as_bytes()).collect::<String>(),
+                    form_urlencoded::byte_serialize(md.value.as_bytes()).collect::<String>()
+                ),
+                FormEncoding::Raw => format!("{}={}", md.key, md.value),
+            })
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+    /// Enabled fields in order as a `multipart/form-data` payload, files read from disk.
+    /// Built up front so the body has bytes that signers can hash.
+    pub fn to_multipart(&self, boundary: &str) -> Result<Vec<u8>, String> {
+        let quote = |value: &str| {
+            value
+                .replace('"', "%22")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A")
+        };
+        let mut payload: Vec<u8> = vec![];
+        for md in self.body_form_data.iter().filter(|md| md.enable) {
+            payload.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            match md.data_type {
+                MultipartDataType::File => {
+                    let path = Path::new(md.value.as_str());
+                    let content =
+                        fs::read(path).map_err(|e| format!("open `{}` failed: {}", md.value, e))?;
+                    let file_name = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or_default();
+                    payload.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                            quote(md.key.as_str()),
+                            quote(file_name),
+                            mime_guess::from_path(path).first_or_octet_stream()
+                        )
+                        .as_bytes(),
+                    );
+                    payload.extend_from_slice(content.as_slice());
+                }
+                MultipartDataType::Text => {
+                    payload.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}",
+                            quote(md.key.as_str()),
+                            md.value
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+            payload.extend_from_slice(b"\r\n");
+        }
+        payload.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+        Ok(payload)
+    }
     pub fn compute_signature(&self) -> String {
         let body_form_data: Vec<String> = self
             .body_form_data
@@ -337,13 +726,14 @@ impl HttpBody {
             .map(|b| b.compute_signature())
             .collect();
         format!(
-            "BodyStr:{} BodyFile:{} BodyType:{} BodyRawType:{} FormData:[{}] XXXForm:[{}]",
+            "BodyStr:{} BodyFile:{} BodyType:{} BodyRawType:{} FormData:[{}] XXXForm:[{}] GraphQL:{}",
             self.body_str,
             self.body_file,
             self.body_type,
             self.body_raw_type,
             body_form_data.join(";"),
-            body_xxx_form.join(";")
+            body_xxx_form.join(";"),
+            self.body_graphql.compute_signature()
         )
     }
     pub fn to_vec(&self) -> Vec<u8> {
@@ -371,10 +761,47 @@ impl HttpBody {
             body_raw_type: Default::default(),
             body_form_data: vec![],
             body_xxx_form: vec![],
+            body_graphql: Default::default(),
         }
     }
 }
 
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphQLBody {
+    pub query: String,
+    pub variables: String,
+    pub operation_name: String,
+}
+
+impl GraphQLBody {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Query:{} Variables:{} OperationName:{}",
+            self.query, self.variables, self.operation_name
+        )
+    }
+
+    pub fn to_payload(&self) -> String {
+        let variables = if self.variables.trim().is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(self.variables.as_str()).unwrap_or(Value::Null)
+        };
+        let operation_name = if self.operation_name.trim().is_empty() {
+            Value::Null
+        } else {
+            Value::String(self.operation_name.clone())
+        };
+        json!({
+            "query": self.query,
+            "variables": variables,
+            "operationName": operation_name,
+        })
+        .to_string()
+    }
+}
+
 #[derive(Debug, Display, PartialEq, EnumString, EnumIter, Clone, Eq, Serialize, Deserialize)]
 pub enum Method {
     POST,
@@ -399,3 +826,189 @@ impl Default for Method {
         Method::GET
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encode_query_component, HttpBody, LockWith, MultipartData, MultipartDataType, QueryParam,
+        Request,
+    };
+
+    fn param(key: &str, value: &str, enable: bool) -> QueryParam {
+        QueryParam {
+            key: key.to_string(),
+            value: value.to_string(),
+            desc: "".to_string(),
+            lock_with: LockWith::NoLock,
+            enable,
+        }
+    }
+
+    fn pairs(request: &Request) -> Vec<(String, String, bool)> {
+        request
+            .params
+            .iter()
+            .map(|p| (p.key.clone(), p.value.clone(), p.enable))
+            .collect()
+    }
+
+    fn round_trip(url: &str) -> Request {
+        let mut request = Request::default();
+        request.set_display_url(url);
+        assert_eq!(request.display_url(), url);
+        request
+    }
+
+    #[test]
+    fn keeps_duplicate_keys() {
+        let request = round_trip("https://example.com/a?id=1&id=2&id=");
+        assert_eq!(request.base_url, "https://example.com/a");
+        assert_eq!(
+            pairs(&request),
+            vec![
+                ("id".to_string(), "1".to_string(), true),
+                ("id".to_string(), "2".to_string(), true),
+                ("id".to_string(), "".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_param_order() {
+        let request = round_trip("https://example.com?b=2&a=1&c=3");
+        let keys: Vec<String> = request.params.iter().map(|p| p.key.clone()).collect();
+        assert_eq!(keys, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn keeps_disabled_param_positions() {
+        let mut request = Request {
+            base_url: "https://example.com".to_string(),
+            params: vec![
+                param("a", "1", true),
+                param("b", "2", false),
+                param("c", "3", true),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(request.display_url(), "https://example.com?a=1&c=3");
+        request.set_display_url("https://example.com?a=1&c=4&d=5");
+        assert_eq!(
+            pairs(&request),
+            vec![
+                ("a".to_string(), "1".to_string(), true),
+                ("b".to_string(), "2".to_string(), false),
+                ("c".to_string(), "4".to_string(), true),
+                ("d".to_string(), "5".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_existing_escapes() {
+        let request = round_trip("https://example.com?q=a%20b&k%3Dx=%26");
+        assert_eq!(
+            pairs(&request),
+            vec![
+                ("q".to_string(), "a%20b".to_string(), true),
+                ("k%3Dx".to_string(), "%26".to_string(), true),
+            ]
+        );
+        assert_eq!(encode_query_component("a%20b", false), "a%20b");
+        assert_eq!(encode_query_component("a b", false), "a%20b");
+        assert_eq!(encode_query_component("100%", false), "100%25");
+        assert_eq!(encode_query_component("a=b", true), "a%3Db");
+        assert_eq!(encode_query_component("a=b", false), "a=b");
+    }
+
+    #[test]
+    fn keeps_delimiters_inside_variables() {
+        let request = round_trip("{{host}}/a?q={{a&b}}&r={{c=d}}&{{k=v}}=1");
+        assert_eq!(
+            pairs(&request),
+            vec![
+                ("q".to_string(), "{{a&b}}".to_string(), true),
+                ("r".to_string(), "{{c=d}}".to_string(), true),
+                ("{{k=v}}".to_string(), "1".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_delimiters_outside_variables() {
+        let request = Request {
+            base_url: "https://example.com".to_string(),
+            params: vec![param("a=b", "c&d#e", true)],
+            ..Default::default()
+        };
+        assert_eq!(request.display_url(), "https://example.com?a%3Db=c%26d%23e");
+    }
+
+    #[test]
+    fn strips_fragment() {
+        let mut request = Request::default();
+        request.set_display_url("https://example.com/a?x=1&y=2#top");
+        assert_eq!(request.base_url, "https://example.com/a");
+        assert_eq!(
+            pairs(&request),
+            vec![
+                ("x".to_string(), "1".to_string(), true),
+                ("y".to_string(), "2".to_string(), true),
+            ]
+        );
+        request.set_display_url("https://example.com/b#top?x=1");
+        assert_eq!(request.base_url, "https://example.com/b");
+        assert!(request.params.is_empty());
+        request.set_display_url("https://example.com/c?x={{a#b}}#top");
+        assert_eq!(
+            pairs(&request),
+            vec![("x".to_string(), "{{a#b}}".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn multipart_payload_keeps_enabled_fields_in_order() {
+        let path = std::env::temp_dir().join("postcat_multipart_test.txt");
+        std::fs::write(&path, "file content").unwrap();
+        let field =
+            |data_type: MultipartDataType, key: &str, value: &str, enable: bool| MultipartData {
+                data_type,
+                key: key.to_string(),
+                value: value.to_string(),
+                enable,
+                ..Default::default()
+            };
+        let body = HttpBody {
+            body_form_data: vec![
+                field(MultipartDataType::Text, "name", "Rex", true),
+                field(MultipartDataType::Text, "skipped", "x", false),
+                field(
+                    MultipartDataType::File,
+                    "say \"hi\"",
+                    path.to_str().unwrap(),
+                    true,
+                ),
+            ],
+            ..Default::default()
+        };
+        let payload = body.to_multipart("b0undary").unwrap();
+        assert_eq!(
+            String::from_utf8(payload).unwrap(),
+            "--b0undary\r\n\
+             Content-Disposition: form-data; name=\"name\"\r\n\r\n\
+             Rex\r\n\
+             --b0undary\r\n\
+             Content-Disposition: form-data; name=\"say %22hi%22\"; \
+             filename=\"postcat_multipart_test.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\n\
+             file content\r\n\
+             --b0undary--\r\n"
+        );
+        let missing = HttpBody {
+            body_form_data: vec![field(MultipartDataType::File, "f", "/no/such/file", true)],
+            ..Default::default()
+        };
+        assert!(missing.to_multipart("b0undary").is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+use serde_json::Value;
+
+pub(crate) fn get_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+pub(crate) fn get_bool(value: &Value, key: &str) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+pub(crate) fn get_array(value: &Value, key: &str) -> Vec<Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{get_array, get_bool, get_str};
+
+    #[test]
+    fn missing_or_mistyped_values_fall_back_to_defaults() {
+        let value = json!({"name": "pets", "flag": true, "items": [1, 2], "count": 3});
+        assert_eq!(get_str(&value, "name"), "pets");
+        assert_eq!(get_str(&value, "count"), "");
+        assert_eq!(get_str(&value, "missing"), "");
+        assert!(get_bool(&value, "flag"));
+        assert!(!get_bool(&value, "name"));
+        assert!(!get_bool(&value, "missing"));
+        assert_eq!(get_array(&value, "items"), vec![json!(1), json!(2)]);
+        assert!(get_array(&value, "name").is_empty());
+        assert!(get_array(&json!("text"), "items").is_empty());
+    }
+}
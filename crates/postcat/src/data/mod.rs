@@ -14,12 +14,24 @@ pub mod central_request_data;
 pub mod collections;
 pub mod config_data;
 pub mod cookies_manager;
+pub mod curl;
 pub mod environment;
 pub mod environment_function;
 pub mod export;
+pub mod graphql;
+pub mod grpc;
+pub mod har;
 pub mod history;
 pub mod http;
+pub mod json;
 pub mod logger;
+pub mod network_settings;
+pub mod oauth2;
+pub mod openapi;
+pub mod postman;
+pub mod signing;
+pub mod stream;
 pub mod test;
+pub mod websocket;
 pub mod workspace;
 pub mod workspace_data;
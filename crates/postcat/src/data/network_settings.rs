@@ -0,0 +1,399 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Identity, NoProxy, Proxy};
+use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+use url::Url;
+
+use crate::persistence::{Persistence, PersistenceItem};
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct NetworkSettings {
+    persistence: Persistence,
+    data: NetworkConfig,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub disable_ssl_verification: bool,
+    pub client_certificates: Vec<ClientCertificate>,
+    pub ca_certificates: Vec<CaCertificate>,
+    pub proxy: ProxyConfig,
+}
+
+/// The network settings of one environment, for clients built outside `WorkspaceData`.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct ClientConfig {
+    pub network: NetworkConfig,
+    pub proxy: ProxyConfig,
+    pub ssl_verification: SslVerification,
+    pub workspace_dir: PathBuf,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientCertificate {
+    pub enable: bool,
+    pub host: String,
+    pub cert_type: CertificateType,
+    pub cert_file: String,
+    pub key_file: String,
+    pub password: String,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaCertificate {
+    pub enable: bool,
+    pub path: String,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    pub http_proxy: String,
+    pub https_proxy: String,
+    pub socks_proxy: String,
+    pub username: String,
+    pub password: String,
+    pub no_proxy: String,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ProxyMode {
+    Inherit,
+    NoProxy,
+    System,
+    Custom,
+}
+
+impl Default for ProxyMode {
+    fn default() -> Self {
+        ProxyMode::Inherit
+    }
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CertificateType {
+    PEM,
+    PKCS12,
+}
+
+impl Default for CertificateType {
+    fn default() -> Self {
+        CertificateType::PEM
+    }
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SslVerification {
+    Inherit,
+    Enable,
+    Disable,
+}
+
+impl Default for SslVerification {
+    fn default() -> Self {
+        SslVerification::Inherit
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestSettings {
+    pub ssl_verification: SslVerification,
+    pub timeout: u64,
+    pub follow_redirects: bool,
+    pub max_redirects: usize,
+    pub http_version: HttpVersion,
+    pub disable_decompression: bool,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum HttpVersion {
+    Auto,
+    Http1Only,
+    Http2PriorKnowledge,
+}
+
+impl Default for HttpVersion {
+    fn default() -> Self {
+        HttpVersion::Auto
+    }
+}
+
+impl Default for RequestSettings {
+    fn default() -> Self {
+        RequestSettings {
+            ssl_verification: SslVerification::Inherit,
+            timeout: 60000,
+            follow_redirects: true,
+            max_redirects: 10,
+            http_version: HttpVersion::Auto,
+            disable_decompression: false,
+        }
+    }
+}
+
+impl RequestSettings {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "SslVerification:{} Timeout:{} FollowRedirects:{} MaxRedirects:{} HttpVersion:{} DisableDecompression:{}",
+            self.ssl_verification,
+            self.timeout,
+            self.follow_redirects,
+            self.max_redirects,
+            self.http_version,
+            self.disable_decompression
+        )
+    }
+
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        builder = builder.redirect(Policy::none());
+        if self.timeout > 0 {
+            builder = builder.timeout(Duration::from_millis(self.timeout));
+        }
+        builder = match self.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+        if self.disable_decompression {
+            builder = builder.no_gzip().no_brotli().no_deflate();
+        }
+        builder
+    }
+}
+
+impl NetworkSettings {
+    pub fn load_all(&mut self, workspace: String) {
+        self.persistence.set_workspace(workspace);
+        self.data = self
+            .persistence
+            .load(Path::new("settings/network.json").to_path_buf())
+            .unwrap_or_default();
+    }
+
+    pub fn get(&self) -> NetworkConfig {
+        self.data.clone()
+    }
+
+    pub fn set(&mut self, config: NetworkConfig) {
+        self.data = config;
+        self.persistence.save(
+            Path::new("settings").to_path_buf(),
+            "network".to_string(),
+            &self.data,
+        );
+    }
+}
+
+impl NetworkConfig {
+    pub fn verify(&self, layers: Vec<SslVerification>) -> bool {
+        for layer in layers {
+            match layer {
+                SslVerification::Inherit => {}
+                SslVerification::Enable => return true,
+                SslVerification::Disable => return false,
+            }
+        }
+        !self.disable_ssl_verification
+    }
+
+    pub fn get_proxy(&self, env_proxy: &ProxyConfig) -> ProxyConfig {
+        if env_proxy.mode == ProxyMode::Inherit {
+            self.proxy.clone()
+        } else {
+            env_proxy.clone()
+        }
+    }
+
+    pub fn match_client_certificate(&self, url: &str) -> Option<usize> {
+        let url = Url::parse(url)
+            .or_else(|_| Url::parse(format!("https://{}", url).as_str()))
+            .ok()?;
+        let host = url.host_str()?.to_lowercase();
+        let port = url.port_or_known_default();
+        self.client_certificates
+            .iter()
+            .position(|c| c.enable && c.is_match(host.as_str(), port))
+    }
+
+    pub fn apply(
+        &self,
+        mut builder: ClientBuilder,
+        verify: bool,
+        client_certificate: Option<usize>,
+        proxy: &ProxyConfig,
+        workspace_dir: &PathBuf,
+    ) -> Result<ClientBuilder, String> {
+        if let Some(proxies) = proxy.build()? {
+            builder = proxies
+                .into_iter()
+                .fold(builder.no_proxy(), |builder, proxy| builder.proxy(proxy));
+        }
+        if !verify {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        for ca in self.ca_certificates.iter().filter(|ca| ca.enable) {
+            let content = Self::read(ca.path.as_str(), workspace_dir)?;
+            let blocks = Self::pem_blocks(&content);
+            if blocks.is_empty() {
+                builder = builder.add_root_certificate(
+                    Certificate::from_der(content.as_slice())
+                        .map_err(|e| format!("load CA `{}` failed: {}", ca.path, e))?,
+                );
+            }
+            for block in blocks {
+                builder = builder.add_root_certificate(
+                    Certificate::from_pem(block.as_bytes())
+                        .map_err(|e| format!("load CA `{}` failed: {}", ca.path, e))?,
+                );
+            }
+        }
+        if let Some(certificate) = client_certificate.and_then(|i| self.client_certificates.get(i))
+        {
+            builder = builder.identity(certificate.load(workspace_dir)?);
+        }
+        Ok(builder)
+    }
+
+    fn read(path: &str, workspace_dir: &PathBuf) -> Result<Vec<u8>, String> {
+        let mut file = PathBuf::from(path.trim());
+        if file.is_relative() {
+            file = workspace_dir.join(file);
+        }
+        fs::read(file.as_path()).map_err(|e| format!("read `{}` failed: {}", path, e))
+    }
+
+    fn pem_blocks(content: &[u8]) -> Vec<String> {
+        let text = String::from_utf8_lossy(content);
+        let mut blocks = vec![];
+        let mut current: Option<String> = None;
+        for line in text.lines() {
+            let line = line.trim();
+            if line == "-----BEGIN CERTIFICATE-----" {
+                current = Some(String::new());
+            }
+            if let Some(block) = current.as_mut() {
+                block.push_str(line);
+                block.push('\n');
+            }
+            if line == "-----END CERTIFICATE-----" {
+                blocks.extend(current.take());
+            }
+        }
+        blocks
+    }
+}
+
+impl ClientConfig {
+    pub fn build_client(&self, url: &str) -> Result<Client, String> {
+        self.network
+            .apply(
+                ClientBuilder::new(),
+                self.network.verify(vec![self.ssl_verification.clone()]),
+                self.network.match_client_certificate(url),
+                &self.proxy,
+                &self.workspace_dir,
+            )?
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl ProxyConfig {
+    /// `None` keeps the reqwest default, which reads the system proxy variables.
+    pub fn build(&self) -> Result<Option<Vec<Proxy>>, String> {
+        match self.mode {
+            ProxyMode::Inherit | ProxyMode::System => Ok(None),
+            ProxyMode::NoProxy => Ok(Some(vec![])),
+            ProxyMode::Custom => {
+                let mut proxies = vec![];
+                if !self.http_proxy.trim().is_empty() {
+                    proxies.push(Proxy::http(
+                        self.proxy_url(self.http_proxy.as_str(), "http")?,
+                    ));
+                }
+                if !self.https_proxy.trim().is_empty() {
+                    proxies.push(Proxy::https(
+                        self.proxy_url(self.https_proxy.as_str(), "http")?,
+                    ));
+                }
+                if !self.socks_proxy.trim().is_empty() {
+                    proxies.push(Proxy::all(
+                        self.proxy_url(self.socks_proxy.as_str(), "socks5")?,
+                    ));
+                }
+                proxies
+                    .into_iter()
+                    .map(|proxy| {
+                        proxy
+                            .map(|p| p.no_proxy(NoProxy::from_string(self.no_proxy.as_str())))
+                            .map_err(|e| format!("invalid proxy: {}", e))
+                    })
+                    .collect::<Result<Vec<Proxy>, String>>()
+                    .map(Some)
+            }
+        }
+    }
+
+    fn proxy_url(&self, proxy: &str, default_scheme: &str) -> Result<Url, String> {
+        let proxy = proxy.trim();
+        let mut url = if proxy.contains("://") {
+            Url::parse(proxy)
+        } else {
+            Url::parse(format!("{}://{}", default_scheme, proxy).as_str())
+        }
+        .map_err(|e| format!("invalid proxy `{}`: {}", proxy, e))?;
+        if !self.username.is_empty() && url.username().is_empty() {
+            url.set_username(self.username.as_str())
+                .and_then(|_| url.set_password(Some(self.password.as_str())))
+                .map_err(|_| format!("invalid proxy `{}`", proxy))?;
+        }
+        Ok(url)
+    }
+}
+
+impl ClientCertificate {
+    pub fn is_match(&self, host: &str, port: Option<u16>) -> bool {
+        let pattern = self.host.trim().to_lowercase();
+        let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+            Some((h, p)) => match p.parse::<u16>() {
+                Ok(p) => (h.to_string(), Some(p)),
+                Err(_) => (pattern.clone(), None),
+            },
+            None => (pattern.clone(), None),
+        };
+        if pattern_port.is_some() && pattern_port != port {
+            return false;
+        }
+        if pattern_host == "*" {
+            return true;
+        }
+        match pattern_host.strip_prefix("*.") {
+            Some(domain) => host.ends_with(format!(".{}", domain).as_str()),
+            None => host == pattern_host,
+        }
+    }
+
+    fn load(&self, workspace_dir: &PathBuf) -> Result<Identity, String> {
+        let cert = NetworkConfig::read(self.cert_file.as_str(), workspace_dir)?;
+        match self.cert_type {
+            CertificateType::PEM => {
+                let key = NetworkConfig::read(self.key_file.as_str(), workspace_dir)?;
+                Identity::from_pkcs8_pem(cert.as_slice(), key.as_slice())
+            }
+            CertificateType::PKCS12 => {
+                Identity::from_pkcs12_der(cert.as_slice(), self.password.as_str())
+            }
+        }
+        .map_err(|e| format!("load client certificate `{}` failed: {}", self.host, e))
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::data::environment::EnvironmentItemValue;
+use crate::utils;
+
+static TOKEN_CACHE: Mutex<BTreeMap<String, OAuth2Token>> = Mutex::new(BTreeMap::new());
+
+pub const DEFAULT_REDIRECT_URL: &str = "http://127.0.0.1:8765/callback";
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OAuth2 {
+    pub grant_type: OAuth2GrantType,
+    pub access_token_url: String,
+    pub auth_url: String,
+    pub redirect_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: String,
+    pub username: String,
+    pub password: String,
+    pub client_authentication: OAuth2ClientAuthentication,
+    #[serde(skip)]
+    pub cache_key: String,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum OAuth2GrantType {
+    ClientCredentials,
+    Password,
+    AuthorizationCodeWithPKCE,
+}
+
+impl Default for OAuth2GrantType {
+    fn default() -> Self {
+        OAuth2GrantType::ClientCredentials
+    }
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum OAuth2ClientAuthentication {
+    BasicAuthHeader,
+    ClientCredentialsInBody,
+}
+
+impl Default for OAuth2ClientAuthentication {
+    fn default() -> Self {
+        OAuth2ClientAuthentication::BasicAuthHeader
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: String,
+    pub scope: String,
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+impl OAuth2 {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "GrantType:{} AccessTokenUrl:{} AuthUrl:{} RedirectUrl:{} ClientId:{} ClientSecret:{} Scope:{} Username:{} Password:{} ClientAuthentication:{}",
+            self.grant_type,
+            self.access_token_url,
+            self.auth_url,
+            self.redirect_url,
+            self.client_id,
+            self.client_secret,
+            self.scope,
+            self.username,
+            self.password,
+            self.client_authentication
+        )
+    }
+
+    /// Tokens are shared by every request with the same resolved token endpoint, client,
+    /// scope and grant, so the key is derived from those instead of the collection path.
+    pub fn with_cache_key(&self, envs: &BTreeMap<String, EnvironmentItemValue>) -> OAuth2 {
+        let resolve = |value: &String| utils::replace_variable(value.clone(), envs.clone());
+        let mut oauth2 = self.clone();
+        oauth2.cache_key = format!(
+            "{}|{}|{}|{}|{}",
+            self.grant_type,
+            resolve(&self.access_token_url),
+            resolve(&self.client_id),
+            resolve(&self.scope),
+            resolve(&self.username)
+        );
+        oauth2
+    }
+
+    pub fn get_redirect_url(&self) -> String {
+        if self.redirect_url.trim().is_empty() {
+            DEFAULT_REDIRECT_URL.to_string()
+        } else {
+            self.redirect_url.trim().to_string()
+        }
+    }
+
+    pub fn get_token(&self) -> Option<OAuth2Token> {
+        TOKEN_CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(self.token_key().as_str()).cloned())
+    }
+
+    pub fn set_token(&self, token: OAuth2Token) {
+        if let Ok(mut cache) = TOKEN_CACHE.lock() {
+            cache.insert(self.token_key(), token);
+        }
+    }
+
+    pub fn clear_token(&self) {
+        if let Ok(mut cache) = TOKEN_CACHE.lock() {
+            cache.remove(self.token_key().as_str());
+        }
+    }
+
+    fn token_key(&self) -> String {
+        if !self.cache_key.is_empty() {
+            return self.cache_key.clone();
+        }
+        format!(
+            "{}|{}|{}|{}|{}",
+            self.grant_type, self.access_token_url, self.client_id, self.scope, self.username
+        )
+    }
+}
+
+impl OAuth2Token {
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let get = |key: &str| match value.get(key) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => "".to_string(),
+        };
+        if !get("error").is_empty() {
+            return Err(format!("{} {}", get("error"), get("error_description"))
+                .trim()
+                .to_string());
+        }
+        let access_token = get("access_token");
+        if access_token.is_empty() {
+            return Err("access_token is missing in the token response".to_string());
+        }
+        Ok(OAuth2Token {
+            access_token,
+            token_type: get("token_type"),
+            refresh_token: get("refresh_token"),
+            scope: get("scope"),
+            expires_at: get("expires_in")
+                .parse::<i64>()
+                .ok()
+                .map(|seconds| Local::now() + Duration::seconds(seconds)),
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| Local::now() + Duration::seconds(30) >= expires_at)
+            .unwrap_or(false)
+    }
+
+    pub fn build_value(&self) -> String {
+        if self.token_type.is_empty() || self.token_type.eq_ignore_ascii_case("bearer") {
+            format!("Bearer {}", self.access_token)
+        } else {
+            format!("{} {}", self.token_type, self.access_token)
+        }
+    }
+
+    pub fn show(&self) -> String {
+        match self.expires_at {
+            None => "Token does not expire".to_string(),
+            Some(expires_at) => {
+                if self.is_expired() {
+                    format!(
+                        "Token expired at {}",
+                        expires_at.format("%Y-%m-%d %H:%M:%S")
+                    )
+                } else {
+                    format!(
+                        "Token expires at {}",
+                        expires_at.format("%Y-%m-%d %H:%M:%S")
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::data::environment::{EnvironmentItemValue, EnvironmentValueType};
+
+    use super::{OAuth2, OAuth2Token};
+
+    fn token(access_token: &str) -> OAuth2Token {
+        OAuth2Token {
+            access_token: access_token.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tokens_are_shared_by_the_resolved_configuration() {
+        let mut envs = BTreeMap::new();
+        envs.insert(
+            "host".to_string(),
+            EnvironmentItemValue {
+                value: "https://cache-key.example.com".to_string(),
+                scope: "".to_string(),
+                value_type: EnvironmentValueType::String,
+            },
+        );
+        let templated = OAuth2 {
+            access_token_url: "{{host}}/token".to_string(),
+            client_id: "client".to_string(),
+            scope: "read".to_string(),
+            ..Default::default()
+        };
+        let literal = OAuth2 {
+            access_token_url: "https://cache-key.example.com/token".to_string(),
+            ..templated.clone()
+        };
+        templated.with_cache_key(&envs).set_token(token("shared"));
+        assert_eq!(
+            literal.with_cache_key(&envs).get_token(),
+            Some(token("shared"))
+        );
+
+        let other_scope = OAuth2 {
+            scope: "write".to_string(),
+            ..literal.clone()
+        };
+        assert_eq!(other_scope.with_cache_key(&envs).get_token(), None);
+        let other_client = OAuth2 {
+            client_id: "other".to_string(),
+            ..literal.clone()
+        };
+        assert_eq!(other_client.with_cache_key(&envs).get_token(), None);
+        assert_eq!(templated.get_token(), None);
+
+        literal.with_cache_key(&envs).clear_token();
+        assert_eq!(templated.with_cache_key(&envs).get_token(), None);
+    }
+}
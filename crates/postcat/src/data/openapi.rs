@@ -0,0 +1,837 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+use crate::data::auth::{ApiKeyLocation, Auth, AuthType};
+use crate::data::collections::{Collection, CollectionFolder};
+use crate::data::environment::{EnvironmentConfig, EnvironmentItem, EnvironmentValueType};
+use crate::data::http::{
+    BodyRawType, BodyType, FormEncoding, Header, HttpBody, HttpRecord, LockWith, Method,
+    MultipartData, MultipartDataType, QueryParam, Request,
+};
+use crate::data::json::{get_array, get_bool, get_str};
+use crate::utils;
+
+const OPERATION_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+const MAX_EXAMPLE_DEPTH: usize = 8;
+const BASE_URL: &str = "baseUrl";
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct OpenApiImport {
+    pub collection: Collection,
+    pub warnings: Vec<String>,
+}
+
+impl OpenApiImport {
+    pub fn is_match(value: &Value) -> bool {
+        value.get("openapi").and_then(Value::as_str).is_some()
+            || value.get("swagger").and_then(Value::as_str).is_some()
+    }
+
+    pub fn from_value(root: &Value) -> Self {
+        let mut import = OpenApiImport::default();
+        let swagger = root.get("swagger").is_some();
+        let info = root.get("info").cloned().unwrap_or_default();
+        let mut name = utils::filter_name(get_str(&info, "title"));
+        if name == "" {
+            name = "OpenAPI".to_string();
+        }
+        let mut envs = EnvironmentConfig::default();
+        envs.items.push(EnvironmentItem {
+            enable: true,
+            key: BASE_URL.to_string(),
+            value: if swagger {
+                Self::swagger_base_url(root)
+            } else {
+                import.openapi_base_url(root)
+            },
+            value_type: EnvironmentValueType::String,
+        });
+        let mut folder = CollectionFolder {
+            name: name.clone(),
+            parent_path: ".".to_string(),
+            desc: get_str(&info, "description").to_string(),
+            auth: import.convert_security(root),
+            is_root: true,
+            ..Default::default()
+        };
+        let mut tag_folders: BTreeMap<String, CollectionFolder> = BTreeMap::new();
+        for tag in get_array(root, "tags").iter() {
+            let tag_name = utils::filter_name(get_str(tag, "name"));
+            if tag_name != "" && !tag_folders.contains_key(tag_name.as_str()) {
+                let order = tag_folders.len() + 1;
+                tag_folders.insert(
+                    tag_name.clone(),
+                    CollectionFolder {
+                        name: tag_name,
+                        desc: get_str(tag, "description").to_string(),
+                        order,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        let mut path_variables = vec![];
+        let paths = root
+            .get("paths")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        for (path, path_item) in paths.iter() {
+            let path_item = resolve(root, path_item);
+            for method in OPERATION_METHODS.iter() {
+                let operation = match path_item.get(*method) {
+                    None => continue,
+                    Some(operation) => operation,
+                };
+                let record_method = match Method::from_str(method.to_uppercase().as_str()) {
+                    Ok(m) => m,
+                    Err(_) => {
+                        import.warnings.push(format!(
+                            "{} {}: method is not supported, skipped",
+                            method.to_uppercase(),
+                            path
+                        ));
+                        continue;
+                    }
+                };
+                let mut record = import.convert_operation(
+                    root,
+                    path,
+                    &path_item,
+                    operation,
+                    record_method,
+                    &mut path_variables,
+                );
+                let order = tag_folders.len() + 1;
+                let target = match get_array(operation, "tags")
+                    .first()
+                    .and_then(Value::as_str)
+                    .map(utils::filter_name)
+                    .filter(|t| t != "")
+                {
+                    None => &mut folder,
+                    Some(tag) => tag_folders.entry(tag.clone()).or_insert(CollectionFolder {
+                        name: tag,
+                        order,
+                        ..Default::default()
+                    }),
+                };
+                record.name =
+                    utils::build_copy_name(record.name, target.requests.keys().cloned().collect());
+                record.order = target.requests.len() + 1;
+                target.requests.insert(record.name.clone(), record);
+            }
+        }
+        let untagged = folder.requests.len();
+        for (tag_name, mut tag_folder) in tag_folders.into_iter() {
+            if !tag_folder.requests.is_empty() {
+                tag_folder.order += untagged;
+                folder
+                    .folders
+                    .insert(tag_name, Rc::new(RefCell::new(tag_folder)));
+            }
+        }
+        for (key, value) in path_variables.into_iter() {
+            envs.items.push(EnvironmentItem {
+                enable: true,
+                key,
+                value,
+                value_type: EnvironmentValueType::String,
+            });
+        }
+        import.collection = Collection {
+            envs,
+            folder: Rc::new(RefCell::new(folder)),
+        };
+        import
+    }
+
+    fn openapi_base_url(&mut self, root: &Value) -> String {
+        let servers = get_array(root, "servers");
+        if servers.len() > 1 {
+            self.warnings.push(format!(
+                "{} servers found, `{}` uses the first one",
+                servers.len(),
+                BASE_URL
+            ));
+        }
+        match servers.first() {
+            None => "".to_string(),
+            Some(server) => {
+                let mut url = get_str(server, "url").to_string();
+                if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+                    for (key, variable) in variables.iter() {
+                        url = url.replace(
+                            format!("{{{}}}", key).as_str(),
+                            get_str(variable, "default"),
+                        );
+                    }
+                }
+                url.trim_end_matches('/').to_string()
+            }
+        }
+    }
+
+    fn swagger_base_url(root: &Value) -> String {
+        let host = get_str(root, "host");
+        if host == "" {
+            return get_str(root, "basePath").trim_end_matches('/').to_string();
+        }
+        let scheme = get_array(root, "schemes")
+            .first()
+            .and_then(Value::as_str)
+            .unwrap_or("https")
+            .to_string();
+        format!("{}://{}{}", scheme, host, get_str(root, "basePath"))
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    fn convert_security(&mut self, root: &Value) -> Auth {
+        let mut auth = Auth {
+            auth_type: AuthType::NoAuth,
+            ..Default::default()
+        };
+        let scheme_name = get_array(root, "security")
+            .first()
+            .and_then(Value::as_object)
+            .and_then(|s| s.keys().next().cloned());
+        let scheme_name = match scheme_name {
+            None => return auth,
+            Some(scheme_name) => scheme_name,
+        };
+        let scheme = root
+            .get("components")
+            .and_then(|c| c.get("securitySchemes"))
+            .or(root.get("securityDefinitions"))
+            .and_then(|s| s.get(scheme_name.as_str()))
+            .map(|s| resolve(root, s))
+            .unwrap_or_default();
+        match (get_str(&scheme, "type"), get_str(&scheme, "scheme")) {
+            ("http", "bearer") => {
+                auth.auth_type = AuthType::BearerToken;
+                auth.bearer_token = "{{token}}".to_string();
+            }
+            ("http", "basic") | ("basic", _) => {
+                auth.auth_type = AuthType::BasicAuth;
+                auth.basic_username = "{{username}}".to_string();
+                auth.basic_password = "{{password}}".to_string();
+            }
+            ("apiKey", _) => {
+                auth.auth_type = AuthType::ApiKey;
+                auth.api_key.name = get_str(&scheme, "name").to_string();
+                auth.api_key.value = "{{apiKey}}".to_string();
+                auth.api_key.location = match get_str(&scheme, "in") {
+                    "query" => ApiKeyLocation::QueryParam,
+                    "cookie" => ApiKeyLocation::Cookie,
+                    _ => ApiKeyLocation::Header,
+                };
+            }
+            (scheme_type, _) => {
+                self.warnings.push(format!(
+                    "security scheme `{}` of type `{}` is not supported",
+                    scheme_name, scheme_type
+                ));
+            }
+        }
+        auth
+    }
+
+    fn convert_operation(
+        &mut self,
+        root: &Value,
+        path: &str,
+        path_item: &Value,
+        operation: &Value,
+        method: Method,
+        path_variables: &mut Vec<(String, String)>,
+    ) -> HttpRecord {
+        let scope = format!("{} {}", method, path);
+        let mut name = utils::filter_name(get_str(operation, "operationId"));
+        if name == "" {
+            name = utils::filter_name(get_str(operation, "summary"));
+        }
+        if name == "" {
+            name = utils::filter_name(format!("{} {}", method, path).as_str());
+        }
+        let mut desc = get_str(operation, "summary").to_string();
+        let description = get_str(operation, "description");
+        if description != "" {
+            if desc != "" {
+                desc.push_str("\n\n");
+            }
+            desc.push_str(description);
+        }
+        let mut request = Request {
+            method,
+            base_url: format!(
+                "{{{{{}}}}}{}",
+                BASE_URL,
+                path.replace('{', "{{").replace('}', "}}")
+            ),
+            auth: Auth {
+                auth_type: AuthType::InheritAuthFromParent,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut parameters: Vec<Value> = vec![];
+        for parameter in get_array(path_item, "parameters")
+            .iter()
+            .chain(get_array(operation, "parameters").iter())
+        {
+            let parameter = resolve(root, parameter);
+            parameters.retain(|p| {
+                !(get_str(p, "name") == get_str(&parameter, "name")
+                    && get_str(p, "in") == get_str(&parameter, "in"))
+            });
+            parameters.push(parameter);
+        }
+        let mut form_parameters = vec![];
+        for parameter in parameters.iter() {
+            let parameter_name = get_str(parameter, "name").to_string();
+            let schema = parameter
+                .get("schema")
+                .cloned()
+                .unwrap_or(parameter.clone());
+            let example = parameter
+                .get("example")
+                .cloned()
+                .unwrap_or_else(|| Self::example(root, &schema, 0));
+            match get_str(parameter, "in") {
+                "path" => {
+                    if path_variables.iter().all(|(k, _)| k != &parameter_name) {
+                        let value = parameter
+                            .get("example")
+                            .or(schema.get("example"))
+                            .or(schema.get("default"))
+                            .map(value_to_string)
+                            .unwrap_or_default();
+                        path_variables.push((parameter_name, value));
+                    }
+                }
+                "query" => request.params.push(QueryParam {
+                    key: parameter_name,
+                    value: value_to_string(&example),
+                    desc: get_str(parameter, "description").to_string(),
+                    lock_with: LockWith::NoLock,
+                    enable: get_bool(parameter, "required"),
+                }),
+                "header" => request.headers.push(Header {
+                    key: parameter_name,
+                    value: value_to_string(&example),
+                    desc: get_str(parameter, "description").to_string(),
+                    enable: get_bool(parameter, "required"),
+                    lock_with: LockWith::NoLock,
+                }),
+                "body" => {
+                    request.body = Self::json_body(root, &schema);
+                }
+                "formData" => form_parameters.push(parameter.clone()),
+                location => self.warnings.push(format!(
+                    "{}: parameter `{}` in `{}` is not supported",
+                    scope, parameter_name, location
+                )),
+            }
+        }
+        if root.get("swagger").is_some() {
+            if !form_parameters.is_empty() {
+                let consumes = get_array(operation, "consumes");
+                let multipart = consumes
+                    .iter()
+                    .chain(get_array(root, "consumes").iter())
+                    .any(|c| c.as_str() == Some("multipart/form-data"))
+                    || form_parameters.iter().any(|p| get_str(p, "type") == "file");
+                request.body = Self::form_body(
+                    root,
+                    form_parameters
+                        .iter()
+                        .map(|p| {
+                            (
+                                get_str(p, "name").to_string(),
+                                p.clone(),
+                                get_bool(p, "required"),
+                            )
+                        })
+                        .collect(),
+                    multipart,
+                );
+            }
+        } else if let Some(request_body) = operation.get("requestBody") {
+            let request_body = resolve(root, request_body);
+            request.body = self.openapi_body(root, &request_body, scope.as_str());
+        }
+        HttpRecord {
+            name,
+            desc,
+            request,
+            ..Default::default()
+        }
+    }
+
+    fn openapi_body(&mut self, root: &Value, request_body: &Value, scope: &str) -> HttpBody {
+        let content = request_body
+            .get("content")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let content_type = content
+            .keys()
+            .find(|k| k.contains("json"))
+            .or(content.keys().next())
+            .cloned()
+            .unwrap_or_default();
+        let media = content
+            .get(content_type.as_str())
+            .cloned()
+            .unwrap_or_default();
+        let schema = media.get("schema").cloned().unwrap_or_default();
+        if content_type.contains("json") {
+            let mut body = Self::json_body(root, &schema);
+            if let Some(example) = media.get("example") {
+                body.body_str = serde_json::to_string_pretty(example).unwrap_or_default();
+            }
+            body
+        } else if content_type == "application/x-www-form-urlencoded"
+            || content_type == "multipart/form-data"
+        {
+            let schema = resolve(root, &schema);
+            let required: HashSet<String> = get_array(&schema, "required")
+                .iter()
+                .filter_map(|r| r.as_str().map(|s| s.to_string()))
+                .collect();
+            let fields = schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| (k.clone(), v, required.contains(k.as_str())))
+                .collect();
+            Self::form_body(root, fields, content_type == "multipart/form-data")
+        } else if content_type.starts_with("text/") || content_type.contains("xml") {
+            let mut body = HttpBody {
+                body_type: BodyType::RAW,
+                body_raw_type: BodyRawType::TEXT,
+                ..Default::default()
+            };
+            if content_type.contains("xml") {
+                body.body_raw_type = BodyRawType::XML;
+            } else if content_type.contains("html") {
+                body.body_raw_type = BodyRawType::HTML;
+            }
+            body.body_str = media
+                .get("example")
+                .map(value_to_string)
+                .unwrap_or_default();
+            body
+        } else if content_type != "" {
+            self.warnings.push(format!(
+                "{}: request body `{}` is imported as binary without a file",
+                scope, content_type
+            ));
+            HttpBody {
+                body_type: BodyType::BINARY,
+                ..Default::default()
+            }
+        } else {
+            HttpBody::default()
+        }
+    }
+
+    fn json_body(root: &Value, schema: &Value) -> HttpBody {
+        HttpBody {
+            body_type: BodyType::RAW,
+            body_raw_type: BodyRawType::JSON,
+            body_str: serde_json::to_string_pretty(&Self::example(root, schema, 0))
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn form_body(root: &Value, fields: Vec<(String, Value, bool)>, multipart: bool) -> HttpBody {
+        let mut body = HttpBody::default();
+        for (key, field, required) in fields.iter() {
+            let field = resolve(root, field);
+            let file = get_str(&field, "type") == "file" || get_str(&field, "format") == "binary";
+            let data = MultipartData {
+                data_type: if file {
+                    MultipartDataType::File
+                } else {
+                    MultipartDataType::Text
+                },
+                key: key.clone(),
+                value: if file {
+                    "".to_string()
+                } else {
+                    value_to_string(&Self::example(root, &field, 0))
+                },
+                desc: get_str(&field, "description").to_string(),
+                lock_with: LockWith::NoLock,
+                enable: *required,
+                encoding: FormEncoding::Encoded,
+            };
+            if multipart {
+                body.body_form_data.push(data);
+            } else {
+                body.body_xxx_form.push(data);
+            }
+        }
+        body.body_type = if multipart {
+            BodyType::FROM_DATA
+        } else {
+            BodyType::X_WWW_FROM_URLENCODED
+        };
+        body
+    }
+
+    fn example(root: &Value, schema: &Value, depth: usize) -> Value {
+        if depth > MAX_EXAMPLE_DEPTH {
+            return Value::Null;
+        }
+        let schema = resolve(root, schema);
+        if let Some(example) = schema.get("example") {
+            return example.clone();
+        }
+        if let Some(default) = schema.get("default") {
+            return default.clone();
+        }
+        if let Some(first) = get_array(&schema, "enum").first() {
+            return first.clone();
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut result = Map::new();
+            for sub_schema in all_of.iter() {
+                if let Value::Object(map) = Self::example(root, sub_schema, depth + 1) {
+                    result.extend(map);
+                }
+            }
+            return Value::Object(result);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = get_array(&schema, key).first() {
+                return Self::example(root, first, depth + 1);
+            }
+        }
+        let schema_type = match schema.get("type") {
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null")
+                .unwrap_or_default()
+                .to_string(),
+            Some(Value::String(t)) => t.clone(),
+            _ => {
+                if schema.get("properties").is_some() {
+                    "object".to_string()
+                } else if schema.get("items").is_some() {
+                    "array".to_string()
+                } else {
+                    "".to_string()
+                }
+            }
+        };
+        match schema_type.as_str() {
+            "object" => {
+                let mut result = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (key, property) in properties.iter() {
+                        result.insert(key.clone(), Self::example(root, property, depth + 1));
+                    }
+                }
+                Value::Object(result)
+            }
+            "array" => {
+                let items = schema.get("items").cloned().unwrap_or_default();
+                Value::Array(vec![Self::example(root, &items, depth + 1)])
+            }
+            "integer" => Value::from(0),
+            "number" => Value::from(0.0),
+            "boolean" => Value::Bool(true),
+            "string" => Value::String(
+                match get_str(&schema, "format") {
+                    "date-time" => "1970-01-01T00:00:00Z",
+                    "date" => "1970-01-01",
+                    "uuid" => "00000000-0000-0000-0000-000000000000",
+                    "email" => "user@example.com",
+                    "uri" | "url" => "https://example.com",
+                    _ => "string",
+                }
+                .to_string(),
+            ),
+            _ => Value::Null,
+        }
+    }
+}
+
+fn resolve(root: &Value, value: &Value) -> Value {
+    let mut current = value.clone();
+    for _ in 0..MAX_EXAMPLE_DEPTH {
+        match get_str(&current, "$ref").strip_prefix('#') {
+            None => break,
+            Some(pointer) => match root.pointer(pointer) {
+                None => return Value::Null,
+                Some(target) => current = target.clone(),
+            },
+        }
+    }
+    current
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::data::auth::{ApiKeyLocation, AuthType};
+    use crate::data::http::{BodyRawType, BodyType, Method, MultipartDataType};
+
+    use super::OpenApiImport;
+
+    fn import(fixture: &str) -> OpenApiImport {
+        let value: Value = serde_json::from_str(fixture).unwrap();
+        assert!(OpenApiImport::is_match(&value));
+        OpenApiImport::from_value(&value)
+    }
+
+    fn openapi() -> OpenApiImport {
+        import(include_str!("../../tests/fixtures/openapi_petstore.json"))
+    }
+
+    fn swagger() -> OpenApiImport {
+        import(include_str!("../../tests/fixtures/swagger_petstore.json"))
+    }
+
+    fn variables(import: &OpenApiImport) -> Vec<(String, String)> {
+        import
+            .collection
+            .envs
+            .items
+            .iter()
+            .map(|i| (i.key.clone(), i.value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn operations_are_grouped_by_tag_in_order() {
+        let import = openapi();
+        let root = import.collection.folder.borrow();
+        assert_eq!(root.name, "Pet_Store_API");
+        assert_eq!(root.desc, "Pets and orders");
+        assert_eq!(root.requests.len(), 1);
+        assert_eq!(root.requests["Health_check"].order, 1);
+        let folders: Vec<(String, usize)> = root
+            .folders
+            .values()
+            .map(|f| (f.borrow().name.clone(), f.borrow().order))
+            .collect();
+        assert_eq!(
+            folders,
+            vec![
+                ("admin".to_string(), 4),
+                ("pets".to_string(), 2),
+                ("store".to_string(), 3)
+            ]
+        );
+        let pets = root.folders["pets"].borrow();
+        assert_eq!(pets.desc, "Pet operations");
+        let requests: Vec<(String, Method, usize)> =
+            ["listPets", "createPet", "showPetById", "uploadPhoto"]
+                .iter()
+                .map(|name| {
+                    let record = &pets.requests[*name];
+                    (
+                        record.name.clone(),
+                        record.request.method.clone(),
+                        record.order,
+                    )
+                })
+                .collect();
+        assert_eq!(
+            requests,
+            vec![
+                ("listPets".to_string(), Method::GET, 1),
+                ("createPet".to_string(), Method::POST, 2),
+                ("showPetById".to_string(), Method::GET, 3),
+                ("uploadPhoto".to_string(), Method::PUT, 4)
+            ]
+        );
+        assert_eq!(
+            root.folders["admin"].borrow().requests["deletePet"]
+                .request
+                .method,
+            Method::DELETE
+        );
+    }
+
+    #[test]
+    fn security_is_mapped_to_collection_auth() {
+        let openapi = openapi();
+        let root = openapi.collection.folder.borrow();
+        assert_eq!(root.auth.auth_type, AuthType::BearerToken);
+        assert_eq!(root.auth.bearer_token, "{{token}}");
+        let pets = root.folders["pets"].borrow();
+        assert_eq!(
+            pets.requests["listPets"].request.auth.auth_type,
+            AuthType::InheritAuthFromParent
+        );
+
+        let swagger = swagger();
+        let root = swagger.collection.folder.borrow();
+        assert_eq!(root.auth.auth_type, AuthType::ApiKey);
+        assert_eq!(root.auth.api_key.name, "api_key");
+        assert_eq!(root.auth.api_key.value, "{{apiKey}}");
+        assert_eq!(root.auth.api_key.location, ApiKeyLocation::QueryParam);
+    }
+
+    #[test]
+    fn parameters_and_bodies_are_converted() {
+        let import = openapi();
+        let root = import.collection.folder.borrow();
+        let pets = root.folders["pets"].borrow();
+        let list = &pets.requests["listPets"];
+        assert_eq!(list.desc, "List pets\n\nReturns every pet");
+        assert_eq!(list.request.base_url, "{{baseUrl}}/pets");
+        assert_eq!(list.request.params.len(), 1);
+        assert_eq!(list.request.params[0].key, "limit");
+        assert_eq!(list.request.params[0].value, "0");
+        assert!(!list.request.params[0].enable);
+        assert_eq!(list.request.headers.len(), 1);
+        assert_eq!(list.request.headers[0].key, "X-Request-Id");
+        assert_eq!(list.request.headers[0].value, "abc");
+        assert!(list.request.headers[0].enable);
+
+        let create = &pets.requests["createPet"].request.body;
+        assert_eq!(create.body_type, BodyType::RAW);
+        assert_eq!(create.body_raw_type, BodyRawType::JSON);
+        assert_eq!(
+            serde_json::from_str::<Value>(create.body_str.as_str()).unwrap(),
+            json!({"id": 0, "name": "Rex", "tag": "string"})
+        );
+
+        assert_eq!(
+            pets.requests["showPetById"].request.base_url,
+            "{{baseUrl}}/pets/{{petId}}"
+        );
+
+        let upload = &pets.requests["uploadPhoto"].request.body;
+        assert_eq!(upload.body_type, BodyType::FROM_DATA);
+        let fields: Vec<(String, MultipartDataType, String, bool)> = upload
+            .body_form_data
+            .iter()
+            .map(|f| {
+                (
+                    f.key.clone(),
+                    f.data_type.clone(),
+                    f.value.clone(),
+                    f.enable,
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "name".to_string(),
+                    MultipartDataType::Text,
+                    "string".to_string(),
+                    false
+                ),
+                (
+                    "photo".to_string(),
+                    MultipartDataType::File,
+                    "".to_string(),
+                    true
+                )
+            ]
+        );
+
+        let store = root.folders["store"].borrow();
+        assert_eq!(
+            store.requests["placeOrder"].request.body.body_type,
+            BodyType::BINARY
+        );
+    }
+
+    #[test]
+    fn swagger_form_and_body_parameters_are_converted() {
+        let import = swagger();
+        let root = import.collection.folder.borrow();
+        assert_eq!(root.name, "Swagger_Petstore");
+        let pet = root.folders["pet"].borrow();
+        let update = &pet.requests["updatePetWithForm"].request;
+        assert_eq!(update.base_url, "{{baseUrl}}/pet/{{petId}}");
+        assert_eq!(update.body.body_type, BodyType::X_WWW_FROM_URLENCODED);
+        let fields: Vec<(String, String, bool)> = update
+            .body
+            .body_xxx_form
+            .iter()
+            .map(|f| (f.key.clone(), f.value.clone(), f.enable))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("name".to_string(), "Rex".to_string(), true),
+                ("status".to_string(), "available".to_string(), false)
+            ]
+        );
+        let user = root.folders["user"].borrow();
+        let create = &user.requests["createUser"].request.body;
+        assert_eq!(create.body_raw_type, BodyRawType::JSON);
+        assert_eq!(
+            serde_json::from_str::<Value>(create.body_str.as_str()).unwrap(),
+            json!({"email": "user@example.com", "id": 0})
+        );
+    }
+
+    #[test]
+    fn servers_and_path_parameters_become_variables() {
+        assert_eq!(
+            variables(&openapi()),
+            vec![
+                (
+                    "baseUrl".to_string(),
+                    "https://api.example.com/v1".to_string()
+                ),
+                ("petId".to_string(), "42".to_string())
+            ]
+        );
+        assert_eq!(
+            variables(&swagger()),
+            vec![
+                (
+                    "baseUrl".to_string(),
+                    "https://petstore.swagger.io/v2".to_string()
+                ),
+                ("petId".to_string(), "1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_parts_are_reported() {
+        assert_eq!(
+            openapi().warnings,
+            vec![
+                "2 servers found, `baseUrl` uses the first one",
+                "TRACE /health: method is not supported, skipped",
+                "DELETE /pets/{petId}: parameter `session` in `cookie` is not supported",
+                "POST /store/order: request body `application/octet-stream` is imported as binary without a file",
+            ]
+        );
+        assert!(swagger().warnings.is_empty());
+    }
+}
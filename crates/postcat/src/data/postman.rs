@@ -0,0 +1,690 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::data::auth::{ApiKeyLocation, Auth, AuthType};
+use crate::data::collections::{Collection, CollectionFolder};
+use crate::data::environment::{EnvironmentConfig, EnvironmentItem, EnvironmentValueType};
+use crate::data::http::{
+    BodyRawType, BodyType, FormEncoding, GraphQLBody, Header, HttpBody, HttpRecord, LockWith,
+    Method, MultipartData, MultipartDataType, QueryParam, Request,
+};
+use crate::data::json::{get_array, get_bool, get_str};
+use crate::data::oauth2::{OAuth2, OAuth2ClientAuthentication, OAuth2GrantType};
+use crate::utils;
+
+/// Script APIs provided by the `pm` and `postman` objects in the script runtime.
+const SUPPORTED_SCRIPT_APIS: [&str; 16] = [
+    "pm.request",
+    "pm.response",
+    "pm.environment",
+    "pm.variables",
+    "pm.collectionVariables",
+    "pm.globals",
+    "pm.test",
+    "pm.expect",
+    "pm.sendRequest",
+    "pm.cookies",
+    "postman.setEnvironmentVariable",
+    "postman.getEnvironmentVariable",
+    "postman.clearEnvironmentVariable",
+    "postman.setGlobalVariable",
+    "postman.getGlobalVariable",
+    "postman.clearGlobalVariable",
+];
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct PostmanImport {
+    pub collection: Collection,
+    pub warnings: Vec<String>,
+}
+
+impl PostmanImport {
+    pub fn is_match(value: &Value) -> bool {
+        value
+            .get("info")
+            .and_then(|info| info.get("schema"))
+            .and_then(Value::as_str)
+            .map(|schema| schema.contains("schema.getpostman.com"))
+            .unwrap_or(false)
+    }
+
+    pub fn from_value(value: &Value) -> Self {
+        let mut import = PostmanImport::default();
+        let info = value.get("info").cloned().unwrap_or_default();
+        let mut name = utils::filter_name(get_str(&info, "name"));
+        if name == "" {
+            name = "Postman".to_string();
+        }
+        let mut folder = CollectionFolder {
+            name: name.clone(),
+            parent_path: ".".to_string(),
+            desc: get_description(&info),
+            auth: Auth {
+                auth_type: AuthType::NoAuth,
+                ..Default::default()
+            },
+            is_root: true,
+            ..Default::default()
+        };
+        if let Some(auth) = value.get("auth") {
+            folder.auth = import.convert_auth(auth, name.as_str());
+        }
+        import.convert_events(value, &mut folder, name.as_str());
+        import.convert_items(value, &mut folder, name.clone());
+        import.collection = Collection {
+            envs: import.convert_variables(value),
+            folder: Rc::new(RefCell::new(folder)),
+        };
+        import
+    }
+
+    fn convert_items(&mut self, value: &Value, folder: &mut CollectionFolder, path: String) {
+        for (index, item) in get_array(value, "item").iter().enumerate() {
+            let mut name = utils::filter_name(get_str(item, "name"));
+            if item.get("item").is_some() {
+                if name == "" {
+                    name = "folder".to_string();
+                }
+                name = utils::build_copy_name(name, folder.folders.keys().cloned().collect());
+                let item_path = format!("{}/{}", path, name);
+                let mut child_folder = CollectionFolder {
+                    name: name.clone(),
+                    desc: get_description(item),
+                    order: index + 1,
+                    ..Default::default()
+                };
+                if let Some(auth) = item.get("auth") {
+                    child_folder.auth = self.convert_auth(auth, item_path.as_str());
+                }
+                self.convert_events(item, &mut child_folder, item_path.as_str());
+                self.convert_items(item, &mut child_folder, item_path);
+                folder
+                    .folders
+                    .insert(name, Rc::new(RefCell::new(child_folder)));
+            } else if let Some(request) = item.get("request") {
+                if name == "" {
+                    name = "request".to_string();
+                }
+                name = utils::build_copy_name(name, folder.requests.keys().cloned().collect());
+                let item_path = format!("{}/{}", path, name);
+                let mut record = HttpRecord {
+                    name: name.clone(),
+                    desc: get_description(request),
+                    request: self.convert_request(request, item_path.as_str()),
+                    order: index + 1,
+                    ..Default::default()
+                };
+                for event in get_array(item, "event").iter() {
+                    let script = self.convert_script(event, item_path.as_str());
+                    match get_str(event, "listen") {
+                        "prerequest" => record.pre_request_script = script,
+                        "test" => record.test_script = script,
+                        _ => {}
+                    }
+                }
+                folder.requests.insert(name, record);
+            }
+        }
+    }
+
+    fn convert_request(&mut self, request: &Value, path: &str) -> Request {
+        if let Some(url) = request.as_str() {
+            return Request {
+                base_url: url.to_string(),
+                ..Default::default()
+            };
+        }
+        let mut result = Request::default();
+        let method = get_str(request, "method").to_uppercase();
+        if method != "" {
+            match Method::from_str(method.as_str()) {
+                Ok(m) => result.method = m,
+                Err(_) => self.warnings.push(format!(
+                    "{}: method `{}` is not supported, using GET",
+                    path, method
+                )),
+            }
+        }
+        self.convert_url(request.get("url"), &mut result);
+        for header in get_array(request, "header").iter() {
+            result.headers.push(Header {
+                key: get_str(header, "key").to_string(),
+                value: get_str(header, "value").to_string(),
+                desc: get_description(header),
+                enable: !get_bool(header, "disabled"),
+                lock_with: LockWith::NoLock,
+            });
+        }
+        if let Some(body) = request.get("body") {
+            result.body = self.convert_body(body, path);
+        }
+        if let Some(auth) = request.get("auth") {
+            result.auth = self.convert_auth(auth, path);
+        }
+        result
+    }
+
+    fn convert_url(&mut self, url: Option<&Value>, request: &mut Request) {
+        let url = match url {
+            None => return,
+            Some(url) => url,
+        };
+        let raw = match url.as_str() {
+            Some(raw) => raw.to_string(),
+            None => {
+                let raw = get_str(url, "raw").to_string();
+                if raw != "" {
+                    raw
+                } else {
+                    build_raw_url(url)
+                }
+            }
+        };
+        let mut split = raw.splitn(2, '?');
+        request.base_url = split.next().unwrap_or_default().to_string();
+        let query = get_array(url, "query");
+        if !query.is_empty() {
+            for q in query.iter() {
+                request.params.push(QueryParam {
+                    key: get_str(q, "key").to_string(),
+                    value: get_str(q, "value").to_string(),
+                    desc: get_description(q),
+                    lock_with: LockWith::NoLock,
+                    enable: !get_bool(q, "disabled"),
+                });
+            }
+        } else if let Some(query_str) = split.next() {
+            for pair in query_str.split('&').filter(|p| *p != "") {
+                let mut kv = pair.splitn(2, '=');
+                request.params.push(QueryParam {
+                    key: kv.next().unwrap_or_default().to_string(),
+                    value: kv.next().unwrap_or_default().to_string(),
+                    desc: "".to_string(),
+                    lock_with: LockWith::NoLock,
+                    enable: true,
+                });
+            }
+        }
+        request.sync_path_variables();
+        for variable in get_array(url, "variable").iter() {
+            let key = get_str(variable, "key");
+            if let Some(path_variable) = request.path_variables.iter_mut().find(|v| v.key == key) {
+                path_variable.value = match variable.get("value") {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Null) | None => "".to_string(),
+                    Some(v) => v.to_string(),
+                };
+                path_variable.desc = get_description(variable);
+            }
+        }
+    }
+
+    fn convert_body(&mut self, body: &Value, path: &str) -> HttpBody {
+        let mut result = HttpBody::default();
+        match get_str(body, "mode") {
+            "raw" => {
+                result.body_type = BodyType::RAW;
+                result.body_str = get_str(body, "raw").to_string();
+                let language = body
+                    .get("options")
+                    .and_then(|o| o.get("raw"))
+                    .map(|r| get_str(r, "language"))
+                    .unwrap_or("");
+                result.body_raw_type = match language {
+                    "json" => BodyRawType::JSON,
+                    "xml" => BodyRawType::XML,
+                    "html" => BodyRawType::HTML,
+                    "javascript" => BodyRawType::JavaScript,
+                    "text" => BodyRawType::TEXT,
+                    _ => {
+                        if serde_json::from_str::<Value>(result.body_str.as_str()).is_ok() {
+                            BodyRawType::JSON
+                        } else {
+                            BodyRawType::TEXT
+                        }
+                    }
+                };
+            }
+            "urlencoded" => {
+                result.body_type = BodyType::X_WWW_FROM_URLENCODED;
+                for field in get_array(body, "urlencoded").iter() {
+                    result.body_xxx_form.push(MultipartData {
+                        data_type: MultipartDataType::Text,
+                        key: get_str(field, "key").to_string(),
+                        value: get_str(field, "value").to_string(),
+                        desc: get_description(field),
+                        lock_with: LockWith::NoLock,
+                        enable: !get_bool(field, "disabled"),
+                        encoding: FormEncoding::Encoded,
+                    });
+                }
+            }
+            "formdata" => {
+                result.body_type = BodyType::FROM_DATA;
+                for field in get_array(body, "formdata").iter() {
+                    let mut data = MultipartData {
+                        data_type: MultipartDataType::Text,
+                        key: get_str(field, "key").to_string(),
+                        value: get_str(field, "value").to_string(),
+                        desc: get_description(field),
+                        lock_with: LockWith::NoLock,
+                        enable: !get_bool(field, "disabled"),
+                        encoding: FormEncoding::Encoded,
+                    };
+                    if get_str(field, "type") == "file" {
+                        data.data_type = MultipartDataType::File;
+                        data.value = match field.get("src") {
+                            Some(Value::Array(src)) => src
+                                .first()
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string(),
+                            Some(Value::String(src)) => src.clone(),
+                            _ => "".to_string(),
+                        };
+                    }
+                    result.body_form_data.push(data);
+                }
+            }
+            "file" => {
+                result.body_type = BodyType::BINARY;
+                result.body_file = body
+                    .get("file")
+                    .map(|f| get_str(f, "src"))
+                    .unwrap_or_default()
+                    .to_string();
+            }
+            "graphql" => {
+                let graphql = body.get("graphql").cloned().unwrap_or_default();
+                result.body_type = BodyType::GRAPHQL;
+                result.body_graphql = GraphQLBody {
+                    query: get_str(&graphql, "query").to_string(),
+                    variables: get_str(&graphql, "variables").to_string(),
+                    operation_name: "".to_string(),
+                };
+            }
+            "" => {}
+            mode => {
+                self.warnings
+                    .push(format!("{}: body mode `{}` is not supported", path, mode));
+            }
+        }
+        result
+    }
+
+    fn convert_auth(&mut self, auth: &Value, path: &str) -> Auth {
+        let mut result = Auth {
+            auth_type: AuthType::NoAuth,
+            ..Default::default()
+        };
+        match get_str(auth, "type") {
+            "noauth" | "" => {}
+            "bearer" => {
+                result.auth_type = AuthType::BearerToken;
+                result.bearer_token = get_auth_attribute(auth, "bearer", "token");
+            }
+            "basic" => {
+                result.auth_type = AuthType::BasicAuth;
+                result.basic_username = get_auth_attribute(auth, "basic", "username");
+                result.basic_password = get_auth_attribute(auth, "basic", "password");
+            }
+            "oauth2" => {
+                result.auth_type = AuthType::OAuth2;
+                result.oauth2 = OAuth2 {
+                    grant_type: match get_auth_attribute(auth, "oauth2", "grant_type").as_str() {
+                        "password_credentials" => OAuth2GrantType::Password,
+                        "authorization_code_with_pkce" | "authorization_code" => {
+                            OAuth2GrantType::AuthorizationCodeWithPKCE
+                        }
+                        _ => OAuth2GrantType::ClientCredentials,
+                    },
+                    access_token_url: get_auth_attribute(auth, "oauth2", "accessTokenUrl"),
+                    auth_url: get_auth_attribute(auth, "oauth2", "authUrl"),
+                    redirect_url: get_auth_attribute(auth, "oauth2", "redirect_uri"),
+                    client_id: get_auth_attribute(auth, "oauth2", "clientId"),
+                    client_secret: get_auth_attribute(auth, "oauth2", "clientSecret"),
+                    scope: get_auth_attribute(auth, "oauth2", "scope"),
+                    username: get_auth_attribute(auth, "oauth2", "username"),
+                    password: get_auth_attribute(auth, "oauth2", "password"),
+                    client_authentication: match get_auth_attribute(
+                        auth,
+                        "oauth2",
+                        "client_authentication",
+                    )
+                    .as_str()
+                    {
+                        "body" => OAuth2ClientAuthentication::ClientCredentialsInBody,
+                        _ => OAuth2ClientAuthentication::BasicAuthHeader,
+                    },
+                    ..Default::default()
+                };
+            }
+            "digest" => {
+                result.auth_type = AuthType::DigestAuth;
+                result.digest.username = get_auth_attribute(auth, "digest", "username");
+                result.digest.password = get_auth_attribute(auth, "digest", "password");
+            }
+            "apikey" => {
+                result.auth_type = AuthType::ApiKey;
+                result.api_key.name = get_auth_attribute(auth, "apikey", "key");
+                result.api_key.value = get_auth_attribute(auth, "apikey", "value");
+                result.api_key.location = match get_auth_attribute(auth, "apikey", "in").as_str() {
+                    "query" => ApiKeyLocation::QueryParam,
+                    _ => ApiKeyLocation::Header,
+                };
+            }
+            "awsv4" => {
+                result.auth_type = AuthType::AwsSigV4;
+                result.aws.access_key = get_auth_attribute(auth, "awsv4", "accessKey");
+                result.aws.secret_key = get_auth_attribute(auth, "awsv4", "secretKey");
+                result.aws.session_token = get_auth_attribute(auth, "awsv4", "sessionToken");
+                result.aws.region = get_auth_attribute(auth, "awsv4", "region");
+                result.aws.service = get_auth_attribute(auth, "awsv4", "service");
+            }
+            auth_type => {
+                self.warnings.push(format!(
+                    "{}: auth type `{}` is not supported, using No Auth",
+                    path, auth_type
+                ));
+            }
+        }
+        result
+    }
+
+    fn convert_events(&mut self, value: &Value, folder: &mut CollectionFolder, path: &str) {
+        for event in get_array(value, "event").iter() {
+            let script = self.convert_script(event, path);
+            match get_str(event, "listen") {
+                "prerequest" => folder.pre_request_script = script,
+                "test" => folder.test_script = script,
+                _ => {}
+            }
+        }
+    }
+
+    fn convert_script(&mut self, event: &Value, path: &str) -> String {
+        let exec = event.get("script").and_then(|s| s.get("exec"));
+        let script = match exec {
+            Some(Value::Array(lines)) => lines
+                .iter()
+                .map(|l| l.as_str().unwrap_or_default())
+                .collect::<Vec<&str>>()
+                .join("\n"),
+            Some(Value::String(s)) => s.clone(),
+            _ => "".to_string(),
+        };
+        let re = Regex::new(r"\b(pm|postman)\.[A-Za-z_$][A-Za-z0-9_$.]*").unwrap();
+        for (index, line) in script.lines().enumerate() {
+            let mut reported = HashSet::new();
+            for find in re.find_iter(line) {
+                let supported = SUPPORTED_SCRIPT_APIS.iter().any(|api| {
+                    find.as_str() == *api || find.as_str().starts_with(format!("{}.", api).as_str())
+                });
+                if !supported && reported.insert(find.as_str()) {
+                    self.warnings.push(format!(
+                        "{} {} script line {}: `{}` is not supported",
+                        path,
+                        get_str(event, "listen"),
+                        index + 1,
+                        find.as_str()
+                    ));
+                }
+            }
+        }
+        script
+    }
+
+    fn convert_variables(&mut self, value: &Value) -> EnvironmentConfig {
+        let mut envs = EnvironmentConfig::default();
+        for variable in get_array(value, "variable").iter() {
+            let variable_value = match variable.get("value") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => "".to_string(),
+                Some(v) => v.to_string(),
+            };
+            envs.items.push(EnvironmentItem {
+                enable: !get_bool(variable, "disabled"),
+                key: get_str(variable, "key").to_string(),
+                value: variable_value,
+                value_type: EnvironmentValueType::String,
+            });
+        }
+        envs
+    }
+}
+
+fn get_description(value: &Value) -> String {
+    match value.get("description") {
+        Some(Value::String(s)) => s.clone(),
+        Some(d) => get_str(d, "content").to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn get_auth_attribute(auth: &Value, auth_type: &str, key: &str) -> String {
+    match auth.get(auth_type) {
+        Some(Value::Array(attributes)) => attributes
+            .iter()
+            .find(|a| get_str(a, "key") == key)
+            .map(|a| match a.get("value") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => "".to_string(),
+                Some(v) => v.to_string(),
+            })
+            .unwrap_or_default(),
+        Some(attributes) => get_str(attributes, key).to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn build_raw_url(url: &Value) -> String {
+    let join = |key: &str, sep: &str| match url.get(key) {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(|p| p.as_str().unwrap_or_default())
+            .collect::<Vec<&str>>()
+            .join(sep),
+        Some(Value::String(s)) => s.clone(),
+        _ => "".to_string(),
+    };
+    let mut raw = join("host", ".");
+    let protocol = get_str(url, "protocol");
+    if protocol != "" {
+        raw = format!("{}://{}", protocol, raw);
+    }
+    let port = get_str(url, "port");
+    if port != "" {
+        raw = format!("{}:{}", raw, port);
+    }
+    let path = join("path", "/");
+    if path != "" {
+        raw = format!("{}/{}", raw, path);
+    }
+    raw
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::data::auth::{ApiKeyLocation, AuthType};
+    use crate::data::http::{BodyRawType, BodyType, Method, MultipartDataType};
+
+    use super::PostmanImport;
+
+    fn import() -> PostmanImport {
+        let value: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/postman_collection.json"))
+                .unwrap();
+        assert!(PostmanImport::is_match(&value));
+        PostmanImport::from_value(&value)
+    }
+
+    #[test]
+    fn folders_keep_nesting_and_order() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        assert_eq!(root.name, "Pet_Store");
+        assert_eq!(root.desc, "Sample pet store requests");
+        assert!(root.is_root);
+        let order: Vec<(String, usize)> = root
+            .requests
+            .values()
+            .map(|r| (r.name.clone(), r.order))
+            .chain(
+                root.folders
+                    .values()
+                    .map(|f| (f.borrow().name.clone(), f.borrow().order)),
+            )
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("Login".to_string(), 1),
+                ("Logout".to_string(), 4),
+                ("Search".to_string(), 3),
+                ("Pets".to_string(), 2)
+            ]
+        );
+        let pets = root.folders["Pets"].borrow();
+        assert_eq!(pets.requests["Get_pet"].order, 1);
+        assert_eq!(pets.requests["Upload_photo"].order, 2);
+        let admin = pets.folders["Admin"].borrow();
+        assert_eq!(admin.order, 3);
+        assert_eq!(admin.requests["Delete_pet"].request.method, Method::DELETE);
+        assert_eq!(
+            admin.requests["Delete_pet"].request.base_url,
+            "https://api.example.com:8443/pets/42"
+        );
+    }
+
+    #[test]
+    fn auth_is_mapped_per_level() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        assert_eq!(root.auth.auth_type, AuthType::NoAuth);
+        let pets = root.folders["Pets"].borrow();
+        assert_eq!(pets.auth.auth_type, AuthType::BearerToken);
+        assert_eq!(pets.auth.bearer_token, "{{token}}");
+        let upload = &pets.requests["Upload_photo"].request.auth;
+        assert_eq!(upload.auth_type, AuthType::ApiKey);
+        assert_eq!(upload.api_key.name, "X-Api-Key");
+        assert_eq!(upload.api_key.value, "{{apiKey}}");
+        assert_eq!(upload.api_key.location, ApiKeyLocation::QueryParam);
+        let admin = pets.folders["Admin"].borrow();
+        assert_eq!(admin.auth.auth_type, AuthType::BasicAuth);
+        assert_eq!(admin.auth.basic_username, "admin");
+        assert_eq!(admin.auth.basic_password, "secret");
+        assert_eq!(
+            admin.requests["Delete_pet"].request.auth.auth_type,
+            AuthType::NoAuth
+        );
+        let search = &root.requests["Search"].request.auth;
+        assert_eq!(search.auth_type, AuthType::AwsSigV4);
+        assert_eq!(search.aws.access_key, "AKIDEXAMPLE");
+        assert_eq!(search.aws.region, "us-east-1");
+        assert_eq!(search.aws.service, "execute-api");
+    }
+
+    #[test]
+    fn requests_keep_url_headers_and_body_modes() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        let login = &root.requests["Login"].request;
+        assert_eq!(login.method, Method::POST);
+        assert_eq!(login.base_url, "{{baseUrl}}/login");
+        assert_eq!(login.params.len(), 1);
+        assert_eq!(
+            (login.params[0].key.as_str(), login.params[0].value.as_str()),
+            ("remember", "true")
+        );
+        assert_eq!(login.headers.len(), 2);
+        assert!(login.headers[0].enable);
+        assert!(!login.headers[1].enable);
+        assert_eq!(login.body.body_type, BodyType::RAW);
+        assert_eq!(login.body.body_raw_type, BodyRawType::JSON);
+        assert!(login.body.body_str.contains("{{user}}"));
+
+        let pets = root.folders["Pets"].borrow();
+        let get_pet = &pets.requests["Get_pet"].request;
+        assert_eq!(get_pet.path_variables.len(), 1);
+        assert_eq!(get_pet.path_variables[0].key, "petId");
+        assert_eq!(get_pet.path_variables[0].value, "42");
+        assert_eq!(get_pet.path_variables[0].desc, "Pet to fetch");
+
+        let upload = &pets.requests["Upload_photo"].request.body;
+        assert_eq!(upload.body_type, BodyType::FROM_DATA);
+        assert_eq!(upload.body_form_data.len(), 2);
+        assert_eq!(upload.body_form_data[0].data_type, MultipartDataType::Text);
+        assert_eq!(upload.body_form_data[0].value, "Rex");
+        assert_eq!(upload.body_form_data[1].data_type, MultipartDataType::File);
+        assert_eq!(upload.body_form_data[1].value, "/tmp/rex.png");
+
+        let admin = pets.folders["Admin"].borrow();
+        let delete = &admin.requests["Delete_pet"].request.body;
+        assert_eq!(delete.body_type, BodyType::X_WWW_FROM_URLENCODED);
+        assert_eq!(delete.body_xxx_form.len(), 2);
+        assert_eq!(delete.body_xxx_form[0].value, "sold out");
+        assert!(!delete.body_xxx_form[1].enable);
+
+        let search = &root.requests["Search"].request.body;
+        assert_eq!(search.body_type, BodyType::GRAPHQL);
+        assert_eq!(search.body_graphql.query, "query { pets { id name } }");
+        assert_eq!(search.body_graphql.variables, "{\"limit\": 10}");
+
+        let logout = &root.requests["Logout"].request;
+        assert_eq!(logout.method, Method::GET);
+        assert_eq!(logout.body.body_type, BodyType::BINARY);
+        assert_eq!(logout.body.body_file, "/tmp/logout.txt");
+    }
+
+    #[test]
+    fn scripts_and_variables_are_imported() {
+        let import = import();
+        let root = import.collection.folder.borrow();
+        assert!(root.requests["Login"]
+            .test_script
+            .starts_with("pm.test(\"status is 200\", function () {\n"));
+        assert_eq!(
+            root.folders["Pets"].borrow().pre_request_script,
+            "const id = pm.iterationData.get(\"id\");"
+        );
+        let variables: Vec<(String, String, bool)> = import
+            .collection
+            .envs
+            .items
+            .iter()
+            .map(|i| (i.key.clone(), i.value.clone(), i.enable))
+            .collect();
+        assert_eq!(
+            variables,
+            vec![
+                (
+                    "baseUrl".to_string(),
+                    "https://petstore.example.com/v1".to_string(),
+                    true
+                ),
+                ("retries".to_string(), "3".to_string(), true),
+                ("legacy".to_string(), "old".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_parts_are_reported() {
+        assert_eq!(
+            import().warnings,
+            vec![
+                "Pet_Store/Login test script line 5: `pm.visualizer.set` is not supported",
+                "Pet_Store/Pets prerequest script line 1: `pm.iterationData.get` is not supported",
+                "Pet_Store/Pets/Admin/Delete_pet: auth type `ntlm` is not supported, using No Auth",
+                "Pet_Store/Logout: method `TRACE` is not supported, using GET",
+            ]
+        );
+    }
+}
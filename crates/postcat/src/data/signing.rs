@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::data::environment::EnvironmentItemValue;
+use crate::utils;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtAuth {
+    pub algorithm: JwtAlgorithm,
+    pub key_source: KeySource,
+    pub key: String,
+    pub claims: String,
+    pub exp_offset: i64,
+    pub header_name: String,
+    pub header_prefix: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HmacAuth {
+    pub algorithm: HmacAlgorithm,
+    pub key_source: KeySource,
+    pub key: String,
+    pub include_method: bool,
+    pub include_path: bool,
+    pub include_timestamp: bool,
+    pub include_body_hash: bool,
+    pub encoding: SignatureEncoding,
+    pub header_name: String,
+    pub header_prefix: String,
+    pub timestamp_header_name: String,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum JwtAlgorithm {
+    HS256,
+    RS256,
+    ES256,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum HmacAlgorithm {
+    SHA256,
+    SHA512,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum KeySource {
+    Value,
+    EnvironmentVariable,
+    File,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SignatureEncoding {
+    Hex,
+    Base64,
+}
+
+impl Default for JwtAuth {
+    fn default() -> Self {
+        JwtAuth {
+            algorithm: JwtAlgorithm::HS256,
+            key_source: KeySource::Value,
+            key: "".to_string(),
+            claims: "{}".to_string(),
+            exp_offset: 300,
+            header_name: "Authorization".to_string(),
+            header_prefix: "Bearer ".to_string(),
+        }
+    }
+}
+
+impl Default for HmacAuth {
+    fn default() -> Self {
+        HmacAuth {
+            algorithm: HmacAlgorithm::SHA256,
+            key_source: KeySource::Value,
+            key: "".to_string(),
+            include_method: true,
+            include_path: true,
+            include_timestamp: true,
+            include_body_hash: true,
+            encoding: SignatureEncoding::Hex,
+            header_name: "X-Signature".to_string(),
+            header_prefix: "".to_string(),
+            timestamp_header_name: "X-Timestamp".to_string(),
+        }
+    }
+}
+
+impl JwtAuth {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Algorithm:{} KeySource:{} Key:{} Claims:{} ExpOffset:{} HeaderName:{} HeaderPrefix:{}",
+            self.algorithm,
+            self.key_source,
+            self.key,
+            self.claims,
+            self.exp_offset,
+            self.header_name,
+            self.header_prefix
+        )
+    }
+
+    pub fn build(&self, envs: &BTreeMap<String, EnvironmentItemValue>) -> JwtAuth {
+        let mut jwt = self.clone();
+        jwt.key = utils::replace_variable(jwt.key, envs.clone());
+        jwt.claims = utils::replace_variable(jwt.claims, envs.clone());
+        jwt.header_name = utils::replace_variable(jwt.header_name, envs.clone());
+        jwt.header_prefix = utils::replace_variable(jwt.header_prefix, envs.clone());
+        jwt
+    }
+}
+
+impl HmacAuth {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Algorithm:{} KeySource:{} Key:{} Method:{} Path:{} Timestamp:{} BodyHash:{} Encoding:{} HeaderName:{} HeaderPrefix:{} TimestampHeaderName:{}",
+            self.algorithm,
+            self.key_source,
+            self.key,
+            self.include_method,
+            self.include_path,
+            self.include_timestamp,
+            self.include_body_hash,
+            self.encoding,
+            self.header_name,
+            self.header_prefix,
+            self.timestamp_header_name
+        )
+    }
+
+    pub fn build(&self, envs: &BTreeMap<String, EnvironmentItemValue>) -> HmacAuth {
+        let mut hmac = self.clone();
+        hmac.key = utils::replace_variable(hmac.key, envs.clone());
+        hmac.header_name = utils::replace_variable(hmac.header_name, envs.clone());
+        hmac.header_prefix = utils::replace_variable(hmac.header_prefix, envs.clone());
+        hmac.timestamp_header_name =
+            utils::replace_variable(hmac.timestamp_header_name, envs.clone());
+        hmac
+    }
+}
+
+impl KeySource {
+    /// Relative key files are read from the workspace directory.
+    pub fn load(&self, key: &str, workspace_dir: &PathBuf) -> Result<Vec<u8>, String> {
+        match self {
+            KeySource::Value => Ok(key.as_bytes().to_vec()),
+            KeySource::EnvironmentVariable => std::env::var(key)
+                .map(|v| v.into_bytes())
+                .map_err(|_| format!("environment variable `{}` is not set", key)),
+            KeySource::File => {
+                let mut file = PathBuf::from(key.trim());
+                if file.is_relative() {
+                    file = workspace_dir.join(file);
+                }
+                fs::read(file).map_err(|e| format!("read key file `{}`: {}", key, e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::data::environment::{EnvironmentItemValue, EnvironmentValueType};
+
+    use super::{HmacAuth, JwtAuth};
+
+    #[test]
+    fn build_resolves_header_names_and_prefixes() {
+        let mut envs = BTreeMap::new();
+        for (key, value) in [("header", "X-Signature"), ("scheme", "Signed")] {
+            envs.insert(
+                key.to_string(),
+                EnvironmentItemValue {
+                    value: value.to_string(),
+                    scope: "".to_string(),
+                    value_type: EnvironmentValueType::String,
+                },
+            );
+        }
+        let jwt = JwtAuth {
+            header_name: "{{header}}".to_string(),
+            header_prefix: "{{scheme}} ".to_string(),
+            ..Default::default()
+        }
+        .build(&envs);
+        assert_eq!(jwt.header_name, "X-Signature");
+        assert_eq!(jwt.header_prefix, "Signed ");
+        let hmac = HmacAuth {
+            header_name: "{{header}}".to_string(),
+            header_prefix: "{{scheme}} ".to_string(),
+            ..Default::default()
+        }
+        .build(&envs);
+        assert_eq!(hmac.header_name, "X-Signature");
+        assert_eq!(hmac.header_prefix, "Signed ");
+    }
+}
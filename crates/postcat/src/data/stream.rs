@@ -0,0 +1,330 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::data::environment::VariableChange;
+use crate::data::http::{Header, HttpBody, Response};
+use crate::data::logger::Logger;
+
+#[derive(Default, Clone, Debug)]
+pub struct ResponseStream {
+    data: Arc<Mutex<StreamData>>,
+    stop: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+    /// Kept apart from the result so a failing script or a stopped stream does not lose them.
+    variable_changes: Arc<Mutex<Vec<VariableChange>>>,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct StreamData {
+    pub started: Option<Instant>,
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<Header>,
+    pub body: Vec<u8>,
+    pub events: Vec<SseEvent>,
+    parsed: usize,
+    parser: SseParser,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct StreamSummary {
+    pub status: u16,
+    pub received: usize,
+    pub events: usize,
+    pub event_stream: bool,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct SseEvent {
+    pub id: String,
+    pub event: String,
+    pub data: String,
+    pub retry: String,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct SseParser {
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl ResponseStream {
+    pub fn start(&self, status: u16, status_text: String, headers: Vec<Header>) {
+        if let Ok(mut data) = self.data.lock() {
+            data.started = Some(Instant::now());
+            data.status = status;
+            data.status_text = status_text;
+            data.headers = headers;
+        }
+    }
+
+    pub fn push(&self, chunk: &[u8]) {
+        if let Ok(mut data) = self.data.lock() {
+            data.push(chunk);
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancel.clone()
+    }
+
+    pub fn set_variable_changes(&self, changes: Vec<VariableChange>) {
+        if let Ok(mut variable_changes) = self.variable_changes.lock() {
+            *variable_changes = changes;
+        }
+    }
+
+    pub fn variable_changes(&self) -> Vec<VariableChange> {
+        self.variable_changes
+            .lock()
+            .map(|c| c.clone())
+            .unwrap_or_default()
+    }
+
+    /// Identifies this stream across clones.
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.data) as usize
+    }
+
+    pub fn started(&self) -> bool {
+        self.data
+            .lock()
+            .map(|d| d.started.is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn summary(&self) -> StreamSummary {
+        self.data
+            .lock()
+            .map(|d| StreamSummary {
+                status: d.status,
+                received: d.body.len(),
+                events: d.events.len(),
+                event_stream: d.is_event_stream(),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Body bytes and events received after the given offsets.
+    pub fn read_since(&self, body_offset: usize, event_offset: usize) -> (Vec<u8>, Vec<SseEvent>) {
+        self.data
+            .lock()
+            .map(|d| {
+                (
+                    d.body.get(body_offset..).unwrap_or_default().to_vec(),
+                    d.events.get(event_offset..).unwrap_or_default().to_vec(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_data(&self) -> StreamData {
+        self.data.lock().map(|d| d.clone()).unwrap_or_default()
+    }
+
+    pub fn to_response(&self) -> Response {
+        let data = self.get_data();
+        Response {
+            headers: data.headers,
+            status: data.status,
+            status_text: data.status_text,
+            elapsed_time: data.started.map(|s| s.elapsed().as_millis()).unwrap_or(0),
+            logger: Logger::default(),
+            body: Arc::new(HttpBody::new(data.body)),
+            ..Default::default()
+        }
+    }
+}
+
+impl StreamData {
+    pub fn is_event_stream(&self) -> bool {
+        self.headers.iter().any(|h| {
+            h.key.to_lowercase() == "content-type" && h.value.contains("text/event-stream")
+        })
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.body.extend_from_slice(chunk);
+        if !self.is_event_stream() {
+            return;
+        }
+        while let Some(pos) = self.body[self.parsed..].iter().position(|b| *b == b'\n') {
+            let line = String::from_utf8_lossy(&self.body[self.parsed..self.parsed + pos])
+                .trim_end_matches('\r')
+                .to_string();
+            self.parsed += pos + 1;
+            if let Some(event) = self.parser.feed_line(line.as_str()) {
+                self.events.push(event);
+            }
+        }
+    }
+}
+
+impl SseParser {
+    pub fn parse(text: &str) -> Vec<SseEvent> {
+        let mut parser = SseParser::default();
+        let mut events: Vec<SseEvent> = text
+            .lines()
+            .filter_map(|line| parser.feed_line(line))
+            .collect();
+        if let Some(event) = parser.feed_line("") {
+            events.push(event);
+        }
+        events
+    }
+
+    pub fn feed_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            // an event without data is dropped, as in the EventSource spec
+            let event = std::mem::take(&mut self.current);
+            return std::mem::replace(&mut self.has_data, false).then_some(event);
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            None => (line, ""),
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+        };
+        match field {
+            "event" => self.current.event = value.to_string(),
+            "id" => self.current.id = value.to_string(),
+            "retry" => {
+                if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+                    self.current.retry = value.to_string();
+                }
+            }
+            "data" => {
+                if self.has_data {
+                    self.current.data.push('\n');
+                }
+                self.current.data.push_str(value);
+                self.has_data = true;
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::http::{Header, LockWith};
+
+    use super::{SseEvent, SseParser, StreamData};
+
+    fn event(id: &str, event: &str, data: &str, retry: &str) -> SseEvent {
+        SseEvent {
+            id: id.to_string(),
+            event: event.to_string(),
+            data: data.to_string(),
+            retry: retry.to_string(),
+        }
+    }
+
+    fn event_stream() -> StreamData {
+        StreamData {
+            headers: vec![Header {
+                key: "Content-Type".to_string(),
+                value: "text/event-stream; charset=utf-8".to_string(),
+                desc: "".to_string(),
+                enable: true,
+                lock_with: LockWith::NoLock,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_events() {
+        let cases: Vec<(&str, Vec<SseEvent>)> = vec![
+            ("data: hello\n\n", vec![event("", "", "hello", "")]),
+            ("data:hello\r\n\r\n", vec![event("", "", "hello", "")]),
+            (
+                "data: first\ndata: second\ndata\n\n",
+                vec![event("", "", "first\nsecond\n", "")],
+            ),
+            (
+                "id: 7\nevent: update\ndata:  padded\nretry: 1500\n\n",
+                vec![event("7", "update", " padded", "1500")],
+            ),
+            (
+                ": keep-alive\n\n:comment\ndata: after comment\n\n",
+                vec![event("", "", "after comment", "")],
+            ),
+            (
+                "event: ping\nid: 1\n\ndata: next\n\n",
+                vec![event("", "", "next", "")],
+            ),
+            (
+                "retry: 10s\ndata: a\n\nretry: -1\ndata: b\n\n",
+                vec![event("", "", "a", ""), event("", "", "b", "")],
+            ),
+            ("unknown: x\ndata: y\n\n", vec![event("", "", "y", "")]),
+            (
+                "data: no trailing blank line",
+                vec![event("", "", "no trailing blank line", "")],
+            ),
+            ("\n\n: only a comment\n\n", vec![]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(SseParser::parse(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn push_parses_events_split_across_chunks() {
+        let mut data = event_stream();
+        for chunk in [
+            "id: 1\r\nda",
+            "ta: hel",
+            "lo\r",
+            "\n\r\n: ping\r\n",
+            "\r\ndata: a\ndata: b\n",
+        ] {
+            data.push(chunk.as_bytes());
+        }
+        assert_eq!(data.events, vec![event("1", "", "hello", "")]);
+        data.push(b"\n");
+        assert_eq!(
+            data.events,
+            vec![event("1", "", "hello", ""), event("", "", "a\nb", "")]
+        );
+    }
+
+    #[test]
+    fn push_keeps_multibyte_characters_split_across_chunks() {
+        let mut data = event_stream();
+        let text = "data: caf\u{e9}\n\n".as_bytes();
+        let split = text.len() - 3;
+        data.push(&text[..split]);
+        data.push(&text[split..]);
+        assert_eq!(data.events, vec![event("", "", "caf\u{e9}", "")]);
+        assert_eq!(data.body, text);
+    }
+
+    #[test]
+    fn push_only_parses_event_streams() {
+        let mut data = StreamData::default();
+        data.push(b"data: hello\n\n");
+        assert!(data.events.is_empty());
+        assert_eq!(data.body, b"data: hello\n\n");
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketRecord {
+    pub messages: Vec<WebSocketMessage>,
+    pub message_script: String,
+}
+
+impl WebSocketRecord {
+    pub fn compute_signature(&self) -> String {
+        let messages: Vec<String> = self
+            .messages
+            .iter()
+            .map(|m| m.compute_signature())
+            .collect();
+        format!(
+            "Messages:[{}] MessageScript:{}",
+            messages.join(";"),
+            self.message_script
+        )
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketMessage {
+    pub name: String,
+    pub message_type: WebSocketMessageType,
+    pub content: String,
+}
+
+impl WebSocketMessage {
+    pub fn compute_signature(&self) -> String {
+        format!(
+            "Name:{} Type:{} Content:{}",
+            self.name, self.message_type, self.content
+        )
+    }
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum WebSocketMessageType {
+    Text,
+    Binary,
+}
+
+impl Default for WebSocketMessageType {
+    fn default() -> Self {
+        WebSocketMessageType::Text
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WebSocketDirection {
+    Inbound,
+    Outbound,
+    Info,
+    Error,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WebSocketLog {
+    pub time: DateTime<Local>,
+    pub direction: WebSocketDirection,
+    pub message_type: WebSocketMessageType,
+    pub content: String,
+}
+
+impl WebSocketLog {
+    pub fn new(
+        direction: WebSocketDirection,
+        message_type: WebSocketMessageType,
+        content: String,
+    ) -> Self {
+        Self {
+            time: Local::now(),
+            direction,
+            message_type,
+            content,
+        }
+    }
+
+    pub fn show(&self) -> String {
+        let arrow = match self.direction {
+            WebSocketDirection::Inbound => "↓",
+            WebSocketDirection::Outbound => "↑",
+            WebSocketDirection::Info => "•",
+            WebSocketDirection::Error => "✖",
+        };
+        let content = match self.message_type {
+            WebSocketMessageType::Text => self.content.clone(),
+            WebSocketMessageType::Binary => format!("[binary base64] {}", self.content),
+        };
+        format!("{} {} {}", self.time.format("%H:%M:%S%.3f"), arrow, content)
+    }
+}
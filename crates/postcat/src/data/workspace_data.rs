@@ -1,20 +1,31 @@
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::Duration;
+use std::sync::Arc;
 
 use chrono::NaiveDate;
 use log::error;
-use reqwest::blocking::Client;
+use reqwest::{Client, ClientBuilder};
+use reqwest_cookie_store::CookieStoreMutex;
 use uuid::Uuid;
 
 use crate::data::auth::{Auth, AuthType};
 use crate::data::central_request_data::{CentralRequestDataList, CentralRequestItem};
 use crate::data::collections::{Collection, CollectionFolder, Collections};
 use crate::data::cookies_manager::{Cookie, CookiesManager};
-use crate::data::environment::{Environment, EnvironmentConfig, EnvironmentItemValue};
+use crate::data::environment::{
+    Environment, EnvironmentConfig, EnvironmentItemValue, VariableChange, VariableScope,
+    ENVIRONMENT_GLOBALS,
+};
 use crate::data::history::{DateGroupHistoryList, HistoryDataList};
+use crate::data::http;
 use crate::data::http::HttpRecord;
+use crate::data::logger::Logger;
+use crate::data::network_settings::{
+    ClientConfig, NetworkConfig, NetworkSettings, ProxyConfig, RequestSettings,
+};
+use crate::operation::connection_timer::TimingResolver;
 use crate::script::script::ScriptScope;
 use crate::utils;
 
@@ -26,30 +37,100 @@ pub struct WorkspaceData {
     history_data_list: RefCell<HistoryDataList>,
     environment: RefCell<Environment>,
     collections: RefCell<Collections>,
-    client: Option<Client>,
+    network_settings: RefCell<NetworkSettings>,
+    clients: BTreeMap<String, Client>,
 }
 
 impl WorkspaceData {
-    pub fn build_http_client(&mut self) -> Client {
-        match &self.client {
-            None => {
-                let client = Client::builder()
-                    .cookie_provider(self.cookies_manager.borrow().cookie_store.clone())
-                    .trust_dns(true)
-                    .tcp_nodelay(true)
-                    .timeout(Duration::from_secs(60))
-                    .build()
-                    .unwrap_or_default();
-                self.client = Some(client.clone());
-                client
-            }
-            Some(client) => client.clone(),
+    pub fn build_crt_http_client(&mut self, crt_id: String) -> Result<Client, String> {
+        let crt = self.must_get_crt(crt_id.clone());
+        let envs = self.get_crt_envs(crt_id);
+        let url = utils::replace_variable(crt.rest.request.base_url, envs);
+        let env_select = self.get_env_select();
+        self.build_http_client_with(url.as_str(), &crt.rest.settings, env_select)
+    }
+
+    pub fn build_http_client_with(
+        &mut self,
+        url: &str,
+        settings: &RequestSettings,
+        env_select: Option<String>,
+    ) -> Result<Client, String> {
+        let config = self.network_settings.borrow().get();
+        let env = env_select
+            .and_then(|select| self.get_env(select))
+            .unwrap_or_default();
+        let verify = config.verify(vec![
+            settings.ssl_verification.clone(),
+            env.ssl_verification,
+        ]);
+        let client_certificate = config.match_client_certificate(url);
+        let proxy = config.get_proxy(&env.proxy);
+        let key = format!(
+            "{}|{:?}|{:?}|{}|{}|{}",
+            verify,
+            client_certificate,
+            proxy,
+            settings.timeout,
+            settings.http_version,
+            settings.disable_decompression
+        );
+        if let Some(client) = self.clients.get(key.as_str()) {
+            return Ok(client.clone());
+        }
+        let builder = ClientBuilder::new()
+            .dns_resolver(Arc::new(TimingResolver::default()))
+            .cookie_provider(self.cookies_manager.borrow().cookie_store.clone())
+            .tcp_nodelay(true);
+        let client = config
+            .apply(
+                settings.apply(builder),
+                verify,
+                client_certificate,
+                &proxy,
+                &self.get_workspace_dir(),
+            )?
+            .build()
+            .map_err(|e| e.to_string())?;
+        self.clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    pub fn get_proxy_config(&self, env_select: Option<String>) -> ProxyConfig {
+        let env = env_select
+            .and_then(|select| self.get_env(select))
+            .unwrap_or_default();
+        self.network_settings.borrow().get().get_proxy(&env.proxy)
+    }
+
+    pub fn get_client_config(&self, env_select: Option<String>) -> ClientConfig {
+        let env = env_select
+            .and_then(|select| self.get_env(select))
+            .unwrap_or_default();
+        let network = self.network_settings.borrow().get();
+        ClientConfig {
+            proxy: network.get_proxy(&env.proxy),
+            ssl_verification: env.ssl_verification,
+            network,
+            workspace_dir: self.get_workspace_dir(),
         }
     }
+
+    pub fn get_network_config(&self) -> NetworkConfig {
+        self.network_settings.borrow().get()
+    }
+
+    pub fn set_network_config(&mut self, config: NetworkConfig) {
+        self.network_settings.borrow_mut().set(config);
+        self.clients.clear();
+    }
 }
 
 //collections
 impl WorkspaceData {
+    pub fn get_workspace_dir(&self) -> PathBuf {
+        self.collections.borrow().get_workspace_dir()
+    }
     pub fn get_collection(&self, option_path: Option<String>) -> Option<Collection> {
         let path = option_path?;
         let collection_name = path.splitn(2, "/").next()?;
@@ -96,6 +177,10 @@ impl WorkspaceData {
         self.collections.borrow().get_auth(path)
     }
 
+    pub fn get_path_scripts(&self, path: String) -> (Vec<ScriptScope>, Vec<ScriptScope>) {
+        self.collections.borrow().get_path_scripts(path)
+    }
+
     pub fn get_collection_names(&self) -> HashSet<String> {
         self.collections
             .borrow()
@@ -145,6 +230,15 @@ impl WorkspaceData {
     ) -> BTreeMap<String, EnvironmentItemValue> {
         self.environment.borrow().get_variable_hash_map(collection)
     }
+    pub fn get_build_envs_with_select(
+        &self,
+        select: Option<String>,
+        collection: Option<Collection>,
+    ) -> BTreeMap<String, EnvironmentItemValue> {
+        self.environment
+            .borrow()
+            .get_variable_hash_map_with_select(select, collection)
+    }
     pub fn get_env_select(&self) -> Option<String> {
         self.environment.borrow().select()
     }
@@ -167,6 +261,62 @@ impl WorkspaceData {
     pub fn remove_env(&self, key: String) {
         self.environment.borrow_mut().remove(key)
     }
+
+    pub fn save_variable_changes(
+        &self,
+        env_select: Option<String>,
+        collection_path: Option<String>,
+        changes: &[VariableChange],
+        logger: &mut Logger,
+    ) {
+        for change in changes.iter() {
+            let action = match &change.value {
+                None => format!("unset `{}`", change.key),
+                Some(value) => format!("set `{}` as `{}`", change.key, value),
+            };
+            match change.scope {
+                VariableScope::Environment | VariableScope::Globals => {
+                    let name = match change.scope {
+                        VariableScope::Globals => Some(ENVIRONMENT_GLOBALS.to_string()),
+                        _ => env_select.clone(),
+                    };
+                    match name {
+                        None => logger.add_warn(
+                            "variables".to_string(),
+                            format!("no environment selected, `{}` not saved", change.key),
+                        ),
+                        Some(name) => {
+                            let mut config = self.get_env(name.clone()).unwrap_or_default();
+                            config.apply(change.key.as_str(), change.value.clone());
+                            self.add_env(name.clone(), config);
+                            logger.add_info(
+                                "variables".to_string(),
+                                format!("{} in environment `{}`", action, name),
+                            );
+                        }
+                    }
+                }
+                VariableScope::Collection => match self.get_collection(collection_path.clone()) {
+                    None => logger.add_warn(
+                        "variables".to_string(),
+                        format!("request is not in a collection, `{}` not saved", change.key),
+                    ),
+                    Some(collection) => {
+                        let name = collection.folder.borrow().name.clone();
+                        let mut envs = collection.envs.clone();
+                        envs.apply(change.key.as_str(), change.value.clone());
+                        self.collections
+                            .borrow_mut()
+                            .update_envs(name.clone(), envs);
+                        logger.add_info(
+                            "variables".to_string(),
+                            format!("{} in collection `{}`", action, name),
+                        );
+                    }
+                },
+            }
+        }
+    }
 }
 
 // history
@@ -174,8 +324,12 @@ impl WorkspaceData {
     pub fn get_history_group(&self) -> BTreeMap<NaiveDate, DateGroupHistoryList> {
         self.history_data_list.borrow().get_group().clone()
     }
-    pub fn history_record(&self, rest: HttpRecord) {
-        self.history_data_list.borrow_mut().record(rest);
+    pub fn history_record(
+        &self,
+        rest: HttpRecord,
+        exchange: Option<(http::Request, http::Response)>,
+    ) {
+        self.history_data_list.borrow_mut().record(rest, exchange);
     }
 }
 // cookie
@@ -186,6 +340,9 @@ impl WorkspaceData {
     pub fn save_cookies(&self) {
         self.cookies_manager.borrow().save()
     }
+    pub fn get_cookie_store(&self) -> Arc<CookieStoreMutex> {
+        self.cookies_manager.borrow().cookie_store.clone()
+    }
     pub fn cookies_contain_domain(&self, domain: String) -> bool {
         self.cookies_manager.borrow().contain_domain(domain)
     }
@@ -273,14 +430,27 @@ impl WorkspaceData {
         self.get_build_envs(self.get_collection(crt.collection_path.clone()))
     }
 
+    pub fn save_crt_variable_changes(
+        &self,
+        id: String,
+        changes: &[VariableChange],
+        logger: &mut Logger,
+    ) {
+        let crt = self.must_get_crt(id);
+        self.save_variable_changes(
+            self.get_env_select(),
+            crt.collection_path.clone(),
+            changes,
+            logger,
+        );
+    }
+
     pub fn get_crt_parent_auth(&self, id: String) -> Auth {
         let crt = self.must_get_crt(id);
         match &crt.collection_path {
             None => Auth {
                 auth_type: AuthType::NoAuth,
-                basic_username: "".to_string(),
-                basic_password: "".to_string(),
-                bearer_token: "".to_string(),
+                ..Default::default()
             },
             Some(collection_path) => self.get_collection_auth(collection_path.clone()),
         }
@@ -293,10 +463,8 @@ impl WorkspaceData {
         match &crt.collection_path {
             None => {}
             Some(collection_path) => {
-                (pre_request_script_scopes, test_script_scopes) = self
-                    .collections
-                    .borrow()
-                    .get_path_scripts(collection_path.clone())
+                (pre_request_script_scopes, test_script_scopes) =
+                    self.get_path_scripts(collection_path.clone())
             }
         }
         (pre_request_script_scopes, test_script_scopes)
@@ -411,7 +579,11 @@ impl WorkspaceData {
         self.collections.borrow_mut().load_all(workspace.clone());
         self.cookies_manager
             .borrow_mut()
-            .load_all(workspace.clone())
+            .load_all(workspace.clone());
+        self.network_settings
+            .borrow_mut()
+            .load_all(workspace.clone());
+        self.clients.clear();
     }
     pub fn reload_data(&mut self, workspace: String) {
         self.history_data_list
@@ -421,6 +593,10 @@ impl WorkspaceData {
         self.collections.borrow_mut().load_all(workspace.clone());
         self.cookies_manager
             .borrow_mut()
-            .load_all(workspace.clone())
+            .load_all(workspace.clone());
+        self.network_settings
+            .borrow_mut()
+            .load_all(workspace.clone());
+        self.clients.clear();
     }
 }
@@ -3,6 +3,7 @@
 use std::string::ToString;
 
 pub use app::App;
+pub use operation::collection_runner::{CollectionRunner, RUN_USAGE};
 
 pub const APP_NAME: &str = "Postcat";
 
@@ -12,11 +12,22 @@ use log4rs::config::{Appender, Root};
 use log4rs::encode::pattern::PatternEncoder;
 use log4rs::Config;
 
-use postcat::APP_NAME;
+use postcat::{CollectionRunner, APP_NAME, RUN_USAGE};
 
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|a| a == "run").unwrap_or(false) {
+        match CollectionRunner::from_args(args[2..].to_vec()) {
+            Ok(runner) => std::process::exit(runner.run()),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("{}", RUN_USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
     set_log_config();
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -0,0 +1,794 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::data::auth::{Auth, AuthType};
+use crate::data::environment::EnvironmentItemValue;
+use crate::data::http;
+use crate::data::http::{
+    BodyType, FormEncoding, Header, HttpRecord, LockWith, Method, MultipartDataType,
+};
+use crate::operation::rest_sender::RestSender;
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq, Eq, Debug)]
+pub enum CodeTarget {
+    Curl,
+    HTTPie,
+    PythonRequests,
+    JavaScriptFetch,
+    NodeAxios,
+    RustReqwest,
+    GoNetHttp,
+}
+
+impl Default for CodeTarget {
+    fn default() -> Self {
+        CodeTarget::Curl
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct CodeGenerator {
+    request: http::Request,
+    basic_auth: Option<(String, String)>,
+    keep_variables: bool,
+}
+
+enum CodeBody {
+    None,
+    Raw(String),
+    Form(Vec<(String, String)>),
+    Multipart(Vec<(String, String, bool)>),
+    File(String),
+}
+
+impl CodeGenerator {
+    pub fn build(
+        request: http::Request,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        parent_auth: Auth,
+        keep_variables: bool,
+    ) -> Self {
+        let mut record = HttpRecord {
+            request,
+            ..Default::default()
+        };
+        record.request.clear_lock_with();
+        if !keep_variables {
+            record.sync(envs.clone(), parent_auth);
+            let request = RestSender::build_request(record.request, envs.clone());
+            return CodeGenerator {
+                request,
+                basic_auth: None,
+                keep_variables,
+            };
+        }
+        let auth = match record.request.auth.auth_type {
+            AuthType::InheritAuthFromParent => parent_auth.clone(),
+            _ => record.request.auth.clone(),
+        };
+        record.sync(BTreeMap::new(), parent_auth);
+        let path_variables = record.request.path_variables.clone();
+        record.request.base_url =
+            http::replace_path_variables(record.request.base_url.as_str(), |key| {
+                path_variables
+                    .iter()
+                    .find(|v| v.key == key && !v.value.is_empty())
+                    .map(|v| v.value.clone())
+            });
+        record.request.headers.retain(|h| {
+            h.enable
+                && !(h.key.to_lowercase() == "authorization" && h.lock_with != LockWith::NoLock)
+        });
+        let mut basic_auth = None;
+        match auth.auth_type {
+            AuthType::BearerToken => record.request.headers.push(Header {
+                key: "Authorization".to_string(),
+                value: format!("Bearer {}", auth.bearer_token),
+                desc: "auto gen".to_string(),
+                enable: true,
+                lock_with: LockWith::LockWithAuto,
+            }),
+            AuthType::BasicAuth => {
+                basic_auth = Some((auth.basic_username.clone(), auth.basic_password.clone()))
+            }
+            AuthType::OAuth2 => {
+                if let Some(token) = auth.oauth2.with_cache_key(&envs).get_token() {
+                    record.request.headers.push(Header {
+                        key: "Authorization".to_string(),
+                        value: token.build_value(),
+                        desc: "auto gen".to_string(),
+                        enable: true,
+                        lock_with: LockWith::LockWithAuto,
+                    })
+                }
+            }
+            _ => {}
+        }
+        CodeGenerator {
+            request: record.request,
+            basic_auth,
+            keep_variables,
+        }
+    }
+
+    pub fn generate(&self, target: &CodeTarget) -> String {
+        match target {
+            CodeTarget::Curl => self.curl(),
+            CodeTarget::HTTPie => self.httpie(),
+            CodeTarget::PythonRequests => self.python_requests(),
+            CodeTarget::JavaScriptFetch => self.javascript_fetch(),
+            CodeTarget::NodeAxios => self.node_axios(),
+            CodeTarget::RustReqwest => self.rust_reqwest(),
+            CodeTarget::GoNetHttp => self.go_net_http(),
+        }
+    }
+
+    fn url(&self) -> String {
+        if !self.keep_variables {
+            return RestSender::build_url(&self.request);
+        }
+        let query: Vec<String> = self
+            .request
+            .params
+            .iter()
+            .filter(|p| p.enable)
+            .map(|p| format!("{}={}", p.key, p.value))
+            .collect();
+        if query.is_empty() {
+            self.request.base_url.clone()
+        } else if self.request.base_url.contains('?') {
+            format!("{}&{}", self.request.base_url, query.join("&"))
+        } else {
+            format!("{}?{}", self.request.base_url, query.join("&"))
+        }
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        let multipart = self.request.body.body_type == BodyType::FROM_DATA;
+        self.request
+            .headers
+            .iter()
+            .filter(|h| h.enable)
+            .filter(|h| !(multipart && h.key.to_lowercase() == "content-type"))
+            .map(|h| (h.key.clone(), h.value.clone()))
+            .collect()
+    }
+
+    fn body(&self) -> CodeBody {
+        let body = &self.request.body;
+        match body.body_type {
+            BodyType::NONE => CodeBody::None,
+            BodyType::RAW => CodeBody::Raw(body.body_str.clone()),
+            BodyType::GRAPHQL => CodeBody::Raw(body.body_graphql.to_payload()),
+            BodyType::X_WWW_FROM_URLENCODED
+                if body
+                    .body_xxx_form
+                    .iter()
+                    .any(|md| md.enable && md.encoding == FormEncoding::Raw) =>
+            {
+                CodeBody::Raw(body.to_form_urlencoded())
+            }
+            BodyType::X_WWW_FROM_URLENCODED => CodeBody::Form(
+                body.body_xxx_form
+                    .iter()
+                    .filter(|md| md.enable)
+                    .map(|md| (md.key.clone(), md.value.clone()))
+                    .collect(),
+            ),
+            BodyType::FROM_DATA => CodeBody::Multipart(
+                body.body_form_data
+                    .iter()
+                    .filter(|md| md.enable)
+                    .map(|md| {
+                        (
+                            md.key.clone(),
+                            md.value.clone(),
+                            md.data_type == MultipartDataType::File,
+                        )
+                    })
+                    .collect(),
+            ),
+            BodyType::BINARY => CodeBody::File(body.body_file.clone()),
+        }
+    }
+
+    fn curl(&self) -> String {
+        let mut lines = vec![format!(
+            "curl -X {} {}",
+            self.request.method,
+            shell_quote(self.url().as_str())
+        )];
+        for (key, value) in self.headers() {
+            lines.push(format!(
+                "-H {}",
+                shell_quote(format!("{}: {}", key, value).as_str())
+            ));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            lines.push(format!(
+                "-u {}",
+                shell_quote(format!("{}:{}", username, password).as_str())
+            ));
+        }
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => lines.push(format!("--data-raw {}", shell_quote(raw.as_str()))),
+            CodeBody::Form(fields) => {
+                for (key, value) in fields {
+                    lines.push(format!(
+                        "--data-urlencode {}",
+                        shell_quote(format!("{}={}", key, value).as_str())
+                    ));
+                }
+            }
+            CodeBody::Multipart(fields) => {
+                for (key, value, file) in fields {
+                    let field = if file {
+                        format!("{}=@{}", key, value)
+                    } else {
+                        format!("{}={}", key, value)
+                    };
+                    lines.push(format!("-F {}", shell_quote(field.as_str())));
+                }
+            }
+            CodeBody::File(path) => lines.push(format!(
+                "--data-binary {}",
+                shell_quote(format!("@{}", path).as_str())
+            )),
+        }
+        lines.join(" \\\n  ")
+    }
+
+    fn httpie(&self) -> String {
+        let mut parts = vec!["http".to_string()];
+        let mut items = vec![];
+        let mut suffix = "".to_string();
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => parts.push(format!("--raw {}", shell_quote(raw.as_str()))),
+            CodeBody::Form(fields) => {
+                parts.push("--form".to_string());
+                for (key, value) in fields {
+                    items.push(shell_quote(format!("{}={}", key, value).as_str()));
+                }
+            }
+            CodeBody::Multipart(fields) => {
+                parts.push("--multipart".to_string());
+                for (key, value, file) in fields {
+                    let item = if file {
+                        format!("{}@{}", key, value)
+                    } else {
+                        format!("{}={}", key, value)
+                    };
+                    items.push(shell_quote(item.as_str()));
+                }
+            }
+            CodeBody::File(path) => suffix = format!(" < {}", shell_quote(path.as_str())),
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            parts.push(format!(
+                "-a {}",
+                shell_quote(format!("{}:{}", username, password).as_str())
+            ));
+        }
+        parts.push(self.request.method.to_string());
+        parts.push(shell_quote(self.url().as_str()));
+        for (key, value) in self.headers() {
+            parts.push(shell_quote(format!("{}:{}", key, value).as_str()));
+        }
+        parts.extend(items);
+        parts.join(" \\\n  ") + suffix.as_str()
+    }
+
+    fn python_requests(&self) -> String {
+        let mut code = "import requests\n\n".to_string();
+        code.push_str(format!("url = {}\n", json_quote(self.url().as_str())).as_str());
+        let mut args = vec![
+            json_quote(self.request.method.to_string().as_str()),
+            "url".to_string(),
+        ];
+        let headers = self.headers();
+        if !headers.is_empty() {
+            code.push_str("headers = {\n");
+            for (key, value) in headers {
+                code.push_str(
+                    format!(
+                        "    {}: {},\n",
+                        json_quote(key.as_str()),
+                        json_quote(value.as_str())
+                    )
+                    .as_str(),
+                );
+            }
+            code.push_str("}\n");
+            args.push("headers=headers".to_string());
+        }
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => {
+                code.push_str(format!("data = {}\n", json_quote(raw.as_str())).as_str());
+                args.push("data=data".to_string());
+            }
+            CodeBody::Form(fields) => {
+                code.push_str("data = [\n");
+                for (key, value) in fields {
+                    code.push_str(
+                        format!(
+                            "    ({}, {}),\n",
+                            json_quote(key.as_str()),
+                            json_quote(value.as_str())
+                        )
+                        .as_str(),
+                    );
+                }
+                code.push_str("]\n");
+                args.push("data=data".to_string());
+            }
+            CodeBody::Multipart(fields) => {
+                code.push_str("files = [\n");
+                for (key, value, file) in fields {
+                    let value = if file {
+                        format!(
+                            "({}, open({}, \"rb\"))",
+                            json_quote(file_name(value.as_str()).as_str()),
+                            json_quote(value.as_str())
+                        )
+                    } else {
+                        format!("(None, {})", json_quote(value.as_str()))
+                    };
+                    code.push_str(
+                        format!("    ({}, {}),\n", json_quote(key.as_str()), value).as_str(),
+                    );
+                }
+                code.push_str("]\n");
+                args.push("files=files".to_string());
+            }
+            CodeBody::File(path) => {
+                code.push_str(
+                    format!("data = open({}, \"rb\")\n", json_quote(path.as_str())).as_str(),
+                );
+                args.push("data=data".to_string());
+            }
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            args.push(format!(
+                "auth=({}, {})",
+                json_quote(username.as_str()),
+                json_quote(password.as_str())
+            ));
+        }
+        code.push_str(
+            format!(
+                "\nresponse = requests.request({})\n\nprint(response.text)\n",
+                args.join(", ")
+            )
+            .as_str(),
+        );
+        code
+    }
+
+    fn javascript_fetch(&self) -> String {
+        let mut code = "".to_string();
+        let mut options = vec![format!(
+            "  method: {},",
+            json_quote(self.request.method.to_string().as_str())
+        )];
+        let mut headers: Vec<String> = self
+            .headers()
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "    {}: {},",
+                    json_quote(key.as_str()),
+                    json_quote(value.as_str())
+                )
+            })
+            .collect();
+        if let Some((username, password)) = &self.basic_auth {
+            headers.push(format!(
+                "    \"Authorization\": \"Basic \" + btoa({}),",
+                json_quote(format!("{}:{}", username, password).as_str())
+            ));
+        }
+        if !headers.is_empty() {
+            options.push(format!("  headers: {{\n{}\n  }},", headers.join("\n")));
+        }
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => options.push(format!("  body: {},", json_quote(raw.as_str()))),
+            CodeBody::Form(fields) => {
+                let pairs: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "    [{}, {}],",
+                            json_quote(key.as_str()),
+                            json_quote(value.as_str())
+                        )
+                    })
+                    .collect();
+                options.push(format!(
+                    "  body: new URLSearchParams([\n{}\n  ]),",
+                    pairs.join("\n")
+                ));
+            }
+            CodeBody::Multipart(fields) => {
+                code.push_str("const formData = new FormData();\n");
+                for (key, value, file) in fields {
+                    if file {
+                        code.push_str(
+                            format!(
+                                "formData.append({}, fileInput.files[0], {});\n",
+                                json_quote(key.as_str()),
+                                json_quote(file_name(value.as_str()).as_str())
+                            )
+                            .as_str(),
+                        );
+                    } else {
+                        code.push_str(
+                            format!(
+                                "formData.append({}, {});\n",
+                                json_quote(key.as_str()),
+                                json_quote(value.as_str())
+                            )
+                            .as_str(),
+                        );
+                    }
+                }
+                code.push('\n');
+                options.push("  body: formData,".to_string());
+            }
+            CodeBody::File(path) => {
+                code.push_str(
+                    format!(
+                        "// body read from {}\nconst file = fileInput.files[0];\n\n",
+                        path
+                    )
+                    .as_str(),
+                );
+                options.push("  body: file,".to_string());
+            }
+        }
+        code.push_str(
+            format!(
+                "fetch({}, {{\n{}\n}})\n  .then((response) => response.text())\n  .then((result) => console.log(result))\n  .catch((error) => console.error(error));\n",
+                json_quote(self.url().as_str()),
+                options.join("\n")
+            )
+            .as_str(),
+        );
+        code
+    }
+
+    fn node_axios(&self) -> String {
+        let mut requires = vec!["const axios = require(\"axios\");".to_string()];
+        let mut code = "".to_string();
+        let mut config = vec![
+            format!(
+                "  method: {},",
+                json_quote(self.request.method.to_string().to_lowercase().as_str())
+            ),
+            format!("  url: {},", json_quote(self.url().as_str())),
+        ];
+        let mut headers: Vec<String> = self
+            .headers()
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "    {}: {},",
+                    json_quote(key.as_str()),
+                    json_quote(value.as_str())
+                )
+            })
+            .collect();
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => {
+                code.push_str(format!("const data = {};\n\n", json_quote(raw.as_str())).as_str())
+            }
+            CodeBody::Form(fields) => {
+                let pairs: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "  [{}, {}],",
+                            json_quote(key.as_str()),
+                            json_quote(value.as_str())
+                        )
+                    })
+                    .collect();
+                code.push_str(
+                    format!(
+                        "const data = new URLSearchParams([\n{}\n]).toString();\n\n",
+                        pairs.join("\n")
+                    )
+                    .as_str(),
+                );
+            }
+            CodeBody::Multipart(fields) => {
+                requires.push("const FormData = require(\"form-data\");".to_string());
+                if fields.iter().any(|(_, _, file)| *file) {
+                    requires.push("const fs = require(\"fs\");".to_string());
+                }
+                code.push_str("const data = new FormData();\n");
+                for (key, value, file) in fields {
+                    let value = if file {
+                        format!("fs.createReadStream({})", json_quote(value.as_str()))
+                    } else {
+                        json_quote(value.as_str())
+                    };
+                    code.push_str(
+                        format!("data.append({}, {});\n", json_quote(key.as_str()), value).as_str(),
+                    );
+                }
+                code.push('\n');
+                headers.insert(0, "    ...data.getHeaders(),".to_string());
+            }
+            CodeBody::File(path) => {
+                requires.push("const fs = require(\"fs\");".to_string());
+                code.push_str(
+                    format!(
+                        "const data = fs.readFileSync({});\n\n",
+                        json_quote(path.as_str())
+                    )
+                    .as_str(),
+                );
+            }
+        }
+        if !headers.is_empty() {
+            config.push(format!("  headers: {{\n{}\n  }},", headers.join("\n")));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            config.push(format!(
+                "  auth: {{\n    username: {},\n    password: {},\n  }},",
+                json_quote(username.as_str()),
+                json_quote(password.as_str())
+            ));
+        }
+        if code != "" {
+            config.push("  data: data,".to_string());
+        }
+        format!(
+            "{}\n\n{}const config = {{\n{}\n}};\n\naxios\n  .request(config)\n  .then((response) => console.log(JSON.stringify(response.data)))\n  .catch((error) => console.error(error));\n",
+            requires.join("\n"),
+            code,
+            config.join("\n")
+        )
+    }
+
+    fn rust_reqwest(&self) -> String {
+        let method = match self.request.method {
+            Method::GET => "reqwest::Method::GET".to_string(),
+            Method::POST => "reqwest::Method::POST".to_string(),
+            Method::PUT => "reqwest::Method::PUT".to_string(),
+            Method::PATCH => "reqwest::Method::PATCH".to_string(),
+            Method::DELETE => "reqwest::Method::DELETE".to_string(),
+            Method::HEAD => "reqwest::Method::HEAD".to_string(),
+            Method::OPTIONS => "reqwest::Method::OPTIONS".to_string(),
+            _ => format!("reqwest::Method::from_bytes(b\"{}\")?", self.request.method),
+        };
+        let mut calls = vec![format!(
+            ".request({}, {})",
+            method,
+            rust_quote(self.url().as_str())
+        )];
+        for (key, value) in self.headers() {
+            calls.push(format!(
+                ".header({}, {})",
+                rust_quote(key.as_str()),
+                rust_quote(value.as_str())
+            ));
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            calls.push(format!(
+                ".basic_auth({}, Some({}))",
+                rust_quote(username.as_str()),
+                rust_quote(password.as_str())
+            ));
+        }
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => calls.push(format!(".body({})", rust_quote(raw.as_str()))),
+            CodeBody::Form(fields) => {
+                let pairs: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "            ({}, {}),",
+                            rust_quote(key.as_str()),
+                            rust_quote(value.as_str())
+                        )
+                    })
+                    .collect();
+                calls.push(format!(".form(&[\n{}\n        ])", pairs.join("\n")));
+            }
+            CodeBody::Multipart(fields) => {
+                let mut form = vec!["reqwest::blocking::multipart::Form::new()".to_string()];
+                for (key, value, file) in fields {
+                    if file {
+                        form.push(format!(
+                            "                .file({}, {})?",
+                            rust_quote(key.as_str()),
+                            rust_quote(value.as_str())
+                        ));
+                    } else {
+                        form.push(format!(
+                            "                .text({}, {})",
+                            rust_quote(key.as_str()),
+                            rust_quote(value.as_str())
+                        ));
+                    }
+                }
+                calls.push(format!(
+                    ".multipart(\n            {},\n        )",
+                    form.join("\n")
+                ));
+            }
+            CodeBody::File(path) => calls.push(format!(
+                ".body(std::fs::read({})?)",
+                rust_quote(path.as_str())
+            )),
+        }
+        calls.push(".send()?".to_string());
+        format!(
+            "fn main() -> Result<(), Box<dyn std::error::Error>> {{\n    let client = reqwest::blocking::Client::new();\n    let response = client\n        {};\n    println!(\"{{}}\", response.text()?);\n    Ok(())\n}}\n",
+            calls.join("\n        ")
+        )
+    }
+
+    fn go_net_http(&self) -> String {
+        let mut imports: BTreeSet<&str> = BTreeSet::new();
+        imports.insert("fmt");
+        imports.insert("io");
+        imports.insert("net/http");
+        let mut code = "".to_string();
+        let mut payload = "nil".to_string();
+        let mut content_type = None;
+        match self.body() {
+            CodeBody::None => {}
+            CodeBody::Raw(raw) => {
+                imports.insert("strings");
+                code.push_str(
+                    format!(
+                        "\tpayload := strings.NewReader({})\n",
+                        go_quote(raw.as_str())
+                    )
+                    .as_str(),
+                );
+                payload = "payload".to_string();
+            }
+            CodeBody::Form(fields) => {
+                imports.insert("net/url");
+                imports.insert("strings");
+                code.push_str("\tform := url.Values{}\n");
+                for (key, value) in fields {
+                    code.push_str(
+                        format!(
+                            "\tform.Add({}, {})\n",
+                            go_quote(key.as_str()),
+                            go_quote(value.as_str())
+                        )
+                        .as_str(),
+                    );
+                }
+                code.push_str("\tpayload := strings.NewReader(form.Encode())\n");
+                payload = "payload".to_string();
+            }
+            CodeBody::Multipart(fields) => {
+                imports.insert("bytes");
+                imports.insert("mime/multipart");
+                code.push_str("\tpayload := &bytes.Buffer{}\n");
+                code.push_str("\twriter := multipart.NewWriter(payload)\n");
+                for (index, (key, value, file)) in fields.iter().enumerate() {
+                    if *file {
+                        imports.insert("os");
+                        imports.insert("path/filepath");
+                        code.push_str(
+                            format!(
+                                "\tfile{index}, err := os.Open({path})\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n\tdefer file{index}.Close()\n\tpart{index}, err := writer.CreateFormFile({key}, filepath.Base({path}))\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n\tif _, err := io.Copy(part{index}, file{index}); err != nil {{\n\t\tpanic(err)\n\t}}\n",
+                                index = index,
+                                key = go_quote(key.as_str()),
+                                path = go_quote(value.as_str())
+                            )
+                            .as_str(),
+                        );
+                    } else {
+                        code.push_str(
+                            format!(
+                                "\tif err := writer.WriteField({}, {}); err != nil {{\n\t\tpanic(err)\n\t}}\n",
+                                go_quote(key.as_str()),
+                                go_quote(value.as_str())
+                            )
+                            .as_str(),
+                        );
+                    }
+                }
+                code.push_str("\tif err := writer.Close(); err != nil {\n\t\tpanic(err)\n\t}\n");
+                payload = "payload".to_string();
+                content_type = Some("writer.FormDataContentType()".to_string());
+            }
+            CodeBody::File(path) => {
+                imports.insert("os");
+                code.push_str(
+                    format!(
+                        "\tpayload, err := os.Open({})\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n\tdefer payload.Close()\n",
+                        go_quote(path.as_str())
+                    )
+                    .as_str(),
+                );
+                payload = "payload".to_string();
+            }
+        }
+        code.push_str(
+            format!(
+                "\treq, err := http.NewRequest({}, {}, {})\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n",
+                go_quote(self.request.method.to_string().as_str()),
+                go_quote(self.url().as_str()),
+                payload
+            )
+            .as_str(),
+        );
+        for (key, value) in self.headers() {
+            code.push_str(
+                format!(
+                    "\treq.Header.Add({}, {})\n",
+                    go_quote(key.as_str()),
+                    go_quote(value.as_str())
+                )
+                .as_str(),
+            );
+        }
+        if let Some(content_type) = content_type {
+            code.push_str(
+                format!("\treq.Header.Set(\"Content-Type\", {})\n", content_type).as_str(),
+            );
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            code.push_str(
+                format!(
+                    "\treq.SetBasicAuth({}, {})\n",
+                    go_quote(username.as_str()),
+                    go_quote(password.as_str())
+                )
+                .as_str(),
+            );
+        }
+        code.push_str("\n\tres, err := http.DefaultClient.Do(req)\n\tif err != nil {\n\t\tpanic(err)\n\t}\n\tdefer res.Body.Close()\n\n\tbody, err := io.ReadAll(res.Body)\n\tif err != nil {\n\t\tpanic(err)\n\t}\n\tfmt.Println(string(body))\n");
+        let imports: Vec<String> = imports.iter().map(|i| format!("\t\"{}\"", i)).collect();
+        format!(
+            "package main\n\nimport (\n{}\n)\n\nfunc main() {{\n{}}}\n",
+            imports.join("\n"),
+            code
+        )
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn json_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn rust_quote(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+fn go_quote(value: &str) -> String {
+    if value.contains('\n') && !value.contains('`') {
+        format!("`{}`", value)
+    } else {
+        json_quote(value)
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(path)
+        .to_string()
+}
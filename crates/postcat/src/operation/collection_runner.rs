@@ -0,0 +1,594 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+use poll_promise::Promise;
+
+use crate::data::collections::{CollectionFolder, CollectionItem};
+use crate::data::config_data::ConfigData;
+use crate::data::har::Har;
+use crate::data::http::{HttpRecord, RecordType};
+use crate::data::logger::Logger;
+use crate::data::stream::ResponseStream;
+use crate::data::test::{TestResult, TestStatus};
+use crate::data::workspace_data::WorkspaceData;
+use crate::data::{http, test};
+use crate::operation::operation::Operation;
+use crate::script::script::ScriptScope;
+use crate::utils;
+
+pub const RUN_USAGE: &str = "Usage: postcat run <collection[/folder...]> [--env <environment>] [--workspace <workspace>] [--har <file>]";
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct CollectionRunner {
+    path: String,
+    environment: Option<String>,
+    workspace: Option<String>,
+    har: Option<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct CollectionRunResult {
+    pub items: Vec<CollectionRunItem>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CollectionRunItem {
+    pub path: String,
+    pub started: DateTime<Utc>,
+    pub record: HttpRecord,
+    pub result: Result<(http::Request, http::Response, test::TestResult), String>,
+}
+
+impl CollectionRunResult {
+    pub fn is_success(&self) -> bool {
+        self.items.iter().all(|item| match &item.result {
+            Ok((_, _, test_result)) => test_result.status != TestStatus::FAIL,
+            Err(_) => false,
+        })
+    }
+
+    pub fn to_har(&self) -> Har {
+        Har::new(
+            self.items
+                .iter()
+                .filter_map(|item| match &item.result {
+                    Ok((request, response, _)) => {
+                        Some((item.started, request.clone(), response.clone()))
+                    }
+                    Err(_) => None,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn write_har(&self, path: String) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&self.to_har()).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl CollectionRunner {
+    pub fn from_args(args: Vec<String>) -> Result<Self, String> {
+        let mut runner = CollectionRunner::default();
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-e" | "--env" => {
+                    runner.environment =
+                        Some(iter.next().ok_or(format!("missing value for `{}`", arg))?);
+                }
+                "--har" => {
+                    runner.har = Some(iter.next().ok_or(format!("missing value for `{}`", arg))?);
+                }
+                "-w" | "--workspace" => {
+                    runner.workspace =
+                        Some(iter.next().ok_or(format!("missing value for `{}`", arg))?);
+                }
+                _ => {
+                    if arg.starts_with("-") {
+                        return Err(format!("unknown option `{}`", arg));
+                    }
+                    if runner.path != "" {
+                        return Err(format!("unexpected argument `{}`", arg));
+                    }
+                    runner.path = arg.trim_matches('/').to_string();
+                }
+            }
+        }
+        if runner.path == "" {
+            return Err("missing collection or folder path".to_string());
+        }
+        Ok(runner)
+    }
+
+    pub fn run(&self) -> i32 {
+        let config_data = ConfigData::load();
+        let workspace = self
+            .workspace
+            .clone()
+            .unwrap_or(config_data.select_workspace().to_string());
+        if !config_data.workspaces().contains_key(workspace.as_str()) {
+            eprintln!("Workspace `{}` not found", workspace);
+            return 2;
+        }
+        let mut workspace_data = WorkspaceData::default();
+        workspace_data.load_all(workspace.clone());
+        self.run_workspace(&mut workspace_data)
+    }
+
+    fn run_workspace(&self, workspace_data: &mut WorkspaceData) -> i32 {
+        if let Some(environment) = &self.environment {
+            if workspace_data.get_env(environment.clone()).is_none() {
+                eprintln!("Environment `{}` not found", environment);
+                return 2;
+            }
+        }
+        let (_, folder_option) = workspace_data.get_folder_with_path(self.path.clone());
+        let folder = match folder_option {
+            None => {
+                eprintln!("Collection or folder `{}` not found", self.path);
+                return 2;
+            }
+            Some(folder) => folder,
+        };
+        let run_result = self.run_folder(workspace_data, folder);
+        self.finish(&run_result)
+    }
+
+    /// Prints the summary and writes the HAR file, returns the exit code.
+    fn finish(&self, run_result: &CollectionRunResult) -> i32 {
+        Self::print_summary(run_result);
+        if let Some(har) = &self.har {
+            if let Err(e) = run_result.write_har(har.clone()) {
+                eprintln!("Write HAR file `{}` failed: {}", har, e);
+                return 2;
+            }
+        }
+        if run_result.is_success() {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn run_folder(
+        &self,
+        workspace_data: &mut WorkspaceData,
+        folder: Rc<RefCell<CollectionFolder>>,
+    ) -> CollectionRunResult {
+        let mut records = vec![];
+        Self::collect_records(self.path.clone(), folder, &mut records);
+        let operation = Operation::default();
+        let mut run_result = CollectionRunResult::default();
+        for (path, mut record) in records {
+            let envs = workspace_data.get_build_envs_with_select(
+                self.environment.clone(),
+                workspace_data.get_collection(Some(path.clone())),
+            );
+            let parent_auth = workspace_data.get_collection_auth(path.clone());
+            let client_config = workspace_data.get_client_config(self.environment.clone());
+            let (mut pre_request_script_scopes, mut test_script_scopes) =
+                workspace_data.get_path_scripts(path.clone());
+            if record.pre_request_script != "" {
+                pre_request_script_scopes.push(ScriptScope {
+                    scope: "request".to_string(),
+                    script: record.pre_request_script.clone(),
+                });
+            }
+            if record.test_script != "" {
+                test_script_scopes.push(ScriptScope {
+                    scope: "request".to_string(),
+                    script: record.test_script.clone(),
+                });
+            }
+            record.request.clear_lock_with();
+            record.sync(envs.clone(), parent_auth.clone());
+            let started = Utc::now();
+            let stream = ResponseStream::default();
+            let send_promise = match record.record_type {
+                RecordType::GRPC => operation.send_grpc_with_script(
+                    record.request.clone(),
+                    record.grpc.clone(),
+                    envs,
+                    parent_auth,
+                    client_config,
+                    pre_request_script_scopes,
+                    test_script_scopes,
+                    workspace_data.get_workspace_dir(),
+                    stream.clone(),
+                ),
+                _ => match workspace_data.build_http_client_with(
+                    utils::replace_variable(record.request.base_url.clone(), envs.clone()).as_str(),
+                    &record.settings,
+                    self.environment.clone(),
+                ) {
+                    Ok(client) => operation.send_with_script(
+                        record.request.clone(),
+                        envs,
+                        parent_auth,
+                        client_config,
+                        pre_request_script_scopes,
+                        test_script_scopes,
+                        client,
+                        record.settings.clone(),
+                        workspace_data.get_cookie_store(),
+                        stream.clone(),
+                    ),
+                    Err(e) => Promise::from_ready(Err(e)),
+                },
+            };
+            let mut result = send_promise.block_and_take();
+            let mut variable_logger = Logger::default();
+            let logger = match &mut result {
+                Ok((_, response, _)) => &mut response.logger,
+                Err(_) => &mut variable_logger,
+            };
+            workspace_data.save_variable_changes(
+                self.environment.clone(),
+                Some(path.clone()),
+                &stream.variable_changes(),
+                logger,
+            );
+            let item = CollectionRunItem {
+                path,
+                started,
+                record,
+                result,
+            };
+            Self::print_item(&item);
+            run_result.items.push(item);
+        }
+        workspace_data.save_cookies();
+        run_result
+    }
+
+    fn collect_records(
+        path: String,
+        folder: Rc<RefCell<CollectionFolder>>,
+        records: &mut Vec<(String, HttpRecord)>,
+    ) {
+        let folder = folder.borrow();
+        for item in folder.ordered_items() {
+            match item {
+                CollectionItem::Request(name) => {
+                    if let Some(record) = folder
+                        .requests
+                        .get(name.as_str())
+                        .filter(|r| r.record_type != RecordType::WebSocket)
+                    {
+                        records.push((path.clone(), record.clone()));
+                    }
+                }
+                CollectionItem::Folder(name) => {
+                    if let Some(child_folder) = folder.folders.get(name.as_str()) {
+                        Self::collect_records(
+                            format!("{}/{}", path, name),
+                            child_folder.clone(),
+                            records,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn print_item(item: &CollectionRunItem) {
+        println!("→ {}/{}", item.path, item.record.name);
+        match &item.result {
+            Ok((request, response, test_result)) => {
+                println!(
+                    "  {} {} [{} {}, {}ms, {}]",
+                    request.method,
+                    request.base_url,
+                    response.status,
+                    response.status_text,
+                    response.elapsed_time,
+                    response.body.get_byte_size()
+                );
+                Self::print_test_result(test_result);
+            }
+            Err(e) => {
+                println!("  ERROR {}", e);
+            }
+        }
+    }
+
+    fn print_test_result(test_result: &TestResult) {
+        for test_info in test_result.test_info_list.iter() {
+            match test_info.status {
+                TestStatus::FAIL => {
+                    println!("  ✗ {}", test_info.name);
+                    for tar in test_info
+                        .results
+                        .iter()
+                        .filter(|tar| tar.assert_result == TestStatus::FAIL)
+                    {
+                        println!("      {}", tar.msg);
+                    }
+                }
+                _ => println!("  ✓ {}", test_info.name),
+            }
+        }
+    }
+
+    fn print_summary(run_result: &CollectionRunResult) {
+        let mut request_failed = 0;
+        let mut test_passed = 0;
+        let mut test_failed = 0;
+        for item in run_result.items.iter() {
+            match &item.result {
+                Ok((_, _, test_result)) => {
+                    for test_info in test_result.test_info_list.iter() {
+                        match test_info.status {
+                            TestStatus::FAIL => test_failed += 1,
+                            _ => test_passed += 1,
+                        }
+                    }
+                }
+                Err(_) => request_failed += 1,
+            }
+        }
+        println!();
+        println!(
+            "Requests: {} executed, {} failed",
+            run_result.items.len(),
+            request_failed
+        );
+        println!("Tests: {} passed, {} failed", test_passed, test_failed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    use chrono::Utc;
+
+    use crate::data::collections::CollectionFolder;
+    use crate::data::har::Har;
+    use crate::data::http::{HttpRecord, Method, RecordType, Request, Response};
+    use crate::data::test::{TestResult, TestStatus};
+    use crate::data::workspace_data::WorkspaceData;
+
+    use super::{CollectionRunItem, CollectionRunResult, CollectionRunner};
+
+    fn record(name: &str, order: usize) -> HttpRecord {
+        HttpRecord {
+            name: name.to_string(),
+            order,
+            ..Default::default()
+        }
+    }
+
+    fn folder(
+        name: &str,
+        order: usize,
+        requests: Vec<HttpRecord>,
+        folders: Vec<CollectionFolder>,
+    ) -> CollectionFolder {
+        CollectionFolder {
+            name: name.to_string(),
+            order,
+            requests: requests.into_iter().map(|r| (r.name.clone(), r)).collect(),
+            folders: folders
+                .into_iter()
+                .map(|f| (f.name.clone(), Rc::new(RefCell::new(f))))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn collect(root: CollectionFolder) -> Vec<String> {
+        let mut records = vec![];
+        CollectionRunner::collect_records(
+            root.name.clone(),
+            Rc::new(RefCell::new(root)),
+            &mut records,
+        );
+        records
+            .into_iter()
+            .map(|(path, record)| format!("{}/{}", path, record.name))
+            .collect()
+    }
+
+    fn item(name: &str, result: Result<TestStatus, String>) -> CollectionRunItem {
+        let request = Request {
+            method: Method::GET,
+            base_url: format!("http://localhost/{}", name),
+            ..Default::default()
+        };
+        let response = Response {
+            status: 200,
+            status_text: "OK".to_string(),
+            ..Default::default()
+        };
+        CollectionRunItem {
+            path: "api".to_string(),
+            started: Utc::now(),
+            record: record(name, 0),
+            result: result.map(|status| {
+                let mut test_result = TestResult::default();
+                if status != TestStatus::None {
+                    test_result.open("check".to_string());
+                    test_result.append(status == TestStatus::PASS, "expected 200".to_string());
+                    test_result.close("check".to_string());
+                }
+                (request, response, test_result)
+            }),
+        }
+    }
+
+    fn runner(args: &[&str]) -> CollectionRunner {
+        CollectionRunner::from_args(args.iter().map(|a| a.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn records_follow_the_collection_order() {
+        let root = folder(
+            "api",
+            0,
+            vec![record("login", 1), record("logout", 4)],
+            vec![
+                folder(
+                    "users",
+                    2,
+                    vec![record("list", 2), record("create", 1)],
+                    vec![],
+                ),
+                folder("admin", 3, vec![record("stats", 1)], vec![]),
+            ],
+        );
+        assert_eq!(
+            collect(root),
+            vec![
+                "api/login",
+                "api/users/create",
+                "api/users/list",
+                "api/admin/stats",
+                "api/logout"
+            ]
+        );
+    }
+
+    #[test]
+    fn records_without_order_run_requests_first_by_name() {
+        let root = folder(
+            "api",
+            0,
+            vec![record("b", 0), record("a", 0)],
+            vec![folder("child", 0, vec![record("c", 0)], vec![])],
+        );
+        assert_eq!(collect(root), vec!["api/a", "api/b", "api/child/c"]);
+    }
+
+    #[test]
+    fn websocket_records_are_skipped() {
+        let mut socket = record("socket", 2);
+        socket.record_type = RecordType::WebSocket;
+        let mut grpc = record("grpc", 3);
+        grpc.record_type = RecordType::GRPC;
+        let root = folder("api", 0, vec![record("http", 1), socket, grpc], vec![]);
+        assert_eq!(collect(root), vec!["api/http", "api/grpc"]);
+    }
+
+    #[test]
+    fn from_args_reads_options() {
+        let runner = runner(&[
+            "/api/users/",
+            "--env",
+            "dev",
+            "-w",
+            "team",
+            "--har",
+            "out.har",
+        ]);
+        assert_eq!(runner.path, "api/users");
+        assert_eq!(runner.environment, Some("dev".to_string()));
+        assert_eq!(runner.workspace, Some("team".to_string()));
+        assert_eq!(runner.har, Some("out.har".to_string()));
+    }
+
+    #[test]
+    fn from_args_rejects_bad_arguments() {
+        for args in [
+            vec![],
+            vec!["api", "--env"],
+            vec!["api", "--verbose"],
+            vec!["api", "other"],
+        ] {
+            assert!(
+                CollectionRunner::from_args(args.iter().map(|a| a.to_string()).collect()).is_err(),
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn exit_code_is_0_when_every_request_and_test_passes() {
+        let run_result = CollectionRunResult {
+            items: vec![
+                item("a", Ok(TestStatus::PASS)),
+                item("b", Ok(TestStatus::None)),
+            ],
+        };
+        assert_eq!(runner(&["api"]).finish(&run_result), 0);
+    }
+
+    #[test]
+    fn exit_code_is_1_when_a_test_or_request_fails() {
+        let failed_test = CollectionRunResult {
+            items: vec![
+                item("a", Ok(TestStatus::PASS)),
+                item("b", Ok(TestStatus::FAIL)),
+            ],
+        };
+        assert_eq!(runner(&["api"]).finish(&failed_test), 1);
+        let failed_request = CollectionRunResult {
+            items: vec![item("a", Err("connection refused".to_string()))],
+        };
+        assert_eq!(runner(&["api"]).finish(&failed_request), 1);
+    }
+
+    #[test]
+    fn exit_code_is_2_when_the_folder_or_environment_is_missing() {
+        let mut workspace_data = WorkspaceData::default();
+        assert_eq!(runner(&["missing"]).run_workspace(&mut workspace_data), 2);
+        assert_eq!(
+            runner(&["missing", "--env", "missing"]).run_workspace(&mut workspace_data),
+            2
+        );
+    }
+
+    #[test]
+    fn exit_code_is_2_when_the_har_file_cannot_be_written() {
+        let dir = std::env::temp_dir().join("postcat_runner_missing_dir");
+        let _ = fs::remove_dir_all(dir.clone());
+        let har = dir.join("run.har").to_string_lossy().to_string();
+        let run_result = CollectionRunResult {
+            items: vec![item("a", Ok(TestStatus::PASS))],
+        };
+        assert_eq!(
+            runner(&["api", "--har", har.as_str()]).finish(&run_result),
+            2
+        );
+    }
+
+    #[test]
+    fn har_output_holds_the_sent_requests_in_run_order() {
+        let har = std::env::temp_dir()
+            .join(format!("postcat_runner_{}.har", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let run_result = CollectionRunResult {
+            items: vec![
+                item("first", Ok(TestStatus::PASS)),
+                item("failed", Err("timeout".to_string())),
+                item("second", Ok(TestStatus::FAIL)),
+            ],
+        };
+        assert_eq!(
+            runner(&["api", "--har", har.as_str()]).finish(&run_result),
+            1
+        );
+        let written: Har =
+            serde_json::from_str(fs::read_to_string(har.as_str()).unwrap().as_str()).unwrap();
+        let _ = fs::remove_file(har);
+        assert_eq!(written.log.version, "1.2");
+        let urls: Vec<String> = written
+            .log
+            .entries
+            .iter()
+            .map(|e| e.request.url.clone())
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["http://localhost/first", "http://localhost/second"]
+        );
+        assert!(written.log.entries.iter().all(|e| e.response.status == 200));
+    }
+}
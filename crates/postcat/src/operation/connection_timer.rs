@@ -0,0 +1,254 @@
+use std::error::Error;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::{Duration, Instant};
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
+
+tokio::task_local! {
+    /// Events of the exchange measured by the current task, so concurrent sends on a
+    /// shared client never see each other's lookups.
+    static EVENTS: Arc<Mutex<ConnectionEvents>>;
+}
+
+#[derive(Default, Clone, Debug)]
+struct ConnectionEvents {
+    lookup: Option<(Instant, Instant)>,
+    wakes: Vec<Instant>,
+}
+
+/// How the connection used by one exchange was opened, zero for a reused connection.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ConnectionPhases {
+    pub dns: Duration,
+    pub tcp: Duration,
+    pub tls: Duration,
+}
+
+/// Resolves hosts through trust-dns, as reqwest does with the `trust-dns` feature,
+/// and records the lookup for the exchange being measured.
+#[derive(Default, Clone)]
+pub struct TimingResolver {
+    /// Built on first use, inside the client runtime.
+    state: Arc<OnceLock<TokioAsyncResolver>>,
+}
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let resolver = resolver.state.get_or_init(|| {
+                TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|_| {
+                    TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+                })
+            });
+            let start = Instant::now();
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            let end = Instant::now();
+            if let Ok(events) = EVENTS.try_with(|events| events.clone()) {
+                if let Ok(mut events) = events.lock() {
+                    events.lookup.get_or_insert((start, end));
+                }
+            }
+            let addrs: Vec<SocketAddr> = lookup
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, 0))
+                .collect();
+            Ok::<Addrs, Box<dyn Error + Send + Sync>>(Box::new(addrs.into_iter()))
+        })
+    }
+}
+
+pub struct ConnectionTimer {}
+
+impl ConnectionTimer {
+    /// Runs one request/response exchange and times the connection it opened.
+    ///
+    /// reqwest reports no connection events, but the lookup, the connect and the TLS
+    /// handshake of a new connection all run in the task awaiting the response. After the
+    /// lookup, the first wake-up of that task is the socket connecting and the last one
+    /// before the response is the connection becoming ready. A pooled connection only
+    /// wakes the task for the response.
+    pub async fn measure<F: Future>(exchange: F, secure: bool) -> (F::Output, ConnectionPhases) {
+        let events = Arc::new(Mutex::new(ConnectionEvents::default()));
+        let start = Instant::now();
+        let output = EVENTS
+            .scope(
+                events.clone(),
+                RecordWakes {
+                    exchange: Box::pin(exchange),
+                    events: events.clone(),
+                },
+            )
+            .await;
+        let end = Instant::now();
+        let events = events.lock().map(|e| e.clone()).unwrap_or_default();
+        (output, Self::phases(&events, start, end, secure))
+    }
+
+    fn phases(
+        events: &ConnectionEvents,
+        start: Instant,
+        end: Instant,
+        secure: bool,
+    ) -> ConnectionPhases {
+        let mut phases = ConnectionPhases::default();
+        let connect_start = match events.lookup {
+            None => start,
+            Some((lookup_start, lookup_end)) => {
+                phases.dns = lookup_end - lookup_start;
+                lookup_end
+            }
+        };
+        let wakes: Vec<Instant> = events
+            .wakes
+            .iter()
+            .filter(|wake| **wake > connect_start && **wake <= end)
+            .cloned()
+            .collect();
+        if wakes.len() >= 2 {
+            phases.tcp = wakes[0] - connect_start;
+            if secure {
+                phases.tls = wakes[wakes.len() - 2] - wakes[0];
+            }
+        }
+        phases
+    }
+}
+
+struct RecordWakes<F> {
+    exchange: Pin<Box<F>>,
+    events: Arc<Mutex<ConnectionEvents>>,
+}
+
+impl<F: Future> Future for RecordWakes<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let waker = Waker::from(Arc::new(WakeRecorder {
+            events: self.events.clone(),
+            waker: cx.waker().clone(),
+        }));
+        self.exchange
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+    }
+}
+
+struct WakeRecorder {
+    events: Arc<Mutex<ConnectionEvents>>,
+    waker: Waker,
+}
+
+impl Wake for WakeRecorder {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Ok(mut events) = self.events.lock() {
+            events.wakes.push(Instant::now());
+        }
+        self.waker.wake_by_ref();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ConnectionEvents, ConnectionPhases, ConnectionTimer};
+
+    fn at(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn new_connection_phases_follow_the_lookup() {
+        let start = Instant::now();
+        let events = ConnectionEvents {
+            lookup: Some((at(start, 1), at(start, 11))),
+            wakes: vec![
+                at(start, 10),
+                at(start, 31),
+                at(start, 52),
+                at(start, 71),
+                at(start, 72),
+                at(start, 200),
+            ],
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(
+            ConnectionTimer::phases(&events, start, at(start, 200), true),
+            ConnectionPhases {
+                dns: ms(10),
+                tcp: ms(20),
+                tls: ms(41),
+            }
+        );
+        assert_eq!(
+            ConnectionTimer::phases(&events, start, at(start, 200), false),
+            ConnectionPhases {
+                dns: ms(10),
+                tcp: ms(20),
+                tls: ms(0),
+            }
+        );
+    }
+
+    #[test]
+    fn connection_to_an_address_has_no_lookup() {
+        let start = Instant::now();
+        let events = ConnectionEvents {
+            lookup: None,
+            wakes: vec![at(start, 5), at(start, 6), at(start, 50)],
+        };
+        assert_eq!(
+            ConnectionTimer::phases(&events, start, at(start, 50), false),
+            ConnectionPhases {
+                dns: Duration::ZERO,
+                tcp: Duration::from_millis(5),
+                tls: Duration::ZERO,
+            }
+        );
+    }
+
+    #[test]
+    fn reused_connection_reports_zero() {
+        let start = Instant::now();
+        let events = ConnectionEvents {
+            lookup: None,
+            wakes: vec![at(start, 40)],
+        };
+        assert_eq!(
+            ConnectionTimer::phases(&events, start, at(start, 40), true),
+            ConnectionPhases::default()
+        );
+    }
+
+    #[test]
+    fn measure_records_the_wakes_of_the_exchange() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let (output, phases) = runtime.block_on(ConnectionTimer::measure(
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                "response"
+            },
+            true,
+        ));
+        assert_eq!(output, "response");
+        assert_eq!(phases.dns, Duration::ZERO);
+        assert!(phases.tcp >= Duration::from_millis(20));
+        assert_eq!(phases.tls, Duration::ZERO);
+    }
+}
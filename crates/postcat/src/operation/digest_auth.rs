@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use md5::Md5;
+use rand::Rng;
+use reqwest::header::{HeaderMap, WWW_AUTHENTICATE};
+use reqwest::Request;
+use sha2::{Digest, Sha256};
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub qop: Vec<String>,
+    pub opaque: Option<String>,
+    pub algorithm: String,
+}
+
+impl DigestChallenge {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(Self::parse)
+            .next()
+    }
+
+    pub fn parse(header: &str) -> Option<Self> {
+        let header = header.trim();
+        match header.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("digest ") => {}
+            _ => return None,
+        }
+        let params = Self::parse_params(&header[7..]);
+        let algorithm = params
+            .get("algorithm")
+            .cloned()
+            .unwrap_or("MD5".to_string())
+            .to_uppercase();
+        if !["MD5", "MD5-SESS", "SHA-256", "SHA-256-SESS"].contains(&algorithm.as_str()) {
+            return None;
+        }
+        Some(DigestChallenge {
+            realm: params.get("realm").cloned().unwrap_or_default(),
+            nonce: params.get("nonce").cloned()?,
+            qop: params
+                .get("qop")
+                .map(|qop| {
+                    qop.split(',')
+                        .map(|q| q.trim().to_lowercase())
+                        .filter(|q| !q.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            opaque: params.get("opaque").cloned(),
+            algorithm,
+        })
+    }
+
+    pub fn authorization(&self, request: &Request, username: &str, password: &str) -> String {
+        let cnonce = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        self.authorization_with(request, username, password, cnonce.as_str())
+    }
+
+    fn authorization_with(
+        &self,
+        request: &Request,
+        username: &str,
+        password: &str,
+        cnonce: &str,
+    ) -> String {
+        let url = request.url();
+        let uri = match url.query() {
+            None => url.path().to_string(),
+            Some(query) => format!("{}?{}", url.path(), query),
+        };
+        let method = request.method().as_str();
+        let nc = "00000001";
+
+        let mut ha1 = self.hash(format!("{}:{}:{}", username, self.realm, password).as_bytes());
+        if self.algorithm.ends_with("-SESS") {
+            ha1 = self.hash(format!("{}:{}:{}", ha1, self.nonce, cnonce).as_bytes());
+        }
+        let qop = if self.qop.iter().any(|q| q == "auth") {
+            Some("auth")
+        } else if self.qop.iter().any(|q| q == "auth-int") {
+            Some("auth-int")
+        } else {
+            None
+        };
+        let ha2 = match qop {
+            Some("auth-int") => {
+                let body = request
+                    .body()
+                    .and_then(|body| body.as_bytes())
+                    .unwrap_or(&[]);
+                format!("{}:{}:{}", method, uri, self.hash(body))
+            }
+            _ => format!("{}:{}", method, uri),
+        };
+        let ha2 = self.hash(ha2.as_bytes());
+        let response = match qop {
+            None => self.hash(format!("{}:{}:{}", ha1, self.nonce, ha2).as_bytes()),
+            Some(qop) => self.hash(
+                format!("{}:{}:{}:{}:{}:{}", ha1, self.nonce, nc, cnonce, qop, ha2).as_bytes(),
+            ),
+        };
+
+        let mut authorization = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            username, self.realm, self.nonce, uri, self.algorithm, response
+        );
+        if let Some(qop) = qop {
+            authorization
+                .push_str(format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce).as_str());
+        }
+        if let Some(opaque) = &self.opaque {
+            authorization.push_str(format!(", opaque=\"{}\"", opaque).as_str());
+        }
+        authorization
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        let bytes = if self.algorithm.starts_with("SHA-256") {
+            Sha256::digest(data).to_vec()
+        } else {
+            Md5::digest(data).to_vec()
+        };
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn parse_params(content: &str) -> BTreeMap<String, String> {
+        let mut params = BTreeMap::new();
+        let mut chars = content.chars().peekable();
+        loop {
+            let key: String = chars
+                .by_ref()
+                .skip_while(|c| c.is_whitespace() || *c == ',')
+                .take_while(|c| *c != '=')
+                .collect();
+            if key.is_empty() {
+                break;
+            }
+            let mut value = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => value.extend(chars.next()),
+                        '"' => break,
+                        _ => value.push(c),
+                    }
+                }
+            } else {
+                value = chars
+                    .by_ref()
+                    .take_while(|c| *c != ',')
+                    .collect::<String>()
+                    .trim()
+                    .to_string();
+            }
+            params.insert(key.trim().to_lowercase(), value);
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Method, Request};
+    use url::Url;
+
+    use super::DigestChallenge;
+
+    fn get(url: &str) -> Request {
+        Request::new(Method::GET, Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parse_rfc2617_challenge() {
+        let challenge = DigestChallenge::parse(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .unwrap();
+        assert_eq!(
+            challenge,
+            DigestChallenge {
+                realm: "testrealm@host.com".to_string(),
+                nonce: "dcd98b7102dd2f0e8b11d0f600bfb0c093".to_string(),
+                qop: vec!["auth".to_string(), "auth-int".to_string()],
+                opaque: Some("5ccc069c403ebaf9f0171e9517f40e41".to_string()),
+                algorithm: "MD5".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_quoting_and_case() {
+        let challenge = DigestChallenge::parse(
+            "digest Realm=\"a \\\"quoted\\\", realm\", NONCE=abc , algorithm=sha-256",
+        )
+        .unwrap();
+        assert_eq!(challenge.realm, "a \"quoted\", realm");
+        assert_eq!(challenge.nonce, "abc");
+        assert_eq!(challenge.algorithm, "SHA-256");
+        assert!(challenge.qop.is_empty());
+        assert_eq!(challenge.opaque, None);
+    }
+
+    #[test]
+    fn parse_rejects_other_challenges() {
+        assert_eq!(DigestChallenge::parse("Basic realm=\"x\""), None);
+        assert_eq!(DigestChallenge::parse("Digest realm=\"x\""), None);
+        assert_eq!(
+            DigestChallenge::parse("Digest realm=\"x\", nonce=\"y\", algorithm=SHA-512"),
+            None
+        );
+    }
+
+    #[test]
+    fn rfc2617_authorization() {
+        let challenge = DigestChallenge::parse(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .unwrap();
+        let authorization = challenge.authorization_with(
+            &get("http://www.nowhere.org/dir/index.html"),
+            "Mufasa",
+            "Circle Of Life",
+            "0a4f113b",
+        );
+        assert_eq!(
+            authorization,
+            "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+             algorithm=MD5, response=\"6629fae49393a05397450978507c4ef1\", qop=auth, \
+             nc=00000001, cnonce=\"0a4f113b\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""
+        );
+    }
+
+    #[test]
+    fn rfc2069_authorization_without_qop() {
+        let challenge = DigestChallenge {
+            realm: "testrealm@host.com".to_string(),
+            nonce: "dcd98b7102dd2f0e8b11d0f600bfb0c093".to_string(),
+            algorithm: "MD5".to_string(),
+            ..Default::default()
+        };
+        let authorization = challenge.authorization_with(
+            &get("http://www.nowhere.org/dir/index.html"),
+            "Mufasa",
+            "Circle Of Life",
+            "0a4f113b",
+        );
+        assert!(authorization.contains("response=\"670fd8c2df070c60b045671b8b24ff02\""));
+        assert!(!authorization.contains("qop="));
+        assert!(!authorization.contains("cnonce="));
+    }
+
+    #[test]
+    fn rfc7616_authorization() {
+        let challenge = |algorithm: &str| {
+            DigestChallenge::parse(
+                format!(
+                    "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+                     algorithm={}, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+                     opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"",
+                    algorithm
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+        let request = get("http://www.example.org/dir/index.html");
+        let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+        let md5 = challenge("MD5").authorization_with(&request, "Mufasa", "Circle of Life", cnonce);
+        assert!(md5.contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+        let sha256 =
+            challenge("SHA-256").authorization_with(&request, "Mufasa", "Circle of Life", cnonce);
+        assert!(sha256.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ));
+        assert!(sha256.contains("algorithm=SHA-256"));
+        assert!(sha256.contains("opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\""));
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor, SerializeOptions,
+};
+use serde_json::Value;
+use tokio::runtime::Runtime;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{
+    AsciiMetadataKey, AsciiMetadataValue, BinaryMetadataKey, BinaryMetadataValue, MetadataMap,
+};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Status};
+use tonic_reflection::pb::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::ServerReflectionRequest;
+
+use crate::data::environment::EnvironmentItemValue;
+use crate::data::grpc::{GrpcRecord, GrpcSource};
+use crate::data::http;
+use crate::data::http::{Header, HttpBody, LockWith};
+use crate::data::logger::Logger;
+use crate::data::stream::ResponseStream;
+use crate::operation::rest_sender::RestSender;
+use crate::utils;
+
+const RESERVED_METADATA: [&str; 4] = ["content-type", "content-length", "te", "user-agent"];
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct GrpcSender {}
+
+impl GrpcSender {
+    pub fn send(
+        request: http::Request,
+        grpc: GrpcRecord,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        workspace_dir: PathBuf,
+        stream: ResponseStream,
+    ) -> Result<(http::Request, http::Response), String> {
+        Self::runtime()?.block_on(Self::send_async(request, grpc, envs, workspace_dir, stream))
+    }
+
+    async fn send_async(
+        request: http::Request,
+        grpc: GrpcRecord,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        workspace_dir: PathBuf,
+        stream: ResponseStream,
+    ) -> Result<(http::Request, http::Response), String> {
+        let channel = Self::connect(request.base_url.as_str()).await?;
+        let pool = match grpc.source {
+            GrpcSource::Reflection => Self::reflect(channel.clone(), &request.headers).await?,
+            GrpcSource::ProtoFile => {
+                Self::load_proto_file(grpc.proto_file.as_str(), workspace_dir)?
+            }
+        };
+        let method = Self::find_method(&pool, grpc.service.as_str(), grpc.method.as_str())?;
+        if method.is_client_streaming() {
+            return Err("Client streaming methods are not supported".to_string());
+        }
+        let mut message = utils::replace_variable(grpc.message.clone(), envs);
+        if message.trim().is_empty() {
+            message = "{}".to_string();
+        }
+        let input = Self::from_json(method.input(), message.as_str())?;
+        let mut grpc_request = tonic::Request::new(input);
+        Self::apply_metadata(grpc_request.metadata_mut(), &request.headers)?;
+        let path = PathAndQuery::from_str(
+            format!("/{}/{}", method.parent_service().full_name(), method.name()).as_str(),
+        )
+        .map_err(|e| e.to_string())?;
+        let codec = DynamicCodec {
+            output: method.output(),
+        };
+        let mut client = tonic::client::Grpc::new(channel);
+        client.ready().await.map_err(|e| e.to_string())?;
+        let start_time = Instant::now();
+        let response = if method.is_server_streaming() {
+            Self::server_streaming(&mut client, grpc_request, path, codec, &stream).await
+        } else {
+            match client.unary(grpc_request, path, codec).await {
+                Ok(response) => {
+                    let headers = Self::metadata_headers(response.metadata());
+                    let value = Self::to_value(response.get_ref())?;
+                    let body = serde_json::to_string_pretty(&value).unwrap_or_default();
+                    Self::build_response(Status::new(Code::Ok, ""), headers, body)
+                }
+                Err(status) => Self::status_response(status),
+            }
+        }?;
+        Ok((
+            request,
+            http::Response {
+                elapsed_time: start_time.elapsed().as_millis(),
+                ..response
+            },
+        ))
+    }
+
+    pub fn load_descriptor(
+        request: http::Request,
+        grpc: GrpcRecord,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        workspace_dir: PathBuf,
+    ) -> Result<DescriptorPool, String> {
+        match grpc.source {
+            GrpcSource::ProtoFile => Self::load_proto_file(grpc.proto_file.as_str(), workspace_dir),
+            GrpcSource::Reflection => {
+                let request = RestSender::build_request(request, envs);
+                Self::runtime()?.block_on(Self::connect_and_reflect(request))
+            }
+        }
+    }
+
+    pub fn find_method(
+        pool: &DescriptorPool,
+        service: &str,
+        method: &str,
+    ) -> Result<MethodDescriptor, String> {
+        pool.get_service_by_name(service)
+            .ok_or(format!("Service `{}` not found", service))?
+            .methods()
+            .find(|m| m.name() == method)
+            .ok_or(format!("Method `{}` not found", method))
+    }
+
+    pub fn message_template(message: MessageDescriptor) -> String {
+        Self::to_value(&DynamicMessage::new(message))
+            .map(|v| serde_json::to_string_pretty(&v).unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    fn load_proto_file(proto_file: &str, workspace_dir: PathBuf) -> Result<DescriptorPool, String> {
+        let mut path = PathBuf::from(proto_file);
+        if path.is_relative() {
+            path = workspace_dir.join(path);
+        }
+        let path = path
+            .canonicalize()
+            .map_err(|e| format!("Load `{}` failed: {}", proto_file, e))?;
+        let mut parser = protobuf_parse::Parser::new();
+        parser.pure();
+        if let Some(parent) = path.parent() {
+            parser.include(parent);
+        }
+        if let Ok(workspace_dir) = workspace_dir.canonicalize() {
+            parser.include(workspace_dir);
+        }
+        parser.input(Path::new(&path));
+        let file_descriptor_set = parser.file_descriptor_set().map_err(|e| e.to_string())?;
+        let bytes =
+            protobuf::Message::write_to_bytes(&file_descriptor_set).map_err(|e| e.to_string())?;
+        DescriptorPool::decode(bytes.as_slice()).map_err(|e| e.to_string())
+    }
+
+    async fn connect_and_reflect(request: http::Request) -> Result<DescriptorPool, String> {
+        let channel = Self::connect(request.base_url.as_str()).await?;
+        Self::reflect(channel, &request.headers).await
+    }
+
+    async fn connect(url: &str) -> Result<Channel, String> {
+        let mut endpoint = Endpoint::from_shared(url.to_string()).map_err(|e| e.to_string())?;
+        if url.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new())
+                .map_err(|e| e.to_string())?;
+        }
+        endpoint
+            .connect()
+            .await
+            .map_err(|e| format!("Connect `{}` failed: {}", url, e))
+    }
+
+    async fn reflect(channel: Channel, headers: &[Header]) -> Result<DescriptorPool, String> {
+        let mut client = ServerReflectionClient::new(channel);
+        let services = match Self::reflection_call(
+            &mut client,
+            headers,
+            MessageRequest::ListServices("".to_string()),
+        )
+        .await?
+        {
+            MessageResponse::ListServicesResponse(list) => list.service,
+            _ => return Err("Unexpected reflection response".to_string()),
+        };
+        let mut files: BTreeMap<String, prost_types::FileDescriptorProto> = BTreeMap::new();
+        for service in services
+            .iter()
+            .filter(|s| !s.name.starts_with("grpc.reflection."))
+        {
+            let request = MessageRequest::FileContainingSymbol(service.name.clone());
+            Self::reflection_files(&mut client, headers, request, &mut files).await?;
+        }
+        loop {
+            let missing: Vec<String> = files
+                .values()
+                .flat_map(|f| f.dependency.clone())
+                .filter(|d| !files.contains_key(d))
+                .collect();
+            if missing.is_empty() {
+                break;
+            }
+            for name in missing {
+                let request = MessageRequest::FileByFilename(name.clone());
+                Self::reflection_files(&mut client, headers, request, &mut files).await?;
+                if !files.contains_key(name.as_str()) {
+                    return Err(format!("Reflection did not return `{}`", name));
+                }
+            }
+        }
+        let mut pool = DescriptorPool::new();
+        pool.add_file_descriptor_protos(files.into_values())
+            .map_err(|e| e.to_string())?;
+        Ok(pool)
+    }
+
+    async fn reflection_files(
+        client: &mut ServerReflectionClient<Channel>,
+        headers: &[Header],
+        request: MessageRequest,
+        files: &mut BTreeMap<String, prost_types::FileDescriptorProto>,
+    ) -> Result<(), String> {
+        match Self::reflection_call(client, headers, request).await? {
+            MessageResponse::FileDescriptorResponse(response) => {
+                for bytes in response.file_descriptor_proto.iter() {
+                    let file = prost_types::FileDescriptorProto::decode(bytes.as_slice())
+                        .map_err(|e| e.to_string())?;
+                    files.insert(file.name().to_string(), file);
+                }
+                Ok(())
+            }
+            _ => Err("Unexpected reflection response".to_string()),
+        }
+    }
+
+    async fn reflection_call(
+        client: &mut ServerReflectionClient<Channel>,
+        headers: &[Header],
+        request: MessageRequest,
+    ) -> Result<MessageResponse, String> {
+        let mut reflection_request =
+            tonic::Request::new(tokio_stream::iter(vec![ServerReflectionRequest {
+                host: "".to_string(),
+                message_request: Some(request),
+            }]));
+        Self::apply_metadata(reflection_request.metadata_mut(), headers)?;
+        let mut response = client
+            .server_reflection_info(reflection_request)
+            .await
+            .map_err(|e| format!("Server reflection failed: {}", e.message()))?
+            .into_inner();
+        match response.message().await {
+            Ok(Some(message)) => match message.message_response {
+                Some(MessageResponse::ErrorResponse(error)) => Err(error.error_message),
+                Some(message_response) => Ok(message_response),
+                None => Err("Empty reflection response".to_string()),
+            },
+            Ok(None) => Err("Empty reflection response".to_string()),
+            Err(e) => Err(format!("Server reflection failed: {}", e.message())),
+        }
+    }
+
+    async fn server_streaming(
+        client: &mut tonic::client::Grpc<Channel>,
+        request: tonic::Request<DynamicMessage>,
+        path: PathAndQuery,
+        codec: DynamicCodec,
+        stream: &ResponseStream,
+    ) -> Result<http::Response, String> {
+        let response = match client.server_streaming(request, path, codec).await {
+            Ok(response) => response,
+            Err(status) => return Self::status_response(status),
+        };
+        let mut headers = Self::metadata_headers(response.metadata());
+        stream.start(0, "Ok".to_string(), headers.clone());
+        let mut streaming = response.into_inner();
+        let mut messages = vec![];
+        let mut status = Status::new(Code::Ok, "");
+        while !stream.is_stopped() && !stream.is_cancelled() {
+            match streaming.message().await {
+                Ok(Some(message)) => {
+                    let value = Self::to_value(&message)?;
+                    stream.push(format!("{}\n", value).as_bytes());
+                    messages.push(value);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    status = e;
+                    break;
+                }
+            }
+        }
+        if stream.is_stopped() {
+            status = Status::new(Code::Cancelled, "Stopped by user");
+        } else if status.code() == Code::Ok {
+            if let Ok(Some(trailers)) = streaming.trailers().await {
+                headers.append(&mut Self::metadata_headers(&trailers));
+            }
+        } else {
+            headers.append(&mut Self::metadata_headers(status.metadata()));
+        }
+        let body = serde_json::to_string_pretty(&Value::Array(messages)).unwrap_or_default();
+        Self::build_response(status, headers, body)
+    }
+
+    fn status_response(status: Status) -> Result<http::Response, String> {
+        let headers = Self::metadata_headers(status.metadata());
+        let body = status.message().to_string();
+        Self::build_response(status, headers, body)
+    }
+
+    fn build_response(
+        status: Status,
+        mut headers: Vec<Header>,
+        body: String,
+    ) -> Result<http::Response, String> {
+        headers.retain(|h| h.key != "grpc-status" && h.key != "grpc-message");
+        headers.push(Self::header(
+            "grpc-status",
+            (status.code() as i32).to_string(),
+        ));
+        if !status.message().is_empty() {
+            headers.push(Self::header("grpc-message", status.message().to_string()));
+        }
+        if !headers.iter().any(|h| h.key == "content-type") {
+            headers.push(Self::header("content-type", "application/grpc".to_string()));
+        }
+        Ok(http::Response {
+            headers,
+            status: status.code() as u16,
+            status_text: format!("{:?}", status.code()),
+            elapsed_time: 0,
+            logger: Logger::default(),
+            body: Arc::new(HttpBody::new(body.into_bytes())),
+            ..Default::default()
+        })
+    }
+
+    fn apply_metadata(metadata: &mut MetadataMap, headers: &[Header]) -> Result<(), String> {
+        for header in headers.iter().filter(|h| h.enable && !h.key.is_empty()) {
+            let key = header.key.to_lowercase();
+            if RESERVED_METADATA.contains(&key.as_str()) {
+                continue;
+            }
+            if key.ends_with("-bin") {
+                let name = BinaryMetadataKey::from_bytes(key.as_bytes())
+                    .map_err(|_| format!("Invalid metadata key `{}`", header.key))?;
+                metadata.append_bin(
+                    name,
+                    BinaryMetadataValue::from_bytes(header.value.as_bytes()),
+                );
+            } else {
+                let name = AsciiMetadataKey::from_bytes(key.as_bytes())
+                    .map_err(|_| format!("Invalid metadata key `{}`", header.key))?;
+                let value = AsciiMetadataValue::from_str(header.value.as_str())
+                    .map_err(|_| format!("Invalid metadata value of `{}`", header.key))?;
+                metadata.append(name, value);
+            }
+        }
+        Ok(())
+    }
+
+    fn header(key: &str, value: String) -> Header {
+        Header {
+            key: key.to_string(),
+            value,
+            desc: "".to_string(),
+            enable: true,
+            lock_with: LockWith::NoLock,
+        }
+    }
+
+    fn metadata_headers(metadata: &MetadataMap) -> Vec<Header> {
+        Header::new_from_map(&metadata.clone().into_headers())
+    }
+
+    fn from_json(message: MessageDescriptor, json: &str) -> Result<DynamicMessage, String> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let dynamic_message = DynamicMessage::deserialize(message, &mut deserializer)
+            .map_err(|e| format!("Invalid message: {}", e))?;
+        deserializer
+            .end()
+            .map_err(|e| format!("Invalid message: {}", e))?;
+        Ok(dynamic_message)
+    }
+
+    fn to_value(message: &DynamicMessage) -> Result<Value, String> {
+        message
+            .serialize_with_options(
+                serde_json::value::Serializer,
+                &SerializeOptions::new().skip_default_fields(false),
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    fn runtime() -> Result<Runtime, String> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+struct DynamicEncoder {}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder {}
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
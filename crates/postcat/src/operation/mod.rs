@@ -2,7 +2,16 @@ use std::str::FromStr;
 
 use crate::operation::windows::Window;
 
+pub mod aws_signer;
+pub mod code_generator;
+pub mod collection_runner;
+pub mod connection_timer;
+pub mod digest_auth;
 mod git;
+pub mod grpc_sender;
+pub mod oauth2_client;
 pub mod operation;
+pub mod request_signer;
 pub mod rest_sender;
+pub mod websocket_client;
 pub mod windows;
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::header::ACCEPT;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::data::auth::{Auth, AuthType};
+use crate::data::environment::EnvironmentItemValue;
+use crate::data::http;
+use crate::data::logger::Logger;
+use crate::data::network_settings::ClientConfig;
+use crate::data::oauth2::{OAuth2, OAuth2ClientAuthentication, OAuth2GrantType, OAuth2Token};
+use crate::operation::rest_sender::RestSender;
+use crate::utils;
+
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct OAuth2Client {}
+
+impl OAuth2Client {
+    pub fn prepare_request(
+        request: &mut http::Request,
+        parent_auth: Auth,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+        logger: &mut Logger,
+    ) -> Result<(), String> {
+        let auth = request.auth.get_final_auth(parent_auth.clone());
+        if auth.auth_type != AuthType::OAuth2 {
+            return Ok(());
+        }
+        let oauth2 = auth.oauth2.with_cache_key(envs);
+        if Self::ensure_token(&oauth2, envs, client_config)? {
+            logger.add_info("oauth2".to_string(), "access token updated".to_string());
+        }
+        request
+            .auth
+            .build_head(&mut request.headers, envs.clone(), parent_auth);
+        Ok(())
+    }
+
+    /// Returns true when a new token was fetched.
+    pub fn ensure_token(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+    ) -> Result<bool, String> {
+        match oauth2.get_token() {
+            Some(token) if !token.is_expired() => Ok(false),
+            Some(token) if !token.refresh_token.is_empty() => {
+                match Self::refresh_token(oauth2, envs, client_config, token.refresh_token.clone())
+                {
+                    Ok(new_token) => {
+                        oauth2.set_token(new_token);
+                        Ok(true)
+                    }
+                    Err(e) => {
+                        oauth2.clear_token();
+                        Self::fetch_without_user(oauth2, envs, client_config).map_err(|_| e)
+                    }
+                }
+            }
+            _ => Self::fetch_without_user(oauth2, envs, client_config),
+        }
+    }
+
+    pub fn fetch_token(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+    ) -> Result<OAuth2Token, String> {
+        match oauth2.grant_type {
+            OAuth2GrantType::ClientCredentials => Self::token_request(
+                oauth2,
+                envs,
+                client_config,
+                vec![("grant_type".to_string(), "client_credentials".to_string())],
+            ),
+            OAuth2GrantType::Password => Self::token_request(
+                oauth2,
+                envs,
+                client_config,
+                vec![
+                    ("grant_type".to_string(), "password".to_string()),
+                    (
+                        "username".to_string(),
+                        utils::replace_variable(oauth2.username.clone(), envs.clone()),
+                    ),
+                    (
+                        "password".to_string(),
+                        utils::replace_variable(oauth2.password.clone(), envs.clone()),
+                    ),
+                ],
+            ),
+            OAuth2GrantType::AuthorizationCodeWithPKCE => {
+                Self::authorize_with_pkce(oauth2, envs, client_config)
+            }
+        }
+    }
+
+    fn fetch_without_user(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+    ) -> Result<bool, String> {
+        if oauth2.grant_type == OAuth2GrantType::AuthorizationCodeWithPKCE {
+            return Err(
+                "No valid OAuth 2.0 token, get a new access token in the Authorization tab"
+                    .to_string(),
+            );
+        }
+        let token = Self::fetch_token(oauth2, envs, client_config)?;
+        oauth2.set_token(token);
+        Ok(true)
+    }
+
+    fn refresh_token(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+        refresh_token: String,
+    ) -> Result<OAuth2Token, String> {
+        let mut token = Self::token_request(
+            oauth2,
+            envs,
+            client_config,
+            vec![
+                ("grant_type".to_string(), "refresh_token".to_string()),
+                ("refresh_token".to_string(), refresh_token.clone()),
+            ],
+        )?;
+        if token.refresh_token.is_empty() {
+            token.refresh_token = refresh_token;
+        }
+        Ok(token)
+    }
+
+    fn token_request(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+        mut params: Vec<(String, String)>,
+    ) -> Result<OAuth2Token, String> {
+        let url = utils::replace_variable(oauth2.access_token_url.clone(), envs.clone());
+        let client_id = utils::replace_variable(oauth2.client_id.clone(), envs.clone());
+        let client_secret = utils::replace_variable(oauth2.client_secret.clone(), envs.clone());
+        let scope = utils::replace_variable(oauth2.scope.clone(), envs.clone());
+        if !scope.is_empty() {
+            params.push(("scope".to_string(), scope));
+        }
+        let mut builder = client_config
+            .build_client(url.as_str())?
+            .post(url)
+            .timeout(TOKEN_REQUEST_TIMEOUT)
+            .header(ACCEPT, "application/json");
+        match oauth2.client_authentication {
+            OAuth2ClientAuthentication::BasicAuthHeader => {
+                builder = builder.basic_auth(client_id, Some(client_secret));
+            }
+            OAuth2ClientAuthentication::ClientCredentialsInBody => {
+                params.push(("client_id".to_string(), client_id));
+                if !client_secret.is_empty() {
+                    params.push(("client_secret".to_string(), client_secret));
+                }
+            }
+        }
+        let (status, text) = RestSender::runtime()?.block_on(async {
+            let response = builder
+                .form(&params)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = response.status();
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            Ok::<_, String>((status, text))
+        })?;
+        match serde_json::from_str::<Value>(text.as_str()) {
+            Ok(value) => OAuth2Token::from_json(&value),
+            Err(_) if !status.is_success() => Err(format!("{} {}", status, text)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn authorize_with_pkce(
+        oauth2: &OAuth2,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+        client_config: &ClientConfig,
+    ) -> Result<OAuth2Token, String> {
+        let redirect_url = utils::replace_variable(oauth2.get_redirect_url(), envs.clone());
+        let redirect = Url::parse(redirect_url.as_str()).map_err(|e| e.to_string())?;
+        let host = redirect.host_str().unwrap_or("127.0.0.1").to_string();
+        if host != "127.0.0.1" && host != "localhost" {
+            return Err("Redirect URL must be a loopback address".to_string());
+        }
+        let port = redirect.port_or_known_default().unwrap_or(80);
+        let listener = TcpListener::bind((host.as_str(), port)).map_err(|e| e.to_string())?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        let code_verifier = Self::random_string(64);
+        let code_challenge =
+            general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+        let state = Self::random_string(16);
+        let mut auth_url =
+            Url::parse(utils::replace_variable(oauth2.auth_url.clone(), envs.clone()).as_str())
+                .map_err(|e| e.to_string())?;
+        auth_url
+            .query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair(
+                "client_id",
+                utils::replace_variable(oauth2.client_id.clone(), envs.clone()).as_str(),
+            )
+            .append_pair("redirect_uri", redirect_url.as_str())
+            .append_pair(
+                "scope",
+                utils::replace_variable(oauth2.scope.clone(), envs.clone()).as_str(),
+            )
+            .append_pair("state", state.as_str())
+            .append_pair("code_challenge", code_challenge.as_str())
+            .append_pair("code_challenge_method", "S256");
+        open::that(auth_url.as_str())
+            .map_err(|e| format!("Open browser failed: {}, url: {}", e, auth_url))?;
+
+        let callback = Self::wait_callback(&listener, redirect.path())?;
+        let query: BTreeMap<String, String> = callback.query_pairs().into_owned().collect();
+        if let Some(error) = query.get("error") {
+            return Err(format!(
+                "{} {}",
+                error,
+                query.get("error_description").cloned().unwrap_or_default()
+            )
+            .trim()
+            .to_string());
+        }
+        if query.get("state") != Some(&state) {
+            return Err("Authorization state mismatch".to_string());
+        }
+        let code = query
+            .get("code")
+            .cloned()
+            .ok_or("Authorization code is missing".to_string())?;
+        Self::token_request(
+            oauth2,
+            envs,
+            client_config,
+            vec![
+                ("grant_type".to_string(), "authorization_code".to_string()),
+                ("code".to_string(), code),
+                ("redirect_uri".to_string(), redirect_url),
+                ("code_verifier".to_string(), code_verifier),
+            ],
+        )
+    }
+
+    fn wait_callback(listener: &TcpListener, path: &str) -> Result<Url, String> {
+        let started = Instant::now();
+        while started.elapsed() < AUTHORIZATION_TIMEOUT {
+            match listener.accept() {
+                Ok((mut stream, _)) => {
+                    stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+                    let mut request_line = String::new();
+                    BufReader::new(&stream)
+                        .read_line(&mut request_line)
+                        .map_err(|e| e.to_string())?;
+                    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+                    let url = Url::parse(format!("http://127.0.0.1{}", target).as_str())
+                        .map_err(|e| e.to_string())?;
+                    if url.path() != path {
+                        let _ = stream
+                            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+                        continue;
+                    }
+                    let body = "<html><body>Authorization finished, you can close this window and return to Postcat.</body></html>";
+                    let _ = stream.write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        )
+                        .as_bytes(),
+                    );
+                    return Ok(url);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Err("Authorization timed out".to_string())
+    }
+
+    fn random_string(len: usize) -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(len)
+            .map(char::from)
+            .collect()
+    }
+}
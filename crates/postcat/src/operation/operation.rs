@@ -1,19 +1,29 @@
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use eframe::emath::Align2;
 use egui::WidgetText;
 use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
 use poll_promise::Promise;
-use reqwest::blocking::Client;
+use reqwest::Client;
+use reqwest_cookie_store::CookieStoreMutex;
 
+use crate::data::auth::Auth;
 use crate::data::config_data::ConfigData;
 use crate::data::environment::EnvironmentItemValue;
+use crate::data::grpc::GrpcRecord;
 use crate::data::logger::Logger;
+use crate::data::network_settings::{ClientConfig, RequestSettings};
+use crate::data::stream::ResponseStream;
 use crate::data::workspace_data::WorkspaceData;
 use crate::data::{http, test};
 use crate::operation::git::Git;
+use crate::operation::grpc_sender::GrpcSender;
+use crate::operation::oauth2_client::OAuth2Client;
 use crate::operation::rest_sender::RestSender;
 use crate::operation::windows::{Window, Windows};
 use crate::script::script::{Context, JsResponse, ScriptRuntime, ScriptScope};
@@ -72,76 +82,141 @@ impl Operation {
         &self,
         request: http::Request,
         envs: BTreeMap<String, EnvironmentItemValue>,
+        parent_auth: Auth,
+        client_config: ClientConfig,
         pre_request_scripts: Vec<ScriptScope>,
         test_scripts: Vec<ScriptScope>,
         client: Client,
+        settings: RequestSettings,
+        cookie_store: Arc<CookieStoreMutex>,
+        stream: ResponseStream,
+    ) -> Promise<Result<(http::Request, http::Response, test::TestResult), String>> {
+        let workspace_dir = client_config.workspace_dir.clone();
+        Self::send_with_script_by(
+            request,
+            envs,
+            parent_auth,
+            client_config,
+            pre_request_scripts,
+            test_scripts,
+            stream.clone(),
+            move |request, _| {
+                RestSender::reqwest_block_send_stream(
+                    request,
+                    client,
+                    settings,
+                    cookie_store,
+                    workspace_dir,
+                    stream,
+                )
+            },
+        )
+    }
+
+    pub fn send_grpc_with_script(
+        &self,
+        request: http::Request,
+        grpc: GrpcRecord,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        parent_auth: Auth,
+        client_config: ClientConfig,
+        pre_request_scripts: Vec<ScriptScope>,
+        test_scripts: Vec<ScriptScope>,
+        workspace_dir: PathBuf,
+        stream: ResponseStream,
+    ) -> Promise<Result<(http::Request, http::Response, test::TestResult), String>> {
+        Self::send_with_script_by(
+            request,
+            envs,
+            parent_auth,
+            client_config,
+            pre_request_scripts,
+            test_scripts,
+            stream.clone(),
+            move |request, envs| GrpcSender::send(request, grpc, envs, workspace_dir, stream),
+        )
+    }
+
+    fn send_with_script_by(
+        request: http::Request,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        parent_auth: Auth,
+        client_config: ClientConfig,
+        pre_request_scripts: Vec<ScriptScope>,
+        test_scripts: Vec<ScriptScope>,
+        stream: ResponseStream,
+        send: impl FnOnce(
+                http::Request,
+                BTreeMap<String, EnvironmentItemValue>,
+            ) -> Result<(http::Request, http::Response), String>
+            + Send
+            + 'static,
     ) -> Promise<Result<(http::Request, http::Response, test::TestResult), String>> {
         let mut logger = Logger::default();
         Promise::spawn_thread("send_with_script", move || {
-            let mut pre_request_context_result = Ok(Context {
+            let mut request = request;
+            OAuth2Client::prepare_request(
+                &mut request,
+                parent_auth.clone(),
+                &envs,
+                &client_config,
+                &mut logger,
+            )?;
+            request.auth = request.auth.get_final_auth(parent_auth);
+            let cancel = stream.cancel_flag();
+            let mut pre_request_context = Context {
                 scope_name: "".to_string(),
                 request: request.clone(),
                 envs: envs.clone(),
+                proxy: client_config.proxy.clone(),
+                cancel: cancel.clone(),
                 ..Default::default()
-            });
+            };
             if pre_request_scripts.len() > 0 {
-                pre_request_context_result = ScriptRuntime::run_block_many(
-                    pre_request_scripts,
-                    Context {
-                        scope_name: "".to_string(),
-                        request: request.clone(),
-                        envs: envs.clone(),
-                        ..Default::default()
-                    },
-                );
+                let (context, result) =
+                    ScriptRuntime::run_block_many_partial(pre_request_scripts, pre_request_context);
+                stream.set_variable_changes(context.variable_changes.clone());
+                result.map_err(|e| e.to_string())?;
+                pre_request_context = context;
+            }
+            for log in pre_request_context.logger.logs.iter() {
+                logger.logs.push(log.clone());
+            }
+            if cancel.load(Ordering::Relaxed) {
+                return Err("request cancelled".to_string());
+            }
+            let build_request = RestSender::build_request(
+                pre_request_context.request.clone(),
+                pre_request_context.envs.clone(),
+            );
+            logger.add_info(
+                "fetch".to_string(),
+                format!("start fetch request: {:?}", build_request),
+            );
+            let (after_request, mut after_response) =
+                send(build_request, pre_request_context.envs.clone())?;
+            for log in after_response.logger.logs.iter() {
+                logger.logs.push(log.clone());
             }
-            match pre_request_context_result {
-                Ok(pre_request_context) => {
-                    for log in pre_request_context.logger.logs.iter() {
-                        logger.logs.push(log.clone());
-                    }
-                    let build_request = RestSender::build_request(
-                        pre_request_context.request.clone(),
-                        pre_request_context.envs.clone(),
-                    );
-                    logger.add_info(
-                        "fetch".to_string(),
-                        format!("start fetch request: {:?}", build_request),
-                    );
-                    match RestSender::reqwest_block_send(build_request, client) {
-                        Ok((after_request, response)) => {
-                            let mut after_response = response;
-                            logger.add_info(
-                                "fetch".to_string(),
-                                format!("get response: {:?}", after_response),
-                            );
-                            after_response.logger = logger;
-                            let mut test_result: test::TestResult = Default::default();
-                            let mut test_context = pre_request_context.clone();
-                            test_context.response =
-                                JsResponse::from_data_response(after_response.clone());
-                            if test_scripts.len() > 0 {
-                                pre_request_context_result =
-                                    ScriptRuntime::run_block_many(test_scripts, test_context);
-                                match pre_request_context_result {
-                                    Ok(test_context) => {
-                                        for log in test_context.logger.logs.iter() {
-                                            after_response.logger.logs.push(log.clone());
-                                        }
-                                        test_result = test_context.test_result.clone();
-                                    }
-                                    Err(e) => {
-                                        return Err(e.to_string());
-                                    }
-                                }
-                            }
-                            Ok((after_request, after_response, test_result))
-                        }
-                        Err(e) => Err(e.to_string()),
-                    }
+            logger.add_info(
+                "fetch".to_string(),
+                format!("get response: {:?}", after_response),
+            );
+            after_response.logger = logger;
+            let mut test_result: test::TestResult = Default::default();
+            if test_scripts.len() > 0 {
+                let mut test_context = pre_request_context.clone();
+                test_context.response = JsResponse::from_data_response(after_response.clone());
+                let (test_context, result) =
+                    ScriptRuntime::run_block_many_partial(test_scripts, test_context);
+                stream.set_variable_changes(test_context.variable_changes.clone());
+                result.map_err(|e| e.to_string())?;
+                for log in test_context.logger.logs.iter() {
+                    after_response.logger.logs.push(log.clone());
                 }
-                Err(e) => Err(e.to_string()),
+                test_result = test_context.test_result.clone();
             }
+            Ok((after_request, after_response, test_result))
         })
     }
 
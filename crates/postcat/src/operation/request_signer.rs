@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Request;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::data::signing::{HmacAlgorithm, HmacAuth, JwtAlgorithm, JwtAuth, SignatureEncoding};
+
+pub struct RequestSigner {}
+
+impl RequestSigner {
+    pub fn sign_jwt(
+        request: &mut Request,
+        jwt: &JwtAuth,
+        workspace_dir: &PathBuf,
+    ) -> Result<(), String> {
+        let key = jwt.key_source.load(jwt.key.as_str(), workspace_dir)?;
+        let (algorithm, encoding_key) = match jwt.algorithm {
+            JwtAlgorithm::HS256 => (Algorithm::HS256, EncodingKey::from_secret(key.as_slice())),
+            JwtAlgorithm::RS256 => (
+                Algorithm::RS256,
+                EncodingKey::from_rsa_pem(key.as_slice()).map_err(|e| e.to_string())?,
+            ),
+            JwtAlgorithm::ES256 => (
+                Algorithm::ES256,
+                EncodingKey::from_ec_pem(key.as_slice()).map_err(|e| e.to_string())?,
+            ),
+        };
+        let mut claims = match jwt.claims.trim() {
+            "" => Map::new(),
+            claims => match serde_json::from_str::<Value>(claims) {
+                Ok(Value::Object(map)) => map,
+                Ok(_) => return Err("JWT claims must be a JSON object".to_string()),
+                Err(e) => return Err(format!("JWT claims: {}", e)),
+            },
+        };
+        let now = Utc::now().timestamp();
+        claims.entry("iat").or_insert(Value::from(now));
+        if jwt.exp_offset > 0 {
+            claims.insert("exp".to_string(), Value::from(now + jwt.exp_offset));
+        }
+        let token = jsonwebtoken::encode(&Header::new(algorithm), &claims, &encoding_key)
+            .map_err(|e| e.to_string())?;
+        Self::insert_header(
+            request,
+            jwt.header_name.as_str(),
+            format!("{}{}", jwt.header_prefix, token).as_str(),
+        )
+    }
+
+    pub fn sign_hmac(
+        request: &mut Request,
+        hmac: &HmacAuth,
+        workspace_dir: &PathBuf,
+    ) -> Result<(), String> {
+        let key = hmac.key_source.load(hmac.key.as_str(), workspace_dir)?;
+        let timestamp = Utc::now().timestamp().to_string();
+        let mut parts = vec![];
+        if hmac.include_method {
+            parts.push(request.method().to_string());
+        }
+        if hmac.include_path {
+            let url = request.url();
+            parts.push(match url.query() {
+                None => url.path().to_string(),
+                Some(query) => format!("{}?{}", url.path(), query),
+            });
+        }
+        if hmac.include_timestamp {
+            parts.push(timestamp.clone());
+        }
+        if hmac.include_body_hash {
+            let body = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or(&[]);
+            parts.push(
+                Sha256::digest(body)
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            );
+        }
+        let content = parts.join("\n");
+        let signature = match hmac.algorithm {
+            HmacAlgorithm::SHA256 => {
+                let mut mac =
+                    Hmac::<Sha256>::new_from_slice(key.as_slice()).map_err(|e| e.to_string())?;
+                mac.update(content.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            HmacAlgorithm::SHA512 => {
+                let mut mac =
+                    Hmac::<Sha512>::new_from_slice(key.as_slice()).map_err(|e| e.to_string())?;
+                mac.update(content.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+        let signature = match hmac.encoding {
+            SignatureEncoding::Hex => signature.iter().map(|b| format!("{:02x}", b)).collect(),
+            SignatureEncoding::Base64 => general_purpose::STANDARD.encode(signature),
+        };
+        if hmac.include_timestamp && !hmac.timestamp_header_name.is_empty() {
+            Self::insert_header(
+                request,
+                hmac.timestamp_header_name.as_str(),
+                timestamp.as_str(),
+            )?;
+        }
+        Self::insert_header(
+            request,
+            hmac.header_name.as_str(),
+            format!("{}{}", hmac.header_prefix, signature).as_str(),
+        )
+    }
+
+    fn insert_header(request: &mut Request, name: &str, value: &str) -> Result<(), String> {
+        request.headers_mut().insert(
+            HeaderName::from_bytes(name.trim().as_bytes()).map_err(|e| e.to_string())?,
+            HeaderValue::from_str(value).map_err(|e| e.to_string())?,
+        );
+        Ok(())
+    }
+}
@@ -1,19 +1,32 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-use reqwest::blocking::{multipart, Client};
-use reqwest::header::CONTENT_TYPE;
-use reqwest::Method;
+use reqwest::cookie::CookieStore;
+use reqwest::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, LOCATION,
+    PROXY_AUTHORIZATION, TRANSFER_ENCODING,
+};
+use reqwest::{Client, Method, StatusCode};
+use reqwest_cookie_store::CookieStoreMutex;
+use tokio::runtime::Runtime;
 
+use crate::data::auth::{AuthType, API_KEY_DESC};
 use crate::data::environment::EnvironmentItemValue;
 use crate::data::http;
-use crate::data::http::{BodyRawType, BodyType, Header, HttpBody, LockWith, MultipartDataType};
+use crate::data::http::{BodyRawType, BodyType, Header, HttpBody, LockWith};
 use crate::data::logger::Logger;
+use crate::data::network_settings::RequestSettings;
+use crate::data::stream::ResponseStream;
+use crate::operation::aws_signer::AwsSigner;
+use crate::operation::connection_timer::{ConnectionPhases, ConnectionTimer};
+use crate::operation::digest_auth::DigestChallenge;
+use crate::operation::request_signer::RequestSigner;
 use crate::utils;
 
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
@@ -23,8 +36,89 @@ impl RestSender {
     pub fn reqwest_block_send(
         request: http::Request,
         client: Client,
-    ) -> reqwest::Result<(http::Request, http::Response)> {
-        let reqwest_request = Self::build_reqwest_request(request.clone())?;
+        settings: RequestSettings,
+        cookie_store: Arc<CookieStoreMutex>,
+        workspace_dir: PathBuf,
+    ) -> Result<(http::Request, http::Response), String> {
+        Self::reqwest_block_send_stream(
+            request,
+            client,
+            settings,
+            cookie_store,
+            workspace_dir,
+            ResponseStream::default(),
+        )
+    }
+
+    /// Drops the in-flight request, closing its connection, as soon as the stream is cancelled.
+    pub fn reqwest_block_send_stream(
+        request: http::Request,
+        client: Client,
+        settings: RequestSettings,
+        cookie_store: Arc<CookieStoreMutex>,
+        workspace_dir: PathBuf,
+        stream: ResponseStream,
+    ) -> Result<(http::Request, http::Response), String> {
+        let cancel = stream.cancel_flag();
+        Self::runtime()?.block_on(async move {
+            let send = Self::send_async(
+                request,
+                client,
+                settings,
+                cookie_store,
+                workspace_dir,
+                stream,
+            );
+            tokio::pin!(send);
+            loop {
+                tokio::select! {
+                    result = &mut send => break result,
+                    _ = tokio::time::sleep(Duration::from_millis(50)) => {
+                        if cancel.load(Ordering::Relaxed) {
+                            break Err("request cancelled".to_string());
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Clients are cached in `WorkspaceData`, so every send runs on one runtime that
+    /// keeps their pooled connections alive.
+    pub(crate) fn runtime() -> Result<&'static Runtime, String> {
+        static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+        if let Some(runtime) = RUNTIME.get() {
+            return Ok(runtime);
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("rest_sender")
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(RUNTIME.get_or_init(|| runtime))
+    }
+
+    async fn send_async(
+        request: http::Request,
+        client: Client,
+        settings: RequestSettings,
+        cookie_store: Arc<CookieStoreMutex>,
+        workspace_dir: PathBuf,
+        stream: ResponseStream,
+    ) -> Result<(http::Request, http::Response), String> {
+        let mut reqwest_request = Self::build_reqwest_request(request.clone())?;
+        Self::merge_cookies(&request, &mut reqwest_request, cookie_store.as_ref())?;
+        match request.auth.auth_type {
+            AuthType::AwsSigV4 => AwsSigner::sign(&mut reqwest_request, &request.auth.aws)?,
+            AuthType::Jwt => {
+                RequestSigner::sign_jwt(&mut reqwest_request, &request.auth.jwt, &workspace_dir)?
+            }
+            AuthType::HmacSignature => {
+                RequestSigner::sign_hmac(&mut reqwest_request, &request.auth.hmac, &workspace_dir)?
+            }
+            _ => {}
+        }
         let mut new_request = request.clone();
         for (hn, hv) in reqwest_request.headers().iter() {
             if new_request
@@ -45,63 +139,341 @@ impl RestSender {
                 })
             }
         }
+        let mut logger = Logger::default();
+        let mut redirects = vec![];
+        let (request_headers, request_body) = Self::request_size(&reqwest_request);
         let start_time = Instant::now();
-        let reqwest_response = client.execute(reqwest_request)?;
+        let mut connection = None;
+        let mut reqwest_response = Self::execute(
+            &client,
+            reqwest_request,
+            &settings,
+            &mut redirects,
+            &mut connection,
+            &mut logger,
+        )
+        .await?;
+        if request.auth.auth_type == AuthType::DigestAuth
+            && reqwest_response.status() == StatusCode::UNAUTHORIZED
+        {
+            match DigestChallenge::from_headers(reqwest_response.headers()) {
+                None => logger.add_warn(
+                    "digest".to_string(),
+                    "no Digest challenge found in the 401 response".to_string(),
+                ),
+                Some(challenge) => {
+                    logger.add_info(
+                        "digest".to_string(),
+                        format!(
+                            "first response: {}, challenge: {:?}",
+                            reqwest_response.status(),
+                            challenge
+                        ),
+                    );
+                    let mut retry_request = Self::build_reqwest_request(request.clone())?;
+                    Self::merge_cookies(&request, &mut retry_request, cookie_store.as_ref())?;
+                    let authorization = challenge.authorization(
+                        &retry_request,
+                        request.auth.digest.username.as_str(),
+                        request.auth.digest.password.as_str(),
+                    );
+                    retry_request.headers_mut().insert(
+                        AUTHORIZATION,
+                        HeaderValue::from_str(authorization.as_str()).map_err(|e| e.to_string())?,
+                    );
+                    logger.add_info(
+                        "digest".to_string(),
+                        format!("retry with authorization: {}", authorization),
+                    );
+                    new_request.headers.push(Header {
+                        key: AUTHORIZATION.to_string(),
+                        value: authorization,
+                        desc: "auto gen".to_string(),
+                        enable: true,
+                        lock_with: LockWith::LockWithAuto,
+                    });
+                    redirects.clear();
+                    reqwest_response = Self::execute(
+                        &client,
+                        retry_request,
+                        &settings,
+                        &mut redirects,
+                        &mut connection,
+                        &mut logger,
+                    )
+                    .await?;
+                    logger.add_info(
+                        "digest".to_string(),
+                        format!("second response: {}", reqwest_response.status()),
+                    );
+                }
+            }
+        }
+        let first_byte_time = start_time.elapsed();
+        let headers = Header::new_from_map(reqwest_response.headers());
+        let status = reqwest_response.status();
+        let response_headers = Self::response_header_size(&reqwest_response);
+        stream.start(status.as_u16(), status.to_string(), headers.clone());
+        let mut bytes = vec![];
+        while !stream.is_stopped() {
+            let chunk = tokio::select! {
+                chunk = reqwest_response.chunk() => chunk,
+                _ = tokio::time::sleep(Duration::from_millis(50)) => continue,
+            };
+            match chunk {
+                Ok(None) => break,
+                Ok(Some(chunk)) => {
+                    bytes.extend_from_slice(&chunk);
+                    stream.push(&chunk);
+                }
+                Err(e) => {
+                    logger.add_error(
+                        "fetch".to_string(),
+                        format!("read body failed after {} bytes: {}", bytes.len(), e),
+                    );
+                    break;
+                }
+            }
+        }
         let total_time = start_time.elapsed();
+        let connection = connection.unwrap_or_default();
+        let timing = http::Timing {
+            dns: connection.dns.as_micros(),
+            tcp: connection.tcp.as_micros(),
+            tls: connection.tls.as_micros(),
+            ttfb: first_byte_time
+                .saturating_sub(connection.dns + connection.tcp + connection.tls)
+                .as_micros(),
+            download: (total_time - first_byte_time).as_micros(),
+            total: total_time.as_micros(),
+        };
         Ok((
             new_request,
             http::Response {
-                headers: Header::new_from_map(reqwest_response.headers()),
-                status: reqwest_response.status().as_u16(),
-                status_text: reqwest_response.status().to_string(),
+                headers,
+                status: status.as_u16(),
+                status_text: status.to_string(),
                 elapsed_time: total_time.as_millis(),
-                logger: Logger::default(),
-                body: Arc::new(HttpBody::new(reqwest_response.bytes()?.to_vec())),
+                redirects,
+                timing,
+                size: http::TransferSize {
+                    request_headers,
+                    request_body,
+                    response_headers,
+                    response_body: bytes.len() as u64,
+                },
+                logger,
+                body: Arc::new(HttpBody::new(bytes)),
+                ..Default::default()
             },
         ))
     }
 
-    pub fn build_reqwest_request(
-        request: http::Request,
-    ) -> reqwest::Result<reqwest::blocking::Request> {
+    fn request_size(request: &reqwest::Request) -> (u64, u64) {
+        let target = match request.url().query() {
+            None => request.url().path().to_string(),
+            Some(query) => format!("{}?{}", request.url().path(), query),
+        };
+        let line = format!(
+            "{} {} {:?}\r\n",
+            request.method(),
+            target,
+            request.version()
+        );
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| bytes.len() as u64)
+            .or_else(|| {
+                request
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok())
+            })
+            .unwrap_or(0);
+        (
+            line.len() as u64 + Self::header_size(request.headers()),
+            body,
+        )
+    }
+
+    fn response_header_size(response: &reqwest::Response) -> u64 {
+        let line = format!("{:?} {}\r\n", response.version(), response.status());
+        line.len() as u64 + Self::header_size(response.headers())
+    }
+
+    fn header_size(headers: &HeaderMap) -> u64 {
+        headers
+            .iter()
+            .map(|(name, value)| (name.as_str().len() + value.len() + 4) as u64)
+            .sum::<u64>()
+            + 2
+    }
+
+    async fn execute(
+        client: &Client,
+        request: reqwest::Request,
+        settings: &RequestSettings,
+        redirects: &mut Vec<http::Redirect>,
+        connection: &mut Option<ConnectionPhases>,
+        logger: &mut Logger,
+    ) -> Result<reqwest::Response, String> {
+        let mut request = request;
+        loop {
+            let method = request.method().clone();
+            let headers = request.headers().clone();
+            let replay = request.try_clone();
+            let secure = request.url().scheme() == "https";
+            let (response, phases) =
+                ConnectionTimer::measure(client.execute(request), secure).await;
+            connection.get_or_insert(phases);
+            let response = response.map_err(|e| e.to_string())?;
+            let status = response.status();
+            if !settings.follow_redirects || !status.is_redirection() {
+                return Ok(response);
+            }
+            let location = match response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| response.url().join(location).ok())
+            {
+                None => return Ok(response),
+                Some(location) => location,
+            };
+            if redirects.len() >= settings.max_redirects {
+                return Err(format!(
+                    "too many redirects, stopped after {}",
+                    settings.max_redirects
+                ));
+            }
+            let switch_to_get = (status == StatusCode::SEE_OTHER && method != Method::HEAD)
+                || ((status == StatusCode::MOVED_PERMANENTLY || status == StatusCode::FOUND)
+                    && method == Method::POST);
+            let mut next = if switch_to_get {
+                let mut next = reqwest::Request::new(Method::GET, location.clone());
+                *next.headers_mut() = headers;
+                for name in [CONTENT_TYPE, CONTENT_LENGTH, TRANSFER_ENCODING] {
+                    next.headers_mut().remove(name);
+                }
+                next
+            } else {
+                match replay {
+                    None => {
+                        logger.add_warn(
+                            "redirect".to_string(),
+                            format!("the request body can not be replayed to {}", location),
+                        );
+                        return Ok(response);
+                    }
+                    Some(mut next) => {
+                        *next.url_mut() = location.clone();
+                        next
+                    }
+                }
+            };
+            if response.url().host_str() != location.host_str()
+                || response.url().port_or_known_default() != location.port_or_known_default()
+            {
+                for name in [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION] {
+                    next.headers_mut().remove(name);
+                }
+            }
+            logger.add_info(
+                "redirect".to_string(),
+                format!(
+                    "{} {} {} -> {} {}",
+                    status,
+                    method,
+                    response.url(),
+                    next.method(),
+                    location
+                ),
+            );
+            redirects.push(http::Redirect {
+                status: status.as_u16(),
+                method: method.to_string(),
+                url: response.url().to_string(),
+                location: location.to_string(),
+            });
+            request = next;
+        }
+    }
+
+    /// Sends every `Cookie` header as one. reqwest skips the cookie jar once a `Cookie`
+    /// header is set, so when only the API key added one the jar cookies are included here.
+    fn merge_cookies(
+        request: &http::Request,
+        reqwest_request: &mut reqwest::Request,
+        cookie_store: &dyn CookieStore,
+    ) -> Result<(), String> {
+        let mut cookies: Vec<String> = reqwest_request
+            .headers()
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+            .collect();
+        if cookies.is_empty() {
+            return Ok(());
+        }
+        let user_cookie = request
+            .headers
+            .iter()
+            .any(|h| h.enable && h.key.eq_ignore_ascii_case("cookie") && h.desc != API_KEY_DESC);
+        if !user_cookie {
+            if let Some(jar) = cookie_store
+                .cookies(reqwest_request.url())
+                .and_then(|v| v.to_str().map(|v| v.to_string()).ok())
+            {
+                cookies.insert(0, jar);
+            }
+        }
+        reqwest_request.headers_mut().insert(
+            COOKIE,
+            HeaderValue::from_str(cookies.join("; ").as_str()).map_err(|e| e.to_string())?,
+        );
+        Ok(())
+    }
+
+    pub fn build_reqwest_request(request: http::Request) -> Result<reqwest::Request, String> {
         let client = Client::new();
         let method = Method::from_str(request.method.to_string().to_uppercase().as_str())
             .unwrap_or_default();
-        let mut builder = client.request(method, request.base_url);
+        let mut builder = client.request(method, Self::build_url(&request));
         for header in request.headers.iter().filter(|h| h.enable) {
             builder = builder.header(header.key.clone(), header.value.clone());
         }
-        let query: Vec<(String, String)> = request
-            .params
-            .iter()
-            .filter(|q| q.enable)
-            .map(|p| (p.key.clone(), p.value.clone()))
-            .collect();
-        builder = builder.query(&query);
         match request.body.body_type {
             BodyType::NONE => {}
             BodyType::FROM_DATA => {
-                let mut form = multipart::Form::new();
-                for md in request.body.body_form_data.iter().filter(|md| md.enable) {
-                    match md.data_type {
-                        MultipartDataType::File => {
-                            form = form
-                                .file(md.key.clone(), Path::new(md.value.as_str()).to_path_buf())
-                                .unwrap()
-                        }
-                        MultipartDataType::Text => {
-                            form = form.text(md.key.clone(), md.value.clone());
-                        }
-                    }
-                }
-                builder = builder.multipart(form);
+                let boundary = format!(
+                    "{:016x}-{:016x}",
+                    rand::random::<u64>(),
+                    rand::random::<u64>()
+                );
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_str(
+                        format!("multipart/form-data; boundary={}", boundary).as_str(),
+                    )
+                    .map_err(|e| e.to_string())?,
+                );
+                builder = builder
+                    .headers(headers)
+                    .body(request.body.to_multipart(boundary.as_str())?);
             }
             BodyType::X_WWW_FROM_URLENCODED => {
-                let mut params = HashMap::new();
-                for md in request.body.body_xxx_form.iter().filter(|md| md.enable) {
-                    params.insert(md.key.clone(), md.value.clone());
-                }
-                builder = builder.form(&params);
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("application/x-www-form-urlencoded"),
+                );
+                builder = builder
+                    .headers(headers)
+                    .body(request.body.to_form_urlencoded());
             }
             BodyType::RAW => match request.body.body_raw_type {
                 BodyRawType::TEXT => {
@@ -125,6 +497,10 @@ impl RestSender {
                     builder = builder.body(request.body.body_str);
                 }
             },
+            BodyType::GRAPHQL => {
+                builder = builder.header(CONTENT_TYPE, "application/json");
+                builder = builder.body(request.body.body_graphql.to_payload());
+            }
             BodyType::BINARY => {
                 let path = Path::new(request.body.body_file.as_str());
                 let content_type = mime_guess::from_path(path);
@@ -140,7 +516,30 @@ impl RestSender {
                 builder = builder.body(inner);
             }
         }
-        builder.build()
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// Appends the enabled params in order, keeping duplicates and existing escapes.
+    pub fn build_url(request: &http::Request) -> String {
+        let query: Vec<String> = request
+            .params
+            .iter()
+            .filter(|q| q.enable)
+            .map(|q| {
+                format!(
+                    "{}={}",
+                    http::encode_query_component(q.key.as_str(), true),
+                    http::encode_query_component(q.value.as_str(), false)
+                )
+            })
+            .collect();
+        if query.is_empty() {
+            request.base_url.clone()
+        } else if request.base_url.contains('?') {
+            format!("{}&{}", request.base_url, query.join("&"))
+        } else {
+            format!("{}?{}", request.base_url, query.join("&"))
+        }
     }
 
     pub(crate) fn build_request(
@@ -148,14 +547,34 @@ impl RestSender {
         envs: BTreeMap<String, EnvironmentItemValue>,
     ) -> http::Request {
         let mut build_request = request.clone();
+        build_request.base_url = utils::replace_variable(
+            http::replace_path_variables(request.base_url.as_str(), |key| {
+                request
+                    .path_variables
+                    .iter()
+                    .find(|v| v.key == key && !v.value.is_empty())
+                    .map(|v| utils::replace_variable(v.value.clone(), envs.clone()))
+            }),
+            envs.clone(),
+        );
+        for param in build_request.params.iter_mut() {
+            param.key = utils::replace_variable(param.key.clone(), envs.clone());
+            param.value = utils::replace_variable(param.value.clone(), envs.clone());
+        }
         if !build_request.base_url.starts_with("http://")
             && !build_request.base_url.starts_with("https://")
+            && !build_request.base_url.starts_with("ws://")
+            && !build_request.base_url.starts_with("wss://")
         {
             build_request.base_url = "http://".to_string() + build_request.base_url.as_str();
         }
         build_request.headers = Self::build_header(request.headers.clone(), &envs);
         build_request.body.body_str =
             utils::replace_variable(build_request.body.body_str, envs.clone());
+        build_request.body.body_graphql.query =
+            utils::replace_variable(build_request.body.body_graphql.query, envs.clone());
+        build_request.body.body_graphql.variables =
+            utils::replace_variable(build_request.body.body_graphql.variables, envs.clone());
         for md in build_request.body.body_xxx_form.iter_mut() {
             md.key = utils::replace_variable(md.key.clone(), envs.clone());
             md.value = utils::replace_variable(md.value.clone(), envs.clone());
@@ -164,6 +583,12 @@ impl RestSender {
             md.key = utils::replace_variable(md.key.clone(), envs.clone());
             md.value = utils::replace_variable(md.value.clone(), envs.clone());
         }
+        build_request.body.body_file =
+            utils::replace_variable(build_request.body.body_file, envs.clone());
+        build_request.auth.aws = build_request.auth.aws.build(&envs);
+        build_request.auth.jwt = build_request.auth.jwt.build(&envs);
+        build_request.auth.hmac = build_request.auth.hmac.build(&envs);
+        build_request.auth.digest = build_request.auth.digest.build(&envs);
         build_request
     }
 
@@ -0,0 +1,399 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::str::FromStr;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use reqwest::cookie::CookieStore;
+use reqwest_cookie_store::CookieStoreMutex;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+use url::Url;
+
+use crate::data::auth::Auth;
+use crate::data::environment::EnvironmentItemValue;
+use crate::data::http;
+use crate::data::logger::Logger;
+use crate::data::network_settings::ClientConfig;
+use crate::data::websocket::{WebSocketDirection, WebSocketLog, WebSocketMessageType};
+use crate::operation::oauth2_client::OAuth2Client;
+use crate::operation::rest_sender::RestSender;
+use crate::script::script::{Context, JsWebSocketMessage, ScriptRuntime, ScriptScope};
+
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WebSocketStatus {
+    Connecting,
+    Connected,
+    Closed,
+}
+
+enum WebSocketCommand {
+    Send(WebSocketMessageType, String),
+    Close,
+}
+
+pub struct WebSocketSession {
+    sender: Sender<WebSocketCommand>,
+    logs: Arc<Mutex<Vec<WebSocketLog>>>,
+    status: Arc<Mutex<WebSocketStatus>>,
+}
+
+struct WebSocketWorker {
+    logs: Arc<Mutex<Vec<WebSocketLog>>>,
+    status: Arc<Mutex<WebSocketStatus>>,
+    cookie_store: Arc<CookieStoreMutex>,
+    message_scripts: Vec<ScriptScope>,
+    client_config: ClientConfig,
+    context: Context,
+}
+
+impl WebSocketSession {
+    pub fn connect(
+        request: http::Request,
+        envs: BTreeMap<String, EnvironmentItemValue>,
+        parent_auth: Auth,
+        client_config: ClientConfig,
+        pre_request_scripts: Vec<ScriptScope>,
+        message_scripts: Vec<ScriptScope>,
+        cookie_store: Arc<CookieStoreMutex>,
+    ) -> Self {
+        let (sender, receiver) = channel();
+        let logs = Arc::new(Mutex::new(vec![]));
+        let status = Arc::new(Mutex::new(WebSocketStatus::Connecting));
+        let mut worker = WebSocketWorker {
+            logs: logs.clone(),
+            status: status.clone(),
+            cookie_store,
+            message_scripts,
+            context: Context {
+                scope_name: "".to_string(),
+                request,
+                envs,
+                proxy: client_config.proxy.clone(),
+                ..Default::default()
+            },
+            client_config,
+        };
+        thread::spawn(move || {
+            worker.run(parent_auth, pre_request_scripts, receiver);
+            worker.set_status(WebSocketStatus::Closed);
+        });
+        Self {
+            sender,
+            logs,
+            status,
+        }
+    }
+
+    pub fn send(&self, message_type: WebSocketMessageType, content: String) {
+        let _ = self
+            .sender
+            .send(WebSocketCommand::Send(message_type, content));
+    }
+
+    pub fn close(&self) {
+        let _ = self.sender.send(WebSocketCommand::Close);
+    }
+
+    pub fn get_status(&self) -> WebSocketStatus {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(WebSocketStatus::Closed)
+    }
+
+    pub fn get_logs(&self) -> Vec<WebSocketLog> {
+        self.logs.lock().map(|l| l.clone()).unwrap_or_default()
+    }
+
+    pub fn clear_logs(&self) {
+        if let Ok(mut logs) = self.logs.lock() {
+            logs.clear();
+        }
+    }
+}
+
+impl Drop for WebSocketSession {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl WebSocketWorker {
+    fn run(
+        &mut self,
+        parent_auth: Auth,
+        pre_request_scripts: Vec<ScriptScope>,
+        receiver: Receiver<WebSocketCommand>,
+    ) {
+        let mut logger = Logger::default();
+        if let Err(e) = OAuth2Client::prepare_request(
+            &mut self.context.request,
+            parent_auth,
+            &self.context.envs,
+            &self.client_config,
+            &mut logger,
+        ) {
+            self.add_error(format!("OAuth 2.0 failed: {}", e));
+            return;
+        }
+        self.add_script_logs(&logger);
+        if !pre_request_scripts.is_empty() {
+            match ScriptRuntime::run_block_many(pre_request_scripts, self.context.clone()) {
+                Ok(context) => {
+                    self.add_script_logs(&context.logger);
+                    self.context = context;
+                }
+                Err(e) => {
+                    self.add_error(format!("Pre-request script failed: {}", e));
+                    return;
+                }
+            }
+        }
+        let request =
+            RestSender::build_request(self.context.request.clone(), self.context.envs.clone());
+        let url = match Self::build_url(&request) {
+            Ok(url) => url,
+            Err(e) => {
+                self.add_error(e);
+                return;
+            }
+        };
+        let cookie_url = Self::cookie_url(&url);
+        let mut ws_request = match url.as_str().into_client_request() {
+            Ok(ws_request) => ws_request,
+            Err(e) => {
+                self.add_error(e.to_string());
+                return;
+            }
+        };
+        for header in request.headers.iter().filter(|h| h.enable) {
+            match (
+                HeaderName::from_str(header.key.as_str()),
+                HeaderValue::from_str(header.value.as_str()),
+            ) {
+                (Ok(name), Ok(value)) => {
+                    ws_request.headers_mut().append(name, value);
+                }
+                _ => self.add_error(format!("Invalid header `{}`", header.key)),
+            }
+        }
+        if !ws_request.headers().contains_key("cookie") {
+            if let Some(cookie) = self.cookie_store.cookies(&cookie_url) {
+                if let Ok(value) = HeaderValue::from_bytes(cookie.as_bytes()) {
+                    ws_request.headers_mut().insert("cookie", value);
+                }
+            }
+        }
+        self.add_log(
+            WebSocketDirection::Info,
+            WebSocketMessageType::Text,
+            format!("Connecting to {}", url),
+        );
+        let mut socket = match tungstenite::connect(ws_request) {
+            Ok((socket, response)) => {
+                let set_cookies: Vec<reqwest::header::HeaderValue> = response
+                    .headers()
+                    .get_all("set-cookie")
+                    .iter()
+                    .filter_map(|v| reqwest::header::HeaderValue::from_bytes(v.as_bytes()).ok())
+                    .collect();
+                self.cookie_store
+                    .set_cookies(&mut set_cookies.iter(), &cookie_url);
+                self.add_log(
+                    WebSocketDirection::Info,
+                    WebSocketMessageType::Text,
+                    format!("Connected: {}", response.status()),
+                );
+                socket
+            }
+            Err(e) => {
+                self.add_error(format!("Connect failed: {}", e));
+                return;
+            }
+        };
+        Self::set_read_timeout(&socket);
+        self.set_status(WebSocketStatus::Connected);
+        loop {
+            match receiver.try_recv() {
+                Ok(WebSocketCommand::Send(message_type, content)) => {
+                    self.send(&mut socket, message_type, content)
+                }
+                Ok(WebSocketCommand::Close) | Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    self.add_log(
+                        WebSocketDirection::Info,
+                        WebSocketMessageType::Text,
+                        "Disconnected".to_string(),
+                    );
+                    return;
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    self.receive(&mut socket, WebSocketMessageType::Text, text)
+                }
+                Ok(Message::Binary(bytes)) => self.receive(
+                    &mut socket,
+                    WebSocketMessageType::Binary,
+                    general_purpose::STANDARD.encode(bytes),
+                ),
+                Ok(Message::Close(frame)) => {
+                    self.add_log(
+                        WebSocketDirection::Info,
+                        WebSocketMessageType::Text,
+                        match frame {
+                            None => "Closed by server".to_string(),
+                            Some(frame) => {
+                                format!("Closed by server: {} {}", frame.code, frame.reason)
+                            }
+                        },
+                    );
+                    return;
+                }
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+                Err(e) => {
+                    self.add_error(e.to_string());
+                    return;
+                }
+            }
+        }
+    }
+
+    fn send(
+        &mut self,
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+        message_type: WebSocketMessageType,
+        content: String,
+    ) {
+        let message = match message_type {
+            WebSocketMessageType::Text => Message::Text(content.clone()),
+            WebSocketMessageType::Binary => {
+                match general_purpose::STANDARD.decode(content.trim()) {
+                    Ok(bytes) => Message::Binary(bytes),
+                    Err(e) => {
+                        self.add_error(format!("Binary message must be base64: {}", e));
+                        return;
+                    }
+                }
+            }
+        };
+        match socket.send(message) {
+            Ok(_) => self.add_log(WebSocketDirection::Outbound, message_type, content),
+            Err(e) => self.add_error(format!("Send failed: {}", e)),
+        }
+    }
+
+    fn receive(
+        &mut self,
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+        message_type: WebSocketMessageType,
+        content: String,
+    ) {
+        self.add_log(
+            WebSocketDirection::Inbound,
+            message_type.clone(),
+            content.clone(),
+        );
+        if self.message_scripts.is_empty() {
+            return;
+        }
+        let mut context = self.context.clone();
+        context.logger = Logger::default();
+        context.outbox = vec![];
+        context.message = JsWebSocketMessage {
+            message_type: message_type.to_string(),
+            data: content,
+        };
+        match ScriptRuntime::run_block_many(self.message_scripts.clone(), context) {
+            Ok(context) => {
+                self.add_script_logs(&context.logger);
+                for data in context.outbox.iter() {
+                    self.send(socket, WebSocketMessageType::Text, data.clone());
+                }
+                self.context.envs = context.envs;
+                self.context.shared_map = context.shared_map;
+            }
+            Err(e) => self.add_error(format!("Message script failed: {}", e)),
+        }
+    }
+
+    fn build_url(request: &http::Request) -> Result<Url, String> {
+        let url = Url::parse(RestSender::build_url(request).as_str()).map_err(|e| e.to_string())?;
+        if url.scheme() != "ws" && url.scheme() != "wss" {
+            return Err(format!("Unsupported scheme `{}`", url.scheme()));
+        }
+        Ok(url)
+    }
+
+    fn cookie_url(url: &Url) -> Url {
+        let mut cookie_url = url.clone();
+        let scheme = if url.scheme() == "wss" {
+            "https"
+        } else {
+            "http"
+        };
+        let _ = cookie_url.set_scheme(scheme);
+        cookie_url
+    }
+
+    fn set_read_timeout(socket: &WebSocket<MaybeTlsStream<TcpStream>>) {
+        match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => {
+                let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+            }
+            MaybeTlsStream::NativeTls(stream) => {
+                let _ = stream.get_ref().set_read_timeout(Some(READ_TIMEOUT));
+            }
+            _ => {}
+        }
+    }
+
+    fn add_script_logs(&self, logger: &Logger) {
+        for log in logger.logs.iter() {
+            self.add_log(
+                WebSocketDirection::Info,
+                WebSocketMessageType::Text,
+                format!("[{}] {}", log.scope, log.msg),
+            );
+        }
+    }
+
+    fn add_error(&self, content: String) {
+        self.add_log(
+            WebSocketDirection::Error,
+            WebSocketMessageType::Text,
+            content,
+        );
+    }
+
+    fn add_log(
+        &self,
+        direction: WebSocketDirection,
+        message_type: WebSocketMessageType,
+        content: String,
+    ) {
+        if let Ok(mut logs) = self.logs.lock() {
+            logs.push(WebSocketLog::new(direction, message_type, content));
+        }
+    }
+
+    fn set_status(&self, status: WebSocketStatus) {
+        if let Ok(mut s) = self.status.lock() {
+            *s = status;
+        }
+    }
+}
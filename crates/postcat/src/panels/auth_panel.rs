@@ -1,13 +1,21 @@
 use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::rc::Rc;
 
-use egui::{Ui, Widget};
+use egui::{Button, RichText, Ui, Widget};
+use poll_promise::Promise;
 use strum::IntoEnumIterator;
 
-use crate::data::auth::{Auth, AuthType};
+use crate::data::auth::{ApiKeyLocation, Auth, AuthType};
 use crate::data::collections::{Collection, CollectionFolder};
 use crate::data::environment::EnvironmentItemValue;
+use crate::data::network_settings::ClientConfig;
+use crate::data::oauth2::{
+    OAuth2, OAuth2ClientAuthentication, OAuth2GrantType, OAuth2Token, DEFAULT_REDIRECT_URL,
+};
+use crate::data::signing::{HmacAuth, JwtAuth, KeySource};
+use crate::operation::oauth2_client::OAuth2Client;
 use crate::panels::{HORIZONTAL_GAP, VERTICAL_GAP};
 use crate::utils;
 use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
@@ -20,6 +28,9 @@ pub struct AuthPanel {
     parent_auth: Option<Auth>,
     name: String,
     no_inherit: bool,
+    token_promise: Option<(OAuth2, Promise<Result<OAuth2Token, String>>)>,
+    token_error: String,
+    client_config: ClientConfig,
 }
 
 impl AuthPanel {
@@ -32,6 +43,10 @@ impl AuthPanel {
         self.parent_auth = parent_auth
     }
 
+    pub fn set_client_config(&mut self, client_config: ClientConfig) {
+        self.client_config = client_config;
+    }
+
     pub fn set_collection_folder(
         &mut self,
         collection: Collection,
@@ -116,6 +131,63 @@ impl AuthPanel {
                     });
                     ui.add_space(VERTICAL_GAP * 2.0);
                 }
+                AuthType::OAuth2 => {
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                    self.render_oauth2(&mut data.oauth2, ui);
+                    ui.add_space(VERTICAL_GAP);
+                    let oauth2 = data.oauth2.with_cache_key(&self.envs);
+                    self.render_oauth2_token(oauth2, ui);
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
+                AuthType::AwsSigV4 => {
+                    ui.add_space(VERTICAL_GAP);
+                    self.render_text(ui, "Access Key:", "aws_access_key", &mut data.aws.access_key);
+                    self.render_text(ui, "Secret Key:", "aws_secret_key", &mut data.aws.secret_key);
+                    self.render_text(
+                        ui,
+                        "Session Token:",
+                        "aws_session_token",
+                        &mut data.aws.session_token,
+                    );
+                    self.render_text(ui, "Region:", "aws_region", &mut data.aws.region);
+                    self.render_text(ui, "Service Name:", "aws_service", &mut data.aws.service);
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
+                AuthType::DigestAuth => {
+                    ui.add_space(VERTICAL_GAP);
+                    self.render_text(ui, "Username:", "digest_username", &mut data.digest.username);
+                    self.render_text(ui, "Password: ", "digest_password", &mut data.digest.password);
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
+                AuthType::Jwt => {
+                    ui.add_space(VERTICAL_GAP);
+                    self.render_jwt(&mut data.jwt, ui);
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
+                AuthType::HmacSignature => {
+                    ui.add_space(VERTICAL_GAP);
+                    self.render_hmac(&mut data.hmac, ui);
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
+                AuthType::ApiKey => {
+                    ui.add_space(VERTICAL_GAP);
+                    self.render_text(ui, "Key:", "api_key_name", &mut data.api_key.name);
+                    self.render_text(ui, "Value:", "api_key_value", &mut data.api_key.value);
+                    ui.add_space(VERTICAL_GAP);
+                    ui.horizontal(|ui| {
+                        ui.add_space(HORIZONTAL_GAP);
+                        ui.label("Add To:");
+                        egui::ComboBox::from_id_source(self.name.clone() + "api_key_location")
+                            .selected_text(data.api_key.location.to_string())
+                            .show_ui(ui, |ui| {
+                                ui.style_mut().wrap = Some(false);
+                                for x in ApiKeyLocation::iter() {
+                                    ui.selectable_value(&mut data.api_key.location, x.clone(), x.to_string());
+                                }
+                            });
+                    });
+                    ui.add_space(VERTICAL_GAP * 2.0);
+                }
                 AuthType::InheritAuthFromParent => {
                     ui.add_space(VERTICAL_GAP);
                     ui.label("This request is not inheriting any authorization helper at the moment. Save it in a collection to use the parent's authorization helper.");
@@ -155,11 +227,347 @@ impl AuthPanel {
                                 });
                                 ui.add_space(VERTICAL_GAP * 2.0);
                             }
+                            AuthType::OAuth2 => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("OAuth 2.0:");
+                                    ui.label(parent_auth.oauth2.grant_type.to_string());
+                                });
+                                ui.add_space(VERTICAL_GAP);
+                                self.render_oauth2_token(parent_auth.oauth2.with_cache_key(&self.envs), ui);
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
+                            AuthType::AwsSigV4 => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Access Key:");
+                                    ui.label(utils::replace_variable(parent_auth.aws.access_key.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Region:");
+                                    ui.label(utils::replace_variable(parent_auth.aws.region.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Service Name:");
+                                    ui.label(utils::replace_variable(parent_auth.aws.service.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
+                            AuthType::DigestAuth => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Username:");
+                                    ui.label(utils::replace_variable(parent_auth.digest.username.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
+                            AuthType::Jwt => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("JWT:");
+                                    ui.label(format!("{} in {}", parent_auth.jwt.algorithm, parent_auth.jwt.header_name));
+                                });
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
+                            AuthType::HmacSignature => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("HMAC:");
+                                    ui.label(format!("{} in {}", parent_auth.hmac.algorithm, parent_auth.hmac.header_name));
+                                });
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
+                            AuthType::ApiKey => {
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Key:");
+                                    ui.label(utils::replace_variable(parent_auth.api_key.name.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Value:");
+                                    ui.label(utils::replace_variable(parent_auth.api_key.value.clone(), self.envs.clone()));
+                                });
+                                ui.add_space(VERTICAL_GAP);
+                                ui.horizontal(|ui| {
+                                    ui.add_space(HORIZONTAL_GAP);
+                                    ui.label("Add To:");
+                                    ui.label(parent_auth.api_key.location.to_string());
+                                });
+                                ui.add_space(VERTICAL_GAP * 2.0);
+                            }
                         }
                     });
                 }
             });
     }
+    fn render_oauth2(&mut self, data: &mut OAuth2, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label("Grant Type:");
+            egui::ComboBox::from_id_source(self.name.clone() + "oauth2_grant_type")
+                .selected_text(data.grant_type.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for x in OAuth2GrantType::iter() {
+                        ui.selectable_value(&mut data.grant_type, x.clone(), x.to_string());
+                    }
+                });
+        });
+        if data.grant_type == OAuth2GrantType::AuthorizationCodeWithPKCE {
+            self.render_text(ui, "Auth URL:", "auth_url", &mut data.auth_url);
+            ui.add_space(VERTICAL_GAP);
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                ui.label("Redirect URL:");
+                egui::TextEdit::singleline(&mut data.redirect_url)
+                    .hint_text(DEFAULT_REDIRECT_URL)
+                    .ui(ui);
+            });
+        }
+        self.render_text(
+            ui,
+            "Access Token URL:",
+            "access_token_url",
+            &mut data.access_token_url,
+        );
+        self.render_text(ui, "Client ID:", "client_id", &mut data.client_id);
+        self.render_text(
+            ui,
+            "Client Secret:",
+            "client_secret",
+            &mut data.client_secret,
+        );
+        self.render_text(ui, "Scope:", "scope", &mut data.scope);
+        if data.grant_type == OAuth2GrantType::Password {
+            self.render_text(ui, "Username:", "username", &mut data.username);
+            self.render_text(ui, "Password:", "password", &mut data.password);
+        }
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label("Client Authentication:");
+            egui::ComboBox::from_id_source(self.name.clone() + "oauth2_client_authentication")
+                .selected_text(data.client_authentication.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for x in OAuth2ClientAuthentication::iter() {
+                        ui.selectable_value(
+                            &mut data.client_authentication,
+                            x.clone(),
+                            x.to_string(),
+                        );
+                    }
+                });
+        });
+    }
+
+    fn render_text(&self, ui: &mut Ui, label: &str, id: &str, value: &mut String) {
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label(label);
+            HighlightTemplateSinglelineBuilder::default()
+                .envs(self.envs.clone())
+                .build(id.to_string(), value)
+                .ui(ui);
+        });
+    }
+
+    fn render_combo<T: IntoEnumIterator + Display + PartialEq + Clone>(
+        &self,
+        ui: &mut Ui,
+        label: &str,
+        id: &str,
+        value: &mut T,
+    ) {
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label(label);
+            egui::ComboBox::from_id_source(self.name.clone() + id)
+                .selected_text(value.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for x in T::iter() {
+                        let text = x.to_string();
+                        ui.selectable_value(value, x, text);
+                    }
+                });
+        });
+    }
+
+    fn render_key(&self, ui: &mut Ui, id: &str, key_source: &mut KeySource, key: &mut String) {
+        self.render_combo(
+            ui,
+            "Key Source:",
+            (id.to_string() + "_key_source").as_str(),
+            key_source,
+        );
+        match key_source {
+            KeySource::Value => {
+                self.render_text(ui, "Key:", (id.to_string() + "_key").as_str(), key)
+            }
+            KeySource::EnvironmentVariable => self.render_text(
+                ui,
+                "Variable Name:",
+                (id.to_string() + "_key").as_str(),
+                key,
+            ),
+            KeySource::File => {
+                ui.add_space(VERTICAL_GAP);
+                ui.horizontal(|ui| {
+                    ui.add_space(HORIZONTAL_GAP);
+                    ui.label("Key File:");
+                    egui::TextEdit::singleline(key).ui(ui);
+                    if ui.button("Browse").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_file() {
+                            *key = path.to_string_lossy().to_string();
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    fn render_jwt(&self, data: &mut JwtAuth, ui: &mut Ui) {
+        self.render_combo(ui, "Algorithm:", "jwt_algorithm", &mut data.algorithm);
+        self.render_key(ui, "jwt", &mut data.key_source, &mut data.key);
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label("Expires In (s):");
+            egui::DragValue::new(&mut data.exp_offset)
+                .clamp_range(0..=i64::MAX)
+                .ui(ui);
+        });
+        self.render_text(ui, "Header Name:", "jwt_header_name", &mut data.header_name);
+        self.render_text(
+            ui,
+            "Header Prefix:",
+            "jwt_header_prefix",
+            &mut data.header_prefix,
+        );
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label("Claims:");
+        });
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            HighlightTemplateSinglelineBuilder::default()
+                .multiline()
+                .envs(self.envs.clone())
+                .all_space(true)
+                .build(self.name.clone() + "jwt_claims", &mut data.claims)
+                .ui(ui);
+        });
+    }
+
+    fn render_hmac(&self, data: &mut HmacAuth, ui: &mut Ui) {
+        self.render_combo(ui, "Algorithm:", "hmac_algorithm", &mut data.algorithm);
+        self.render_key(ui, "hmac", &mut data.key_source, &mut data.key);
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            ui.label("Sign:");
+            ui.checkbox(&mut data.include_method, "Method");
+            ui.checkbox(&mut data.include_path, "Path");
+            ui.checkbox(&mut data.include_timestamp, "Timestamp");
+            ui.checkbox(&mut data.include_body_hash, "Body Hash");
+        });
+        self.render_combo(ui, "Encoding:", "hmac_encoding", &mut data.encoding);
+        self.render_text(
+            ui,
+            "Header Name:",
+            "hmac_header_name",
+            &mut data.header_name,
+        );
+        self.render_text(
+            ui,
+            "Header Prefix:",
+            "hmac_header_prefix",
+            &mut data.header_prefix,
+        );
+        if data.include_timestamp {
+            self.render_text(
+                ui,
+                "Timestamp Header:",
+                "hmac_timestamp_header_name",
+                &mut data.timestamp_header_name,
+            );
+        }
+    }
+
+    fn render_oauth2_token(&mut self, oauth2: OAuth2, ui: &mut Ui) {
+        let ready = self
+            .token_promise
+            .as_ref()
+            .and_then(|(o, p)| p.ready().map(|r| (o.clone(), r.clone())));
+        if let Some((promise_oauth2, result)) = ready {
+            match result {
+                Ok(token) => {
+                    promise_oauth2.set_token(token);
+                    self.token_error = "".to_string();
+                }
+                Err(e) => self.token_error = e,
+            }
+            self.token_promise = None;
+        }
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            match oauth2.get_token() {
+                None => {
+                    ui.label("No access token");
+                }
+                Some(token) => {
+                    ui.label(token.show());
+                }
+            }
+        });
+        ui.add_space(VERTICAL_GAP);
+        ui.horizontal(|ui| {
+            ui.add_space(HORIZONTAL_GAP);
+            if self.token_promise.is_some() {
+                ui.add_enabled(false, Button::new("Get New Access Token"));
+                ui.spinner();
+            } else if ui.button("Get New Access Token").clicked() {
+                let envs = self.envs.clone();
+                let client_config = self.client_config.clone();
+                let promise_oauth2 = oauth2.clone();
+                self.token_promise = Some((
+                    oauth2.clone(),
+                    Promise::spawn_thread("oauth2_token", move || {
+                        OAuth2Client::fetch_token(&promise_oauth2, &envs, &client_config)
+                    }),
+                ));
+            }
+            if ui.button("Clear Token").clicked() {
+                oauth2.clear_token();
+            }
+        });
+        if !self.token_error.is_empty() {
+            ui.add_space(VERTICAL_GAP);
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                ui.label(
+                    RichText::new(self.token_error.clone()).color(ui.visuals().error_fg_color),
+                );
+            });
+        }
+    }
+
     pub(crate) fn set_and_render(&mut self, ui: &mut Ui, data: &mut Auth) {
         ui.horizontal(|ui| {
             self.auth_left(data, ui);
@@ -2,9 +2,12 @@ use eframe::emath::Align;
 use egui::{FontSelection, Response, RichText, Style, Ui, WidgetText};
 
 use crate::data::environment::ENVIRONMENT_GLOBALS;
+use crate::data::http::RecordType;
 use crate::data::workspace_data::WorkspaceData;
 use crate::operation::operation::Operation;
+use crate::panels::grpc_panel::GrpcPanel;
 use crate::panels::rest_panel::RestPanel;
+use crate::panels::websocket_panel::WebSocketPanel;
 use crate::panels::{DataView, HORIZONTAL_GAP};
 use crate::utils;
 use crate::windows::environment_windows::EnvironmentWindows;
@@ -13,6 +16,8 @@ use crate::windows::request_close_windows::RequestCloseWindows;
 #[derive(Default)]
 pub struct MyCentralPanel {
     editor_panel: RestPanel,
+    websocket_panel: WebSocketPanel,
+    grpc_panel: GrpcPanel,
     select_crt_id: Option<String>,
 }
 
@@ -40,10 +45,36 @@ impl MyCentralPanel {
         });
         ui.separator();
         match &workspace_data.get_crt_select_id() {
-            Some(request_id) => {
-                self.editor_panel
-                    .set_and_render(ui, operation, workspace_data, request_id.clone());
-            }
+            Some(request_id) => match workspace_data
+                .must_get_crt(request_id.clone())
+                .rest
+                .record_type
+            {
+                RecordType::HTTP => {
+                    self.editor_panel.set_and_render(
+                        ui,
+                        operation,
+                        workspace_data,
+                        request_id.clone(),
+                    );
+                }
+                RecordType::WebSocket => {
+                    self.websocket_panel.set_and_render(
+                        ui,
+                        operation,
+                        workspace_data,
+                        request_id.clone(),
+                    );
+                }
+                RecordType::GRPC => {
+                    self.grpc_panel.set_and_render(
+                        ui,
+                        operation,
+                        workspace_data,
+                        request_id.clone(),
+                    );
+                }
+            },
             _ => {}
         }
 
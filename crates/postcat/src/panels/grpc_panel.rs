@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+
+use egui::ahash::HashSet;
+use egui::{Button, Ui, Widget};
+use poll_promise::Promise;
+use prost_reflect::DescriptorPool;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::data::grpc::GrpcSource;
+use crate::data::http::{HttpRecord, LockWith};
+use crate::data::logger::Logger;
+use crate::data::stream::ResponseStream;
+use crate::data::test::TestStatus;
+use crate::data::workspace_data::WorkspaceData;
+use crate::data::{http, test};
+use crate::operation::grpc_sender::GrpcSender;
+use crate::operation::operation::Operation;
+use crate::panels::auth_panel::AuthPanel;
+use crate::panels::request_headers_panel::RequestHeadersPanel;
+use crate::panels::request_pre_script_panel::RequestPreScriptPanel;
+use crate::panels::response_panel::ResponsePanel;
+use crate::panels::rest_panel::RestPanel;
+use crate::panels::test_script_panel::TestScriptPanel;
+use crate::panels::{HORIZONTAL_GAP, VERTICAL_GAP};
+use crate::script::script::ScriptScope;
+use crate::utils;
+use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
+use crate::windows::save_crt_windows::SaveCRTWindows;
+
+#[derive(Default)]
+pub struct GrpcPanel {
+    open_panel_enum: GrpcPanelEnum,
+    auth_panel: AuthPanel,
+    request_headers_panel: RequestHeadersPanel,
+    request_pre_script_panel: RequestPreScriptPanel,
+    test_script_panel: TestScriptPanel,
+    response_panel: ResponsePanel,
+    pools: HashMap<String, DescriptorPool>,
+    load_promise: Option<(String, Promise<Result<DescriptorPool, String>>)>,
+    send_promise:
+        Option<Promise<Result<(http::Request, http::Response, test::TestResult), String>>>,
+    send_rest: Option<HttpRecord>,
+    send_stream: Option<ResponseStream>,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq)]
+enum GrpcPanelEnum {
+    Message,
+    Authorization,
+    Metadata,
+    PreRequestScript,
+    Tests,
+}
+
+impl Default for GrpcPanelEnum {
+    fn default() -> Self {
+        GrpcPanelEnum::Message
+    }
+}
+
+impl GrpcPanel {
+    pub fn set_and_render(
+        &mut self,
+        ui: &mut Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        self.pools
+            .retain(|id, _| workspace_data.contains_crt_id(id.clone()));
+        self.load_promise(ui, operation);
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                RestPanel::render_name_label(workspace_data, crt_id.clone(), ui);
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                self.render_editor_right_panel(operation, workspace_data, crt_id.clone(), ui);
+                self.render_editor_left_panel(workspace_data, crt_id.clone(), ui);
+            });
+            ui.separator();
+            self.render_service_select(workspace_data, crt_id.clone(), ui);
+            ui.separator();
+            let crt = workspace_data.must_get_crt(crt_id.clone());
+            ui.horizontal(|ui| {
+                for x in GrpcPanelEnum::iter() {
+                    ui.selectable_value(
+                        &mut self.open_panel_enum,
+                        x.clone(),
+                        utils::build_with_count_ui_header(
+                            x.to_string(),
+                            Self::get_count(&crt.rest, x),
+                            ui,
+                        ),
+                    );
+                }
+            });
+            ui.separator();
+        });
+        self.send_promise(ui, workspace_data, operation, crt_id.clone());
+        self.render_open_panel(ui, operation, workspace_data, crt_id.clone());
+        ui.separator();
+        self.response_panel.set_and_render(
+            ui,
+            operation,
+            workspace_data,
+            crt_id,
+            self.send_stream.as_ref(),
+        );
+    }
+
+    fn get_count(hr: &HttpRecord, panel_enum: GrpcPanelEnum) -> usize {
+        match panel_enum {
+            GrpcPanelEnum::Message => {
+                if hr.grpc.message != "" {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
+            GrpcPanelEnum::Authorization => 0,
+            GrpcPanelEnum::Metadata => hr.request.headers.iter().filter(|i| i.enable).count(),
+            GrpcPanelEnum::PreRequestScript => {
+                if hr.pre_request_script != "" {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
+            GrpcPanelEnum::Tests => {
+                if hr.test_script != "" {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    fn render_editor_right_panel(
+        &mut self,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        ui: &mut Ui,
+    ) {
+        let (mut pre_request_parent_script_scopes, mut test_parent_script_scopes) =
+            workspace_data.get_crt_parent_scripts(crt_id.clone());
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let parent_auth = workspace_data.get_crt_parent_auth(crt_id.clone());
+        egui::SidePanel::right("grpc_editor_right_panel")
+            .resizable(false)
+            .show_inside(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(HORIZONTAL_GAP);
+                    if self.send_promise.is_some() {
+                        if ui.button("Cancel").clicked() {
+                            self.cancel(workspace_data, operation, crt_id.clone());
+                        }
+                    } else if ui.button("Invoke").clicked() {
+                        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                            crt.rest.request.clear_lock_with();
+                            crt.rest.sync(envs.clone(), parent_auth.clone());
+                        });
+                        let crt = workspace_data.must_get_crt(crt_id.clone());
+                        if crt.rest.pre_request_script.clone() != "" {
+                            pre_request_parent_script_scopes.push(ScriptScope {
+                                scope: "request".to_string(),
+                                script: crt.rest.pre_request_script.clone(),
+                            });
+                        }
+                        if crt.rest.test_script.clone() != "" {
+                            test_parent_script_scopes.push(ScriptScope {
+                                scope: "request".to_string(),
+                                script: crt.rest.test_script.clone(),
+                            });
+                        }
+                        let send_stream = ResponseStream::default();
+                        self.send_promise = Some(operation.send_grpc_with_script(
+                            crt.rest.request.clone(),
+                            crt.rest.grpc.clone(),
+                            envs.clone(),
+                            parent_auth.clone(),
+                            workspace_data.get_client_config(workspace_data.get_env_select()),
+                            pre_request_parent_script_scopes,
+                            test_parent_script_scopes,
+                            workspace_data.get_workspace_dir(),
+                            send_stream.clone(),
+                        ));
+                        self.send_rest = Some(crt.rest.clone());
+                        self.send_stream = Some(send_stream);
+                    }
+                    if ui.button("Save").clicked() {
+                        let crt = workspace_data.must_get_crt(crt_id.clone());
+                        match &crt.collection_path {
+                            None => {
+                                operation.add_window(Box::new(
+                                    SaveCRTWindows::default().with(crt.id.clone()),
+                                ));
+                            }
+                            Some(collection_path) => {
+                                workspace_data.save_crt(
+                                    crt.id.clone(),
+                                    collection_path.clone(),
+                                    |_| {},
+                                );
+                                operation.add_success_toast("Save success.");
+                                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                                    crt.set_baseline();
+                                });
+                            }
+                        }
+                    }
+                });
+            });
+    }
+
+    fn render_editor_left_panel(
+        &self,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        ui: &mut Ui,
+    ) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            egui::SidePanel::left("grpc_editor_left_panel")
+                .min_width(ui.available_width() - HORIZONTAL_GAP)
+                .show_separator_line(false)
+                .resizable(false)
+                .show_inside(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        utils::record_method_combo(ui, &mut crt.rest);
+                        let mut filter: HashSet<String> = HashSet::default();
+                        filter.insert(" ".to_string());
+                        ui.centered_and_justified(|ui| {
+                            HighlightTemplateSinglelineBuilder::default()
+                                .filter(filter)
+                                .envs(envs.clone())
+                                .all_space(false)
+                                .build(crt_id.clone() + "url", &mut crt.rest.request.base_url)
+                                .ui(ui);
+                        });
+                    });
+                });
+        });
+    }
+
+    fn render_service_select(
+        &mut self,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        ui: &mut Ui,
+    ) {
+        let workspace_dir = workspace_data.get_workspace_dir();
+        let pool = self.pools.get(crt_id.as_str()).cloned();
+        let mut load = false;
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                for x in GrpcSource::iter() {
+                    utils::selectable_check(
+                        ui,
+                        &mut crt.rest.grpc.source,
+                        x.clone(),
+                        x.to_string(),
+                    );
+                }
+                if crt.rest.grpc.source == GrpcSource::ProtoFile {
+                    ui.text_edit_singleline(&mut crt.rest.grpc.proto_file);
+                    if ui.button("Browse").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("proto", &["proto"])
+                            .set_directory(workspace_dir.clone())
+                            .pick_file()
+                        {
+                            crt.rest.grpc.proto_file = path
+                                .strip_prefix(workspace_dir.clone())
+                                .unwrap_or(path.as_path())
+                                .to_string_lossy()
+                                .to_string();
+                            load = true;
+                        }
+                    }
+                }
+                if self.load_promise.is_some() {
+                    ui.add_enabled(false, Button::new("Load Services"));
+                    ui.spinner();
+                } else if ui.button("Load Services").clicked() {
+                    load = true;
+                }
+            });
+            ui.add_space(VERTICAL_GAP);
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                match &pool {
+                    None => {
+                        ui.label("Load services to pick a method.");
+                    }
+                    Some(pool) => {
+                        egui::ComboBox::from_id_source("grpc_service")
+                            .selected_text(crt.rest.grpc.service.clone())
+                            .width(240.0)
+                            .show_ui(ui, |ui| {
+                                for service in pool.services() {
+                                    ui.selectable_value(
+                                        &mut crt.rest.grpc.service,
+                                        service.full_name().to_string(),
+                                        service.full_name(),
+                                    );
+                                }
+                            });
+                        let methods = pool
+                            .get_service_by_name(crt.rest.grpc.service.as_str())
+                            .map(|s| s.methods().collect::<Vec<_>>())
+                            .unwrap_or_default();
+                        egui::ComboBox::from_id_source("grpc_method")
+                            .selected_text(crt.rest.grpc.method.clone())
+                            .width(200.0)
+                            .show_ui(ui, |ui| {
+                                for method in methods.iter() {
+                                    let mut text = method.name().to_string();
+                                    if method.is_server_streaming() {
+                                        text = text + " (stream)";
+                                    }
+                                    ui.selectable_value(
+                                        &mut crt.rest.grpc.method,
+                                        method.name().to_string(),
+                                        text,
+                                    );
+                                }
+                            });
+                        if let Ok(method) = GrpcSender::find_method(
+                            pool,
+                            crt.rest.grpc.service.as_str(),
+                            crt.rest.grpc.method.as_str(),
+                        ) {
+                            ui.label(format!(
+                                "{} → {}",
+                                method.input().full_name(),
+                                method.output().full_name()
+                            ));
+                            if ui.button("Generate Message").clicked() {
+                                crt.rest.grpc.message =
+                                    GrpcSender::message_template(method.input());
+                            }
+                        }
+                    }
+                }
+            });
+        });
+        if load {
+            self.load_services(workspace_data, crt_id);
+        }
+    }
+
+    fn render_open_panel(
+        &mut self,
+        ui: &mut Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        let crt = workspace_data.must_get_crt(crt_id.clone());
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        match self.open_panel_enum {
+            GrpcPanelEnum::Message => {
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                    ui.push_id("grpc_message", |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                HighlightTemplateSinglelineBuilder::default()
+                                    .multiline()
+                                    .envs(envs.clone())
+                                    .all_space(true)
+                                    .build("grpc_message".to_string(), &mut crt.rest.grpc.message)
+                                    .ui(ui);
+                            });
+                    });
+                });
+            }
+            GrpcPanelEnum::Authorization => {
+                let parent_auth = crt
+                    .collection_path
+                    .clone()
+                    .map(|path| workspace_data.get_collection_auth(path));
+                self.auth_panel.set_envs(envs.clone(), parent_auth);
+                self.auth_panel.set_client_config(
+                    workspace_data.get_client_config(workspace_data.get_env_select()),
+                );
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                    self.auth_panel
+                        .set_and_render(ui, &mut crt.rest.request.auth);
+                });
+            }
+            GrpcPanelEnum::Metadata => {
+                self.request_headers_panel
+                    .set_and_render(ui, workspace_data, crt_id)
+            }
+            GrpcPanelEnum::PreRequestScript => {
+                let (pre_request_parent_script_scopes, _) =
+                    workspace_data.get_crt_parent_scripts(crt_id.clone());
+                let script = self.request_pre_script_panel.set_and_render(
+                    ui,
+                    operation,
+                    crt.rest.pre_request_script.clone(),
+                    pre_request_parent_script_scopes,
+                    crt.rest.request.clone(),
+                    envs,
+                    workspace_data.get_proxy_config(workspace_data.get_env_select()),
+                    "grpc".to_string(),
+                );
+                workspace_data.must_get_mut_crt(crt_id, |crt| {
+                    crt.rest.pre_request_script = script;
+                });
+            }
+            GrpcPanelEnum::Tests => {
+                let script = self.test_script_panel.set_and_render(
+                    ui,
+                    crt.rest.test_script.clone(),
+                    "grpc".to_string(),
+                );
+                workspace_data.must_get_mut_crt(crt_id, |crt| {
+                    crt.rest.test_script = script;
+                });
+            }
+        }
+    }
+
+    fn load_services(&mut self, workspace_data: &mut WorkspaceData, crt_id: String) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let parent_auth = workspace_data.get_crt_parent_auth(crt_id.clone());
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            crt.rest.request.clear_lock_with();
+            crt.rest.sync(envs.clone(), parent_auth.clone());
+        });
+        let crt = workspace_data.must_get_crt(crt_id.clone());
+        let workspace_dir = workspace_data.get_workspace_dir();
+        let promise = Promise::spawn_thread("grpc_load_services", move || {
+            GrpcSender::load_descriptor(crt.rest.request, crt.rest.grpc, envs, workspace_dir)
+        });
+        self.load_promise = Some((crt_id, promise));
+    }
+
+    fn load_promise(&mut self, ui: &mut Ui, operation: &Operation) {
+        if let Some((crt_id, promise)) = &self.load_promise {
+            if let Some(result) = promise.ready() {
+                match result {
+                    Ok(pool) => {
+                        self.pools.insert(crt_id.clone(), pool.clone());
+                        operation.add_success_toast("Load services success.");
+                    }
+                    Err(e) => {
+                        operation.add_error_toast(format!("Load services failed: {}", e));
+                    }
+                }
+                self.load_promise = None;
+            } else {
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+
+    fn cancel(
+        &mut self,
+        workspace_data: &mut WorkspaceData,
+        operation: &Operation,
+        crt_id: String,
+    ) {
+        if let Some(stream) = self.send_stream.take() {
+            stream.cancel();
+        }
+        self.send_promise = None;
+        self.send_rest = None;
+        workspace_data.must_get_mut_crt(crt_id, |crt| crt.rest.cancelled());
+        operation.add_success_toast("Request cancelled.");
+    }
+
+    fn send_promise(
+        &mut self,
+        ui: &mut Ui,
+        workspace_data: &mut WorkspaceData,
+        operation: &Operation,
+        crt_id: String,
+    ) {
+        if let Some(promise) = &self.send_promise {
+            if let Some(result) = promise.ready() {
+                if let Some(send_rest) = self.send_rest.take() {
+                    match result {
+                        Ok((request, response, _)) => workspace_data
+                            .history_record(send_rest, Some((request.clone(), response.clone()))),
+                        Err(_) => workspace_data.history_record(send_rest, None),
+                    }
+                }
+                let mut variable_logger = Logger::default();
+                if let Some(stream) = &self.send_stream {
+                    workspace_data.save_crt_variable_changes(
+                        crt_id.clone(),
+                        &stream.variable_changes(),
+                        &mut variable_logger,
+                    );
+                }
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| match result {
+                    Ok((request, response, test_result)) => {
+                        request
+                            .headers
+                            .iter()
+                            .filter(|h| h.lock_with != LockWith::NoLock)
+                            .for_each(|h| {
+                                crt.rest.request.headers.push(h.clone());
+                            });
+                        crt.rest.response = response.clone();
+                        crt.rest
+                            .response
+                            .logger
+                            .logs
+                            .extend(variable_logger.logs.clone());
+                        crt.rest.ready();
+                        if response.status == 0 {
+                            operation.add_success_toast("Invoke success");
+                        } else {
+                            operation.add_error_toast(format!(
+                                "Invoke failed: {}",
+                                response.status_text
+                            ));
+                        }
+                        crt.test_result = test_result.clone();
+                        match test_result.status {
+                            TestStatus::None => {}
+                            TestStatus::PASS => {
+                                operation.add_success_toast("Test success.");
+                            }
+                            TestStatus::FAIL => {
+                                operation.add_error_toast("Test failed.");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        crt.rest.error();
+                        operation.add_error_toast(format!("Invoke failed: {}", e));
+                    }
+                });
+                self.send_promise = None;
+                self.send_stream = None;
+            } else if let Some(stream) = self.send_stream.clone().filter(|s| s.is_stopped()) {
+                let mut response = stream.to_response();
+                workspace_data.save_crt_variable_changes(
+                    crt_id.clone(),
+                    &stream.variable_changes(),
+                    &mut response.logger,
+                );
+                if let Some(send_rest) = self.send_rest.take() {
+                    workspace_data.history_record(
+                        send_rest.clone(),
+                        Some((send_rest.request, response.clone())),
+                    );
+                }
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                    crt.rest.response = response.clone();
+                    crt.rest.ready();
+                });
+                operation.add_success_toast("Stream stopped.");
+                self.send_promise = None;
+                self.send_stream = None;
+            } else {
+                ui.ctx().request_repaint();
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| crt.rest.pending());
+            }
+        }
+    }
+}
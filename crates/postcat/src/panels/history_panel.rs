@@ -1,16 +1,27 @@
+use std::fs::File;
+use std::io::Write;
+
 use egui::CollapsingHeader;
 
 use crate::data::central_request_data::CentralRequestItem;
+use crate::data::har::Har;
+use crate::data::history::HistoryRestItem;
 use crate::data::workspace_data::WorkspaceData;
+use crate::operation::operation::Operation;
 use crate::utils;
 
 #[derive(Default)]
 pub struct HistoryPanel {}
 
 impl HistoryPanel {
-    pub fn set_and_render(&mut self, ui: &mut egui::Ui, workspace_data: &mut WorkspaceData) {
+    pub fn set_and_render(
+        &mut self,
+        ui: &mut egui::Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+    ) {
         for (date, date_history_data) in workspace_data.get_history_group().iter().rev() {
-            CollapsingHeader::new(date.to_string())
+            let collapsing_response = CollapsingHeader::new(date.to_string())
                 .default_open(false)
                 .show(ui, |ui| {
                     for history_rest_item in date_history_data.history_list.iter().rev() {
@@ -25,8 +36,60 @@ impl HistoryPanel {
                                 ..Default::default()
                             })
                         }
+                        button.context_menu(|ui| {
+                            if utils::select_label(ui, "Export HAR").clicked() {
+                                ui.close_menu();
+                                Self::export_har(
+                                    operation,
+                                    vec![history_rest_item.clone()],
+                                    format!("history-{}.har", history_rest_item.id),
+                                );
+                            }
+                        });
                     }
                 });
+            collapsing_response.header_response.context_menu(|ui| {
+                if utils::select_label(ui, "Export HAR").clicked() {
+                    ui.close_menu();
+                    Self::export_har(
+                        operation,
+                        date_history_data.history_list.clone(),
+                        format!("history-{}.har", date),
+                    );
+                }
+            });
+        }
+    }
+
+    fn export_har(operation: &Operation, items: Vec<HistoryRestItem>, file_name: String) {
+        let exchanges = items
+            .into_iter()
+            .filter_map(|item| {
+                item.response.map(|response| {
+                    (
+                        item.record_date,
+                        item.request.unwrap_or(item.rest.request),
+                        response,
+                    )
+                })
+            })
+            .collect();
+        if let Ok(json) = serde_json::to_string_pretty(&Har::new(exchanges)) {
+            if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
+                match File::create(path) {
+                    Ok(mut file) => match file.write_all(json.as_bytes()) {
+                        Ok(_) => {
+                            operation.add_success_toast("Export HAR success.");
+                        }
+                        Err(e) => {
+                            operation.add_error_toast(format!("Export HAR file failed: {}", e));
+                        }
+                    },
+                    Err(e) => {
+                        operation.add_error_toast(format!("Export HAR file failed: {}", e));
+                    }
+                }
+            }
         }
     }
 }
@@ -46,7 +46,8 @@ impl MyLeftPanel {
         });
         ScrollArea::vertical().show(ui, |ui| match self.open_panel {
             Panel::History => {
-                self.history_panel.set_and_render(ui, workspace_data);
+                self.history_panel
+                    .set_and_render(ui, operation, workspace_data);
             }
             Panel::Collections => {
                 self.collections_panel
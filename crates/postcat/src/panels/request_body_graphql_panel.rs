@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+
+use egui::text::{CCursor, CCursorRange};
+use egui::text_edit::TextEditState;
+use egui::{Button, Color32, RichText, Ui, Widget};
+use poll_promise::Promise;
+use serde_json::Value;
+
+use crate::data::graphql::{GraphQLField, GraphQLSchema, INTROSPECTION_QUERY};
+use crate::data::http::{BodyType, GraphQLBody, HttpBody, Method};
+use crate::data::workspace_data::WorkspaceData;
+use crate::operation::operation::Operation;
+use crate::operation::rest_sender::RestSender;
+use crate::panels::VERTICAL_GAP;
+use crate::utils;
+use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
+
+const MAX_SUGGESTIONS: usize = 20;
+
+#[derive(Default)]
+pub struct RequestBodyGraphQLPanel {
+    schemas: BTreeMap<String, GraphQLSchema>,
+    fetch_promise: Option<(String, Promise<Result<GraphQLSchema, String>>)>,
+    query_cursor: Option<usize>,
+    explorer_open: bool,
+    explorer_path: Vec<String>,
+    explorer_filter: String,
+}
+
+impl RequestBodyGraphQLPanel {
+    pub fn set_and_render(
+        &mut self,
+        ui: &mut Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let crt = workspace_data.must_get_crt(crt_id.clone());
+        let url = utils::replace_variable(crt.rest.request.base_url.clone(), envs.clone());
+        self.fetch_promise(ui, operation);
+        ui.horizontal(|ui| {
+            ui.label("Operation Name");
+            workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                ui.text_edit_singleline(&mut crt.rest.request.body.body_graphql.operation_name);
+            });
+            if self.fetch_promise.is_some() {
+                ui.add_enabled(false, Button::new("Fetch Schema"));
+                ui.spinner();
+            } else if ui.button("Fetch Schema").clicked() {
+                self.fetch_schema(workspace_data, crt_id.clone(), url.clone());
+            }
+            ui.checkbox(&mut self.explorer_open, "Schema Explorer");
+        });
+        ui.add_space(VERTICAL_GAP);
+        let schema = self.schemas.get(url.as_str()).cloned();
+        if self.explorer_open {
+            egui::SidePanel::right("graphql_schema_explorer")
+                .resizable(true)
+                .show_inside(ui, |ui| match &schema {
+                    None => {
+                        ui.label("Fetch the schema to explore it.");
+                    }
+                    Some(schema) => self.render_explorer(ui, schema),
+                });
+        }
+        ui.label("Query");
+        let mut query_id = None;
+        ui.push_id("request_body_graphql_query", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                        let output = egui::TextEdit::multiline(
+                            &mut crt.rest.request.body.body_graphql.query,
+                        )
+                        .font(egui::TextStyle::Monospace)
+                        .code_editor()
+                        .desired_rows(8)
+                        .desired_width(f32::INFINITY)
+                        .show(ui);
+                        if let Some(c) = output.cursor_range {
+                            self.query_cursor = Some(c.primary.ccursor.index);
+                        }
+                        query_id = Some(output.response.id);
+                    });
+                });
+        });
+        let query = workspace_data
+            .must_get_crt(crt_id.clone())
+            .rest
+            .request
+            .body
+            .body_graphql
+            .query;
+        if let (Some(schema), Some(query_id)) = (&schema, query_id) {
+            self.render_suggestions(
+                ui,
+                workspace_data,
+                crt_id.clone(),
+                schema,
+                query.as_str(),
+                query_id,
+            );
+            for error in schema.validate(query.as_str()).iter() {
+                ui.label(
+                    RichText::new(format!("Line {}: {}", error.line, error.msg))
+                        .color(Color32::RED),
+                );
+            }
+        }
+        ui.add_space(VERTICAL_GAP);
+        ui.label("Variables");
+        let variables = crt.rest.request.body.body_graphql.variables.clone();
+        ui.push_id("request_body_graphql_variables", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(100.0)
+                .show(ui, |ui| {
+                    workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                        HighlightTemplateSinglelineBuilder::default()
+                            .multiline()
+                            .envs(envs.clone())
+                            .all_space(true)
+                            .build(
+                                "request_body_graphql_variables".to_string(),
+                                &mut crt.rest.request.body.body_graphql.variables,
+                            )
+                            .ui(ui);
+                    });
+                });
+        });
+        let variables = utils::replace_variable(variables, envs);
+        if variables.trim() != "" {
+            if let Err(e) = serde_json::from_str::<Value>(variables.as_str()) {
+                ui.label(RichText::new(format!("Invalid variables: {}", e)).color(Color32::RED));
+            }
+        }
+    }
+
+    fn render_suggestions(
+        &mut self,
+        ui: &mut Ui,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        schema: &GraphQLSchema,
+        query: &str,
+        query_id: egui::Id,
+    ) {
+        let cursor = match self.query_cursor {
+            Some(cursor) if cursor <= query.chars().count() => cursor,
+            _ => return,
+        };
+        let (prefix, fields) = schema.complete(query, cursor);
+        if fields.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for field in fields.iter().take(MAX_SUGGESTIONS) {
+                let button = ui
+                    .small_button(field.name.as_str())
+                    .on_hover_text(Self::field_signature(field));
+                if button.clicked() {
+                    let insert = field.name[prefix.len()..].to_string();
+                    let new_cursor = cursor + insert.chars().count();
+                    workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                        let query = &mut crt.rest.request.body.body_graphql.query;
+                        let byte_index = query
+                            .char_indices()
+                            .nth(cursor)
+                            .map(|(i, _)| i)
+                            .unwrap_or(query.len());
+                        query.insert_str(byte_index, insert.as_str());
+                    });
+                    if let Some(mut state) = TextEditState::load(ui.ctx(), query_id) {
+                        state.set_ccursor_range(Some(CCursorRange::one(CCursor::new(new_cursor))));
+                        state.store(ui.ctx(), query_id);
+                    }
+                    ui.memory_mut(|mem| mem.request_focus(query_id));
+                    self.query_cursor = Some(new_cursor);
+                }
+            }
+        });
+    }
+
+    fn render_explorer(&mut self, ui: &mut Ui, schema: &GraphQLSchema) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.explorer_path.is_empty(), Button::new("⬅"))
+                .clicked()
+            {
+                self.explorer_path.pop();
+            }
+            utils::text_edit_singleline_filter_justify(ui, &mut self.explorer_filter);
+        });
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| match self.explorer_path.last().cloned() {
+            None => {
+                for root in schema.root_types().iter() {
+                    if ui.link(root.as_str()).clicked() {
+                        self.explorer_path.push(root.clone());
+                    }
+                }
+                ui.separator();
+                ui.collapsing("All Types", |ui| {
+                    for name in schema
+                        .types
+                        .keys()
+                        .filter(|name| !name.starts_with("__"))
+                        .filter(|name| {
+                            name.to_lowercase()
+                                .contains(self.explorer_filter.to_lowercase().as_str())
+                        })
+                    {
+                        if ui.link(name.as_str()).clicked() {
+                            self.explorer_path.push(name.clone());
+                        }
+                    }
+                });
+            }
+            Some(type_name) => match schema.types.get(type_name.as_str()) {
+                None => {
+                    ui.label(format!("Unknown type `{}`", type_name));
+                }
+                Some(t) => {
+                    ui.strong(format!("{} ({})", t.name, t.kind));
+                    if t.description != "" {
+                        ui.label(t.description.as_str());
+                    }
+                    ui.separator();
+                    for field in t.fields.iter().chain(t.input_fields.iter()).filter(|f| {
+                        f.name
+                            .to_lowercase()
+                            .contains(self.explorer_filter.to_lowercase().as_str())
+                    }) {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.monospace(Self::field_signature(field));
+                            if schema.types.contains_key(field.named_type.as_str())
+                                && ui.link(field.named_type.as_str()).clicked()
+                            {
+                                self.explorer_path.push(field.named_type.clone());
+                            }
+                        });
+                        if field.description != "" {
+                            ui.weak(field.description.as_str());
+                        }
+                    }
+                    for value in t.enum_values.iter() {
+                        ui.monospace(value.as_str());
+                    }
+                }
+            },
+        });
+    }
+
+    fn field_signature(field: &GraphQLField) -> String {
+        let args: Vec<String> = field
+            .args
+            .iter()
+            .map(|a| format!("{}: {}", a.name, a.type_name))
+            .collect();
+        if args.is_empty() {
+            format!("{}: {}", field.name, field.type_name)
+        } else {
+            format!("{}({}): {}", field.name, args.join(", "), field.type_name)
+        }
+    }
+
+    fn fetch_schema(&mut self, workspace_data: &mut WorkspaceData, crt_id: String, url: String) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let parent_auth = workspace_data.get_crt_parent_auth(crt_id.clone());
+        let mut rest = workspace_data.must_get_crt(crt_id).rest;
+        let env_select = workspace_data.get_env_select();
+        let client = workspace_data.build_http_client_with(
+            utils::replace_variable(url.clone(), envs.clone()).as_str(),
+            &rest.settings,
+            env_select,
+        );
+        rest.request.base_url = url.clone();
+        rest.request.method = Method::POST;
+        rest.request.body = HttpBody {
+            body_type: BodyType::GRAPHQL,
+            body_graphql: GraphQLBody {
+                query: INTROSPECTION_QUERY.to_string(),
+                variables: "".to_string(),
+                operation_name: "IntrospectionQuery".to_string(),
+            },
+            ..Default::default()
+        };
+        rest.request.clear_lock_with();
+        rest.sync(envs.clone(), parent_auth);
+        let settings = rest.settings.clone();
+        let cookie_store = workspace_data.get_cookie_store();
+        let workspace_dir = workspace_data.get_workspace_dir();
+        let request = RestSender::build_request(rest.request, envs);
+        let promise = Promise::spawn_thread("graphql_introspection", move || {
+            let (_, response) = RestSender::reqwest_block_send(
+                request,
+                client?,
+                settings,
+                cookie_store,
+                workspace_dir,
+            )?;
+            let value: Value = serde_json::from_slice(response.body.to_vec().as_slice())
+                .map_err(|e| format!("{} {}", response.status_text, e))?;
+            GraphQLSchema::from_introspection(&value)
+        });
+        self.fetch_promise = Some((url, promise));
+    }
+
+    fn fetch_promise(&mut self, ui: &mut Ui, operation: &Operation) {
+        if let Some((url, promise)) = &self.fetch_promise {
+            if let Some(result) = promise.ready() {
+                match result {
+                    Ok(schema) => {
+                        self.schemas.insert(url.clone(), schema.clone());
+                        self.explorer_path.clear();
+                        operation.add_success_toast("Fetch schema success.");
+                    }
+                    Err(e) => {
+                        operation.add_error_toast(format!("Fetch schema failed: {}", e));
+                    }
+                }
+                self.fetch_promise = None;
+            } else {
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+}
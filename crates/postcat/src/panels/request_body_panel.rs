@@ -1,10 +1,11 @@
 use egui::{Ui, Widget};
 use strum::IntoEnumIterator;
 
-use crate::data::http::{BodyRawType, BodyType};
+use crate::data::http::{BodyRawType, BodyType, Method};
 use crate::data::workspace_data::WorkspaceData;
 use crate::operation::operation::Operation;
 use crate::panels::request_body_form_data_panel::RequestBodyFormDataPanel;
+use crate::panels::request_body_graphql_panel::RequestBodyGraphQLPanel;
 use crate::panels::request_body_xxx_form_panel::RequestBodyXXXFormPanel;
 use crate::panels::{DataView, HORIZONTAL_GAP, VERTICAL_GAP};
 use crate::utils;
@@ -14,6 +15,7 @@ use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
 pub struct RequestBodyPanel {
     request_body_form_data_panel: RequestBodyFormDataPanel,
     request_body_xxx_form_panel: RequestBodyXXXFormPanel,
+    request_body_graphql_panel: RequestBodyGraphQLPanel,
 }
 
 impl RequestBodyPanel {
@@ -30,12 +32,18 @@ impl RequestBodyPanel {
             ui.add_space(HORIZONTAL_GAP);
             for x in BodyType::iter() {
                 workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
-                    utils::selectable_check(
+                    if utils::selectable_check(
                         ui,
                         &mut crt.rest.request.body.body_type,
                         x.clone(),
                         x.to_string(),
-                    );
+                    )
+                    .changed()
+                        && x == BodyType::GRAPHQL
+                        && crt.rest.request.method == Method::GET
+                    {
+                        crt.rest.request.method = Method::POST;
+                    }
                 });
             }
             if crt.rest.request.body.body_type == BodyType::RAW {
@@ -88,6 +96,12 @@ impl RequestBodyPanel {
                         });
                 });
             }
+            BodyType::GRAPHQL => self.request_body_graphql_panel.set_and_render(
+                ui,
+                operation,
+                workspace_data,
+                crt_id,
+            ),
             BodyType::BINARY => {
                 let mut button_name =
                     utils::build_with_count_ui_header("Select File".to_string(), 0, ui);
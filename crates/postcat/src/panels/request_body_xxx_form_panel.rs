@@ -3,10 +3,11 @@ use std::collections::BTreeMap;
 use eframe::emath::Align;
 use egui::{Button, Checkbox, Layout, TextBuffer, TextEdit, Widget};
 use egui_extras::{Column, TableBody, TableBuilder};
+use strum::IntoEnumIterator;
 
 use crate::data::central_request_data::CentralRequestItem;
 use crate::data::environment::EnvironmentItemValue;
-use crate::data::http::MultipartData;
+use crate::data::http::{FormEncoding, MultipartData};
 use crate::data::workspace_data::WorkspaceData;
 use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
 
@@ -30,6 +31,7 @@ impl RequestBodyXXXFormPanel {
                 .cell_layout(Layout::left_to_right(Align::Center))
                 .column(Column::auto())
                 .column(Column::exact(20.0))
+                .column(Column::exact(100.0))
                 .column(Column::initial(200.0).range(40.0..=300.0))
                 .column(Column::initial(200.0).range(40.0..=300.0))
                 .column(Column::remainder())
@@ -42,6 +44,9 @@ impl RequestBodyXXXFormPanel {
                     header.col(|ui| {
                         ui.strong("");
                     });
+                    header.col(|ui| {
+                        ui.strong("ENCODING");
+                    });
                     header.col(|ui| {
                         ui.strong("KEY");
                     });
@@ -97,6 +102,13 @@ impl RequestBodyXXXFormPanel {
                         delete_index = Some(index)
                     }
                 });
+                row.col(|ui| {
+                    Self::encoding_combo(
+                        ui,
+                        "form_encoding_".to_string() + index.to_string().as_str(),
+                        &mut param.encoding,
+                    );
+                });
                 row.col(|ui| {
                     HighlightTemplateSinglelineBuilder::default()
                         .envs(envs.clone())
@@ -139,6 +151,13 @@ impl RequestBodyXXXFormPanel {
             row.col(|ui| {
                 ui.add_enabled(false, Button::new("x"));
             });
+            row.col(|ui| {
+                Self::encoding_combo(
+                    ui,
+                    "form_encoding_new".to_string(),
+                    &mut self.new_form.encoding,
+                );
+            });
             row.col(|ui| {
                 HighlightTemplateSinglelineBuilder::default()
                     .envs(envs.clone())
@@ -163,4 +182,18 @@ impl RequestBodyXXXFormPanel {
             });
         });
     }
+
+    fn encoding_combo(ui: &mut egui::Ui, id: String, encoding: &mut FormEncoding) {
+        egui::ComboBox::from_id_source(id)
+            .selected_text(encoding.to_string())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(60.0);
+                for x in FormEncoding::iter() {
+                    ui.selectable_value(encoding, x.clone(), x.to_string());
+                }
+            })
+            .response
+            .on_hover_text("Raw sends the key and value exactly as typed.");
+    }
 }
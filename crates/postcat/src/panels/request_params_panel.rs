@@ -8,6 +8,7 @@ use crate::data::central_request_data::CentralRequestItem;
 use crate::data::environment::EnvironmentItemValue;
 use crate::data::http::{LockWith, QueryParam};
 use crate::data::workspace_data::WorkspaceData;
+use crate::panels::VERTICAL_GAP;
 use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
 
 #[derive(Default)]
@@ -68,6 +69,14 @@ impl RequestParamsPanel {
                 self.new_param.desc = "".to_string();
                 self.new_param.enable = false;
             }
+            crt.rest.request.sync_path_variables();
+            if !crt.rest.request.path_variables.is_empty() {
+                ui.add_space(VERTICAL_GAP);
+                ui.label("Path Variables");
+                ui.push_id("request_path_variables", |ui| {
+                    Self::build_path_variables(ui, crt, &envs);
+                });
+            }
         });
     }
 }
@@ -129,6 +138,57 @@ impl RequestParamsPanel {
         delete_index
     }
 
+    fn build_path_variables(
+        ui: &mut egui::Ui,
+        data: &mut CentralRequestItem,
+        envs: &BTreeMap<String, EnvironmentItemValue>,
+    ) {
+        let table = TableBuilder::new(ui)
+            .resizable(false)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::initial(200.0).range(40.0..=300.0))
+            .column(Column::initial(200.0).range(40.0..=300.0))
+            .column(Column::remainder())
+            .max_scroll_height(100.0);
+        table
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("KEY");
+                });
+                header.col(|ui| {
+                    ui.strong("VALUE");
+                });
+                header.col(|ui| {
+                    ui.strong("DESCRIPTION");
+                });
+            })
+            .body(|mut body| {
+                for (index, variable) in data.rest.request.path_variables.iter_mut().enumerate() {
+                    body.row(18.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(variable.key.as_str());
+                        });
+                        row.col(|ui| {
+                            HighlightTemplateSinglelineBuilder::default()
+                                .envs(envs.clone())
+                                .all_space(false)
+                                .build(
+                                    "request_path_variable_value_".to_string()
+                                        + index.to_string().as_str(),
+                                    &mut variable.value,
+                                )
+                                .ui(ui);
+                        });
+                        row.col(|ui| {
+                            TextEdit::singleline(&mut variable.desc)
+                                .desired_width(f32::INFINITY)
+                                .ui(ui);
+                        });
+                    });
+                }
+            });
+    }
+
     fn build_new_body(
         &mut self,
         envs: BTreeMap<String, EnvironmentItemValue>,
@@ -5,6 +5,7 @@ use egui::Ui;
 
 use crate::data::environment::EnvironmentItemValue;
 use crate::data::http::Request;
+use crate::data::network_settings::ProxyConfig;
 use crate::operation::operation::Operation;
 use crate::panels::HORIZONTAL_GAP;
 use crate::script::script::{Context, ScriptScope};
@@ -22,6 +23,7 @@ impl RequestPreScriptPanel {
         mut parent_scripts: Vec<ScriptScope>,
         request: Request,
         envs: BTreeMap<String, EnvironmentItemValue>,
+        proxy: ProxyConfig,
         id: String,
     ) -> String {
         let theme = egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx());
@@ -48,6 +50,7 @@ impl RequestPreScriptPanel {
                             scope_name: "request".to_string(),
                             request: request.clone(),
                             envs,
+                            proxy,
                             ..Default::default()
                         };
                         operation.add_window(Box::new(TestScriptWindows::default().with(parent_scripts, context)));
@@ -70,6 +73,12 @@ impl RequestPreScriptPanel {
                         if ui.link("Set a variable").clicked() {
                             script = script.clone().add("\npostcat.set_env(\"variable_key\",\"variable_value\");");
                         }
+                        if ui.link("Save an environment variable").clicked() {
+                            script = script.clone().add("\npostcat.environment.set(\"variable_key\",\"variable_value\");");
+                        }
+                        if ui.link("Save a collection variable").clicked() {
+                            script = script.clone().add("\npostcat.collectionVariables.set(\"variable_key\",\"variable_value\");");
+                        }
                         if ui.link("Add a header").clicked() {
                             script = script.clone().add("\npostcat.add_header(\"header_key\",\"header_value\");");
                         }
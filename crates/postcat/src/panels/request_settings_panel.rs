@@ -0,0 +1,73 @@
+use egui::{DragValue, Ui, Widget};
+use strum::IntoEnumIterator;
+
+use crate::data::network_settings::{HttpVersion, SslVerification};
+use crate::data::workspace_data::WorkspaceData;
+use crate::panels::VERTICAL_GAP;
+
+#[derive(Default)]
+pub struct RequestSettingsPanel {}
+
+impl RequestSettingsPanel {
+    pub fn set_and_render(
+        &mut self,
+        ui: &mut Ui,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            ui.label("Settings");
+            ui.add_space(VERTICAL_GAP);
+            let settings = &mut crt.rest.settings;
+            egui::Grid::new("request_settings_grid")
+                .num_columns(2)
+                .spacing([20.0, 4.0])
+                .show(ui, |ui| {
+                    ui.strong("SSL Certificate Verification")
+                        .on_hover_text("Inherit uses the selected environment, then the workspace network settings.");
+                    egui::ComboBox::from_id_source("request_ssl_verification")
+                        .selected_text(settings.ssl_verification.to_string())
+                        .show_ui(ui, |ui| {
+                            for x in SslVerification::iter() {
+                                ui.selectable_value(
+                                    &mut settings.ssl_verification,
+                                    x.clone(),
+                                    x.to_string(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                    ui.strong("Timeout (ms)")
+                        .on_hover_text("Set to 0 to never time out.");
+                    DragValue::new(&mut settings.timeout).speed(100).ui(ui);
+                    ui.end_row();
+                    ui.strong("Follow Redirects");
+                    ui.checkbox(&mut settings.follow_redirects, "");
+                    ui.end_row();
+                    ui.strong("Max Redirects");
+                    ui.add_enabled(
+                        settings.follow_redirects,
+                        DragValue::new(&mut settings.max_redirects).clamp_range(0..=100),
+                    );
+                    ui.end_row();
+                    ui.strong("HTTP Version");
+                    egui::ComboBox::from_id_source("request_http_version")
+                        .selected_text(settings.http_version.to_string())
+                        .show_ui(ui, |ui| {
+                            for x in HttpVersion::iter() {
+                                ui.selectable_value(
+                                    &mut settings.http_version,
+                                    x.clone(),
+                                    x.to_string(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                    ui.strong("Disable Decompression")
+                        .on_hover_text("Keep gzip, brotli and deflate bodies as they are sent by the server.");
+                    ui.checkbox(&mut settings.disable_decompression, "");
+                    ui.end_row();
+                });
+        });
+    }
+}
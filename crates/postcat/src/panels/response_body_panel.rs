@@ -1,10 +1,50 @@
-use egui::{Image, TextBuffer};
+use eframe::emath::Align;
+use egui::{Image, Layout, TextBuffer};
+use egui_extras::{Column, TableBuilder};
 
 use crate::data::http::Response;
+use crate::data::stream::{ResponseStream, SseEvent, SseParser};
 use crate::data::workspace_data::WorkspaceData;
 
 #[derive(Default)]
-pub struct ResponseBodyPanel {}
+pub struct ResponseBodyPanel {
+    show_raw: bool,
+    stream_cache: StreamCache,
+}
+
+/// What has been read from the live stream so far, so each frame only copies new data.
+#[derive(Default)]
+struct StreamCache {
+    id: usize,
+    received: usize,
+    text: String,
+    pending: Vec<u8>,
+    events: Vec<SseEvent>,
+}
+
+impl StreamCache {
+    fn update(&mut self, stream: &ResponseStream) {
+        if self.id != stream.id() {
+            *self = StreamCache {
+                id: stream.id(),
+                ..Default::default()
+            };
+        }
+        let (body, events) = stream.read_since(self.received, self.events.len());
+        self.received += body.len();
+        self.events.extend(events);
+        self.pending.extend(body);
+        let valid = match std::str::from_utf8(self.pending.as_slice()) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let rest = self.pending.split_off(valid);
+        self.text
+            .push_str(String::from_utf8_lossy(self.pending.as_slice()).as_ref());
+        self.pending = rest;
+    }
+}
 
 impl ResponseBodyPanel {
     pub fn set_and_render(
@@ -34,7 +74,15 @@ impl ResponseBodyPanel {
                         crt.rest.response.body.to_vec(),
                     );
                     ui.add(image);
+                } else if content_type.value.starts_with("text/event-stream") && !self.show_raw {
+                    ui.selectable_value(&mut self.show_raw, true, "Raw");
+                    let body = String::from_utf8_lossy(crt.rest.response.body.to_vec().as_slice())
+                        .to_string();
+                    Self::render_events(ui, &SseParser::parse(body.as_str()));
                 } else {
+                    if content_type.value.starts_with("text/event-stream") {
+                        ui.selectable_value(&mut self.show_raw, false, "Events");
+                    }
                     match String::from_utf8(crt.rest.response.body.to_vec()) {
                         Ok(s) => {
                             let tooltip = "Click to copy the response body";
@@ -66,6 +114,91 @@ impl ResponseBodyPanel {
             }
         }
     }
+    pub fn render_stream(&mut self, ui: &mut egui::Ui, stream: &ResponseStream) {
+        let summary = stream.summary();
+        self.stream_cache.update(stream);
+        ui.horizontal(|ui| {
+            if ui.button("Stop").clicked() {
+                stream.stop();
+            }
+            ui.label(format!("Status: {}", summary.status));
+            ui.label(format!("Received: {} bytes", summary.received));
+            if summary.event_stream {
+                ui.label(format!("Events: {}", summary.events));
+            }
+        });
+        ui.separator();
+        if summary.event_stream && !self.show_raw {
+            ui.selectable_value(&mut self.show_raw, true, "Raw");
+            Self::render_events(ui, &self.stream_cache.events);
+        } else {
+            if summary.event_stream {
+                ui.selectable_value(&mut self.show_raw, false, "Events");
+            }
+            let mut content = self.stream_cache.text.as_str();
+            ui.push_id("response_stream_body", |ui| {
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut content)
+                                .font(egui::TextStyle::Monospace)
+                                .code_editor()
+                                .desired_rows(10)
+                                .lock_focus(true)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+            });
+        }
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    fn render_events(ui: &mut egui::Ui, events: &[SseEvent]) {
+        ui.push_id("response_events_table", |ui| {
+            let table = TableBuilder::new(ui)
+                .resizable(true)
+                .stick_to_bottom(true)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::initial(80.0).range(40.0..=200.0))
+                .column(Column::initial(120.0).range(40.0..=300.0))
+                .column(Column::remainder())
+                .min_scrolled_height(200.0);
+            table
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("ID");
+                    });
+                    header.col(|ui| {
+                        ui.strong("EVENT");
+                    });
+                    header.col(|ui| {
+                        ui.strong("DATA");
+                    });
+                })
+                .body(|body| {
+                    body.rows(18.0, events.len(), |index, mut row| {
+                        let event = &events[index];
+                        row.col(|ui| {
+                            ui.label(event.id.as_str());
+                        });
+                        row.col(|ui| {
+                            if event.event.is_empty() {
+                                ui.label("message");
+                            } else {
+                                ui.label(event.event.as_str());
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(event.data.as_str())
+                                .on_hover_text(event.data.as_str());
+                        });
+                    });
+                });
+        });
+    }
+
     fn get_language(response: &Response) -> String {
         match response.headers.iter().find(|h| h.key == "content-type") {
             None => "json".to_string(),
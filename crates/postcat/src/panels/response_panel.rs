@@ -7,6 +7,7 @@ use strum_macros::{Display, EnumIter, EnumString};
 use crate::data::central_request_data::CentralRequestItem;
 use crate::data::cookies_manager::Cookie;
 use crate::data::http::{Response, ResponseStatus};
+use crate::data::stream::ResponseStream;
 use crate::data::test::{TestResult, TestStatus};
 use crate::data::workspace_data::WorkspaceData;
 use crate::operation::operation::Operation;
@@ -14,6 +15,8 @@ use crate::panels::response_body_panel::ResponseBodyPanel;
 use crate::panels::response_cookies_panel::ResponseCookiesPanel;
 use crate::panels::response_headers_panel::ResponseHeadersPanel;
 use crate::panels::response_log_panel::ResponseLogPanel;
+use crate::panels::response_redirects_panel::ResponseRedirectsPanel;
+use crate::panels::response_timing_panel::ResponseTimingPanel;
 use crate::panels::test_result_panel::TestResultPanel;
 use crate::panels::DataView;
 use crate::utils;
@@ -25,6 +28,8 @@ pub struct ResponsePanel {
     response_headers_panel: ResponseHeadersPanel,
     response_cookies_panel: ResponseCookiesPanel,
     response_log_panel: ResponseLogPanel,
+    response_redirects_panel: ResponseRedirectsPanel,
+    response_timing_panel: ResponseTimingPanel,
     test_result_panel: TestResultPanel,
 }
 
@@ -33,6 +38,8 @@ enum ResponsePanelEnum {
     Body,
     Cookies,
     Headers,
+    Redirects,
+    Timing,
     Logs,
     TestResult,
 }
@@ -50,6 +57,7 @@ impl ResponsePanel {
         operation: &Operation,
         workspace_data: &mut WorkspaceData,
         crt_id: String,
+        stream: Option<&ResponseStream>,
     ) {
         let crt = workspace_data.must_get_crt(crt_id.clone());
         let cookies = workspace_data.get_url_cookies(crt.rest.request.base_url.clone());
@@ -61,11 +69,18 @@ impl ResponsePanel {
                     ui.label("Hit the Send button to get a response");
                 });
             }
-            ResponseStatus::Pending => {
-                ui.centered_and_justified(|ui| {
-                    ui.label("Loading...");
-                });
-            }
+            ResponseStatus::Pending => match stream.filter(|s| s.started()) {
+                None => {
+                    ui.centered_and_justified(|ui| {
+                        ui.label("Loading...");
+                    });
+                }
+                Some(stream) => {
+                    ui.strong("Response");
+                    ui.separator();
+                    self.response_body_panel.render_stream(ui, stream);
+                }
+            },
 
             ResponseStatus::Ready => {
                 self.build_ready_panel(operation, workspace_data, crt_id, ui, &crt, cookies);
@@ -75,6 +90,11 @@ impl ResponsePanel {
                     ui.label("Could not get any response");
                 });
             }
+            ResponseStatus::Cancelled => {
+                ui.centered_and_justified(|ui| {
+                    ui.label("Request cancelled");
+                });
+            }
         }
     }
     fn get_count(
@@ -87,6 +107,8 @@ impl ResponsePanel {
             ResponsePanelEnum::Body => 0,
             ResponsePanelEnum::Cookies => cookies.len(),
             ResponsePanelEnum::Headers => response.headers.iter().count(),
+            ResponsePanelEnum::Redirects => response.redirects.len(),
+            ResponsePanelEnum::Timing => 0,
             ResponsePanelEnum::Logs => response.logger.logs.len(),
             ResponsePanelEnum::TestResult => match test_result.status {
                 TestStatus::None => 0,
@@ -166,6 +188,17 @@ impl ResponsePanel {
                 self.response_headers_panel
                     .set_and_render(ui, workspace_data, cursor);
             }
+            ResponsePanelEnum::Redirects => {
+                self.response_redirects_panel
+                    .set_and_render(ui, &data.rest.response.redirects);
+            }
+            ResponsePanelEnum::Timing => {
+                self.response_timing_panel.set_and_render(
+                    ui,
+                    &data.rest.response.timing,
+                    &data.rest.response.size,
+                );
+            }
             ResponsePanelEnum::Logs => {
                 self.response_log_panel
                     .set_and_render(ui, workspace_data, cursor);
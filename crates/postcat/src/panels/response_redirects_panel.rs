@@ -0,0 +1,66 @@
+use eframe::emath::Align;
+use egui::{Layout, TextEdit, Widget};
+use egui_extras::{Column, TableBuilder};
+
+use crate::data::http::Redirect;
+
+#[derive(Default)]
+pub struct ResponseRedirectsPanel {}
+
+impl ResponseRedirectsPanel {
+    pub fn set_and_render(&mut self, ui: &mut egui::Ui, redirects: &Vec<Redirect>) {
+        ui.label("Redirects");
+        ui.push_id("response_redirects_table", |ui| {
+            let table = TableBuilder::new(ui)
+                .resizable(false)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::exact(20.0))
+                .column(Column::initial(50.0).range(50.0..=100.0))
+                .column(Column::initial(60.0).range(50.0..=100.0))
+                .column(Column::initial(300.0).range(100.0..=500.0))
+                .column(Column::remainder())
+                .min_scrolled_height(200.0);
+            table
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("#");
+                    });
+                    header.col(|ui| {
+                        ui.strong("STATUS");
+                    });
+                    header.col(|ui| {
+                        ui.strong("METHOD");
+                    });
+                    header.col(|ui| {
+                        ui.strong("URL");
+                    });
+                    header.col(|ui| {
+                        ui.strong("LOCATION");
+                    });
+                })
+                .body(|mut body| {
+                    for (index, redirect) in redirects.iter().enumerate() {
+                        body.row(18.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label((index + 1).to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(redirect.status.to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(redirect.method.clone());
+                            });
+                            row.col(|ui| {
+                                ui.text_edit_singleline(&mut redirect.url.clone());
+                            });
+                            row.col(|ui| {
+                                TextEdit::singleline(&mut redirect.location.clone())
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                            });
+                        });
+                    }
+                });
+        });
+    }
+}
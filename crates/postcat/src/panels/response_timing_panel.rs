@@ -0,0 +1,104 @@
+use egui::{Color32, Rect, Sense, Ui, Vec2};
+
+use crate::data::http::{Timing, TransferSize};
+use crate::panels::VERTICAL_GAP;
+
+const BAR_WIDTH: f32 = 300.0;
+
+#[derive(Default)]
+pub struct ResponseTimingPanel {}
+
+impl ResponseTimingPanel {
+    pub fn set_and_render(&mut self, ui: &mut Ui, timing: &Timing, size: &TransferSize) {
+        ui.label("Timing");
+        let phases = [
+            (
+                "DNS Lookup",
+                timing.dns,
+                Color32::from_rgb(0x4e, 0x9a, 0x06),
+            ),
+            (
+                "TCP Connect",
+                timing.tcp,
+                Color32::from_rgb(0xf5, 0x79, 0x00),
+            ),
+            (
+                "TLS Handshake",
+                timing.tls,
+                Color32::from_rgb(0x75, 0x50, 0x7b),
+            ),
+            (
+                "Waiting (TTFB)",
+                timing.ttfb,
+                Color32::from_rgb(0x34, 0x65, 0xa4),
+            ),
+            (
+                "Download",
+                timing.download,
+                Color32::from_rgb(0x06, 0x98, 0x9a),
+            ),
+        ];
+        let total = phases.iter().map(|(_, d, _)| *d).sum::<u128>().max(1);
+        egui::Grid::new("response_timing_grid")
+            .num_columns(3)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                let mut offset = 0;
+                for (label, duration, color) in phases {
+                    ui.label(label);
+                    let (rect, _) =
+                        ui.allocate_exact_size(Vec2::new(BAR_WIDTH, 12.0), Sense::hover());
+                    let left = rect.left() + BAR_WIDTH * offset as f32 / total as f32;
+                    let width = (BAR_WIDTH * duration as f32 / total as f32).max(1.0);
+                    ui.painter().rect_filled(
+                        Rect::from_min_size(
+                            egui::pos2(left, rect.top()),
+                            Vec2::new(width, rect.height()),
+                        ),
+                        2.0,
+                        color,
+                    );
+                    ui.label(Self::format_duration(duration));
+                    ui.end_row();
+                    offset += duration;
+                }
+                ui.strong("Total");
+                ui.label("");
+                ui.strong(Self::format_duration(timing.total));
+                ui.end_row();
+            });
+        ui.add_space(VERTICAL_GAP * 2.0);
+        ui.label("Size");
+        egui::Grid::new("response_size_grid")
+            .num_columns(3)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("");
+                ui.strong("Headers");
+                ui.strong("Body");
+                ui.end_row();
+                ui.label("Request");
+                ui.label(Self::format_size(size.request_headers));
+                ui.label(Self::format_size(size.request_body));
+                ui.end_row();
+                ui.label("Response");
+                ui.label(Self::format_size(size.response_headers));
+                ui.label(Self::format_size(size.response_body));
+                ui.end_row();
+            });
+    }
+
+    fn format_duration(micros: u128) -> String {
+        format!("{:.2} ms", micros as f64 / 1000.0)
+    }
+
+    fn format_size(size: u64) -> String {
+        if size > 1000000 {
+            format!("{:.2} MB", size as f64 / 1000000.0)
+        } else if size > 1000 {
+            format!("{:.2} KB", size as f64 / 1000.0)
+        } else {
+            format!("{} B", size)
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use egui::ahash::HashSet;
-use egui::{Button, Label, RichText, Ui, Widget};
+use egui::{Label, RichText, Ui, Widget};
 use poll_promise::Promise;
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
 
 use crate::data::auth::{Auth, AuthType};
-use crate::data::http::{BodyType, HttpRecord, LockWith, Method};
+use crate::data::curl::CurlImport;
+use crate::data::http::{BodyType, HttpRecord, LockWith};
+use crate::data::logger::Logger;
+use crate::data::network_settings::RequestSettings;
+use crate::data::stream::ResponseStream;
 use crate::data::test::TestStatus;
 use crate::data::workspace_data::WorkspaceData;
 use crate::data::{http, test};
@@ -15,13 +19,16 @@ use crate::panels::request_body_panel::RequestBodyPanel;
 use crate::panels::request_headers_panel::RequestHeadersPanel;
 use crate::panels::request_params_panel::RequestParamsPanel;
 use crate::panels::request_pre_script_panel::RequestPreScriptPanel;
+use crate::panels::request_settings_panel::RequestSettingsPanel;
 use crate::panels::response_panel::ResponsePanel;
 use crate::panels::test_script_panel::TestScriptPanel;
 use crate::panels::{DataView, HORIZONTAL_GAP};
 use crate::script::script::ScriptScope;
 use crate::utils;
 use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
+use crate::windows::code_windows::CodeWindows;
 use crate::windows::cookies_windows::CookiesWindows;
+use crate::windows::network_settings_windows::NetworkSettingsWindows;
 use crate::windows::save_crt_windows::SaveCRTWindows;
 
 #[derive(Default)]
@@ -34,8 +41,13 @@ pub struct RestPanel {
     response_panel: ResponsePanel,
     request_pre_script_panel: RequestPreScriptPanel,
     test_script_panel: TestScriptPanel,
+    request_settings_panel: RequestSettingsPanel,
     send_promise:
         Option<Promise<Result<(http::Request, http::Response, test::TestResult), String>>>,
+    send_rest: Option<HttpRecord>,
+    send_stream: Option<ResponseStream>,
+    /// The url bar text as typed, kept while it still parses to the current request.
+    url_edit: Option<(String, String, String)>,
 }
 
 #[derive(Clone, EnumIter, EnumString, Display, PartialEq)]
@@ -46,6 +58,7 @@ enum RequestPanelEnum {
     Body,
     PreRequestScript,
     Tests,
+    Settings,
 }
 
 impl Default for RequestPanelEnum {
@@ -65,12 +78,12 @@ impl RestPanel {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
                 ui.add_space(HORIZONTAL_GAP);
-                self.render_name_label(workspace_data, crt_id.clone(), ui);
+                Self::render_name_label(workspace_data, crt_id.clone(), ui);
             });
             ui.separator();
             ui.horizontal(|ui| {
                 self.render_editor_right_panel(operation, workspace_data, crt_id.clone(), ui);
-                self.render_editor_left_panel(workspace_data, crt_id.clone(), ui);
+                self.render_editor_left_panel(operation, workspace_data, crt_id.clone(), ui);
             });
             ui.separator();
             self.render_middle_select(operation, workspace_data, crt_id.clone(), ui);
@@ -79,18 +92,32 @@ impl RestPanel {
         self.send_promise(ui, workspace_data, operation, crt_id.clone());
         self.render_request_open_panel(ui, operation, workspace_data, crt_id.clone());
         ui.separator();
-        self.response_panel
-            .set_and_render(ui, operation, workspace_data, crt_id.clone());
+        self.response_panel.set_and_render(
+            ui,
+            operation,
+            workspace_data,
+            crt_id.clone(),
+            self.send_stream.as_ref(),
+        );
     }
     fn get_count(hr: &HttpRecord, panel_enum: RequestPanelEnum, parnet_auth: &Auth) -> usize {
         match panel_enum {
-            RequestPanelEnum::Params => hr.request.params.iter().filter(|i| i.enable).count(),
+            RequestPanelEnum::Params => {
+                hr.request.params.iter().filter(|i| i.enable).count()
+                    + hr.request.path_variables.len()
+            }
             RequestPanelEnum::Authorization => {
                 match hr.request.auth.get_final_type(parnet_auth.clone()) {
                     AuthType::InheritAuthFromParent => 0,
                     AuthType::NoAuth => 0,
                     AuthType::BearerToken => usize::MAX,
                     AuthType::BasicAuth => usize::MAX,
+                    AuthType::OAuth2 => usize::MAX,
+                    AuthType::AwsSigV4 => usize::MAX,
+                    AuthType::DigestAuth => usize::MAX,
+                    AuthType::ApiKey => usize::MAX,
+                    AuthType::Jwt => usize::MAX,
+                    AuthType::HmacSignature => usize::MAX,
                 }
             }
             RequestPanelEnum::Headers => hr.request.headers.iter().filter(|i| i.enable).count(),
@@ -105,6 +132,13 @@ impl RestPanel {
                         0
                     }
                 }
+                BodyType::GRAPHQL => {
+                    if hr.request.body.body_graphql.query != "" {
+                        usize::MAX
+                    } else {
+                        0
+                    }
+                }
                 BodyType::BINARY => {
                     if hr.request.body.body_file != "" {
                         usize::MAX
@@ -127,11 +161,17 @@ impl RestPanel {
                     0
                 }
             }
+            RequestPanelEnum::Settings => {
+                if hr.settings != RequestSettings::default() {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
         }
     }
 
-    fn render_name_label(
-        &mut self,
+    pub(crate) fn render_name_label(
         workspace_data: &mut WorkspaceData,
         cursor: String,
         ui: &mut Ui,
@@ -169,8 +209,6 @@ impl RestPanel {
         crt_id: String,
         ui: &mut Ui,
     ) {
-        let mut send_rest = None;
-        let client = workspace_data.build_http_client();
         let (mut pre_request_parent_script_scopes, mut test_parent_script_scopes) =
             workspace_data.get_crt_parent_scripts(crt_id.clone());
         let envs = workspace_data.get_crt_envs(crt_id.clone());
@@ -182,7 +220,9 @@ impl RestPanel {
                 ui.horizontal(|ui| {
                     ui.add_space(HORIZONTAL_GAP);
                     if self.send_promise.is_some() {
-                        ui.add_enabled(false, Button::new("Send"));
+                        if ui.button("Cancel").clicked() {
+                            self.cancel(workspace_data, operation, crt_id.clone());
+                        }
                     } else {
                         if ui.button("Send").clicked() {
                             workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
@@ -202,15 +242,28 @@ impl RestPanel {
                                     script: crt.rest.test_script.clone(),
                                 });
                             }
-                            let send_response = operation.send_with_script(
-                                crt.rest.request.clone(),
-                                envs.clone(),
-                                pre_request_parent_script_scopes,
-                                test_parent_script_scopes,
-                                client,
-                            );
-                            self.send_promise = Some(send_response);
-                            send_rest = Some(crt.rest.clone());
+                            match workspace_data.build_crt_http_client(crt_id.clone()) {
+                                Ok(client) => {
+                                    let send_stream = ResponseStream::default();
+                                    let send_response = operation.send_with_script(
+                                        crt.rest.request.clone(),
+                                        envs.clone(),
+                                        parent_auth.clone(),
+                                        workspace_data
+                                            .get_client_config(workspace_data.get_env_select()),
+                                        pre_request_parent_script_scopes,
+                                        test_parent_script_scopes,
+                                        client,
+                                        crt.rest.settings.clone(),
+                                        workspace_data.get_cookie_store(),
+                                        send_stream.clone(),
+                                    );
+                                    self.send_promise = Some(send_response);
+                                    self.send_stream = Some(send_stream);
+                                    self.send_rest = Some(crt.rest.clone());
+                                }
+                                Err(e) => operation.add_error_toast(e),
+                            }
                         }
                     }
                     if ui.button("Save").clicked() {
@@ -235,13 +288,10 @@ impl RestPanel {
                     }
                 });
             });
-
-        send_rest.map(|r| {
-            workspace_data.history_record(r);
-        });
     }
     fn render_editor_left_panel(
-        &self,
+        &mut self,
+        operation: &Operation,
         workspace_data: &mut WorkspaceData,
         cursor: String,
         ui: &mut Ui,
@@ -254,31 +304,73 @@ impl RestPanel {
                 .resizable(false)
                 .show_inside(ui, |ui| {
                     ui.horizontal(|ui| {
-                        egui::ComboBox::from_id_source("method")
-                            .selected_text(crt.rest.request.method.clone().to_string())
-                            .show_ui(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                ui.set_min_width(60.0);
-                                for x in Method::iter() {
-                                    ui.selectable_value(
-                                        &mut crt.rest.request.method,
-                                        x.clone(),
-                                        x.to_string(),
-                                    );
-                                }
-                            });
+                        utils::record_method_combo(ui, &mut crt.rest);
                         let mut filter: HashSet<String> = HashSet::default();
-                        filter.insert("?".to_string());
                         filter.insert(" ".to_string());
-                        filter.insert("&".to_string());
-                        ui.centered_and_justified(|ui| {
-                            HighlightTemplateSinglelineBuilder::default()
-                                .filter(filter)
-                                .envs(envs.clone())
-                                .all_space(false)
-                                .build(cursor.clone() + "url", &mut crt.rest.request.base_url)
-                                .ui(ui);
-                        });
+                        let display_url = crt.rest.request.display_url();
+                        let shown_url = match &self.url_edit {
+                            Some((id, url, typed)) if *id == cursor && *url == display_url => {
+                                typed.clone()
+                            }
+                            _ => display_url,
+                        };
+                        let mut url = shown_url.clone();
+                        let response = ui
+                            .centered_and_justified(|ui| {
+                                HighlightTemplateSinglelineBuilder::default()
+                                    .filter(filter)
+                                    .envs(envs.clone())
+                                    .all_space(false)
+                                    .build(cursor.clone() + "url", &mut url)
+                                    .ui(ui)
+                            })
+                            .inner;
+                        // the single line editor flattens newlines, so a pasted
+                        // multi-line cURL command is read from the raw clipboard text
+                        let pasted_curl = if response.has_focus() {
+                            ui.input(|i| {
+                                i.events.iter().find_map(|event| match event {
+                                    egui::Event::Paste(text) if CurlImport::is_match(text) => {
+                                        Some(text.clone())
+                                    }
+                                    _ => None,
+                                })
+                            })
+                        } else {
+                            None
+                        };
+                        if url != shown_url {
+                            if pasted_curl.is_some() || CurlImport::is_match(url.as_str()) {
+                                let command = pasted_curl.unwrap_or(url.clone());
+                                match CurlImport::parse(command.as_str()) {
+                                    Ok(curl_import) => {
+                                        crt.rest.request = curl_import.request;
+                                        crt.rest.settings = curl_import.settings;
+                                        self.url_edit = None;
+                                        operation.add_success_toast("Import cURL request success.");
+                                        if !curl_import.warnings.is_empty() {
+                                            operation
+                                                .add_error_toast(curl_import.warnings.join("\n"));
+                                        }
+                                    }
+                                    Err(e) => {
+                                        self.url_edit = Some((
+                                            cursor.clone(),
+                                            crt.rest.request.display_url(),
+                                            url,
+                                        ));
+                                        operation.add_error_toast(format!(
+                                            "Error while importing cURL: {}",
+                                            e
+                                        ));
+                                    }
+                                }
+                            } else {
+                                crt.rest.request.set_display_url(url.as_str());
+                                self.url_edit =
+                                    Some((cursor.clone(), crt.rest.request.display_url(), url));
+                            }
+                        }
                     });
                 });
         });
@@ -310,6 +402,9 @@ impl RestPanel {
                     }
                 }
                 self.auth_panel.set_envs(envs.clone(), parent_auth);
+                self.auth_panel.set_client_config(
+                    workspace_data.get_client_config(workspace_data.get_env_select()),
+                );
                 {
                     workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
                         self.auth_panel
@@ -336,6 +431,7 @@ impl RestPanel {
                     pre_request_parent_script_scopes,
                     crt.rest.request.clone(),
                     envs.clone(),
+                    workspace_data.get_proxy_config(workspace_data.get_env_select()),
                     "rest".to_string(),
                 );
                 {
@@ -356,9 +452,28 @@ impl RestPanel {
                     });
                 }
             }
+            RequestPanelEnum::Settings => {
+                self.request_settings_panel
+                    .set_and_render(ui, workspace_data, crt_id.clone())
+            }
         }
     }
 
+    fn cancel(
+        &mut self,
+        workspace_data: &mut WorkspaceData,
+        operation: &Operation,
+        crt_id: String,
+    ) {
+        if let Some(stream) = self.send_stream.take() {
+            stream.cancel();
+        }
+        self.send_promise = None;
+        self.send_rest = None;
+        workspace_data.must_get_mut_crt(crt_id, |crt| crt.rest.cancelled());
+        operation.add_success_toast("Request cancelled.");
+    }
+
     fn send_promise(
         &mut self,
         ui: &mut Ui,
@@ -369,6 +484,21 @@ impl RestPanel {
         if let Some(promise) = &self.send_promise {
             if let Some(result) = promise.ready() {
                 workspace_data.save_cookies();
+                if let Some(send_rest) = self.send_rest.take() {
+                    match result {
+                        Ok((request, response, _)) => workspace_data
+                            .history_record(send_rest, Some((request.clone(), response.clone()))),
+                        Err(_) => workspace_data.history_record(send_rest, None),
+                    }
+                }
+                let mut variable_logger = Logger::default();
+                if let Some(stream) = &self.send_stream {
+                    workspace_data.save_crt_variable_changes(
+                        crt_id.clone(),
+                        &stream.variable_changes(),
+                        &mut variable_logger,
+                    );
+                }
                 workspace_data.must_get_mut_crt(crt_id.clone(), |crt| match result {
                     Ok((request, response, test_result)) => {
                         request
@@ -379,6 +509,11 @@ impl RestPanel {
                                 crt.rest.request.headers.push(h.clone());
                             });
                         crt.rest.response = response.clone();
+                        crt.rest
+                            .response
+                            .logger
+                            .logs
+                            .extend(variable_logger.logs.clone());
                         crt.rest.ready();
                         operation.add_success_toast("Send request success");
                         crt.test_result = test_result.clone();
@@ -399,6 +534,28 @@ impl RestPanel {
                     }
                 });
                 self.send_promise = None;
+                self.send_stream = None;
+            } else if let Some(stream) = self.send_stream.clone().filter(|s| s.is_stopped()) {
+                let mut response = stream.to_response();
+                workspace_data.save_crt_variable_changes(
+                    crt_id.clone(),
+                    &stream.variable_changes(),
+                    &mut response.logger,
+                );
+                workspace_data.save_cookies();
+                if let Some(send_rest) = self.send_rest.take() {
+                    workspace_data.history_record(
+                        send_rest.clone(),
+                        Some((send_rest.request, response.clone())),
+                    );
+                }
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                    crt.rest.response = response.clone();
+                    crt.rest.ready();
+                });
+                operation.add_success_toast("Stream stopped.");
+                self.send_promise = None;
+                self.send_stream = None;
             } else {
                 ui.ctx().request_repaint();
                 workspace_data.must_get_mut_crt(crt_id.clone(), |crt| crt.rest.pending());
@@ -438,7 +595,12 @@ impl RestPanel {
                     if ui.link("Cookies").clicked() {
                         operation.add_window(Box::new(CookiesWindows::default()));
                     };
-                    ui.link("Code");
+                    if ui.link("Network").clicked() {
+                        operation.add_window(Box::new(NetworkSettingsWindows::default()));
+                    };
+                    if ui.link("Code").clicked() {
+                        operation.add_window(Box::new(CodeWindows::default().with(crt_id.clone())));
+                    };
                 });
             },
         );
@@ -51,6 +51,12 @@ postcat.test("This is a test example",function(){
                             if ui.link("Set a variable").clicked() {
                                 script = script.clone().add("\npostcat.set_env(\"variable_key\",\"variable_value\");");
                             }
+                            if ui.link("Save an environment variable").clicked() {
+                                script = script.clone().add("\npostcat.environment.set(\"variable_key\",\"variable_value\");");
+                            }
+                            if ui.link("Save a collection variable").clicked() {
+                                script = script.clone().add("\npostcat.collectionVariables.set(\"variable_key\",\"variable_value\");");
+                            }
                             if ui.link("Get a shared").clicked() {
                                 script = script.clone().add("\npostcat.get_shared(\"shared_key\");");
                             }
@@ -60,6 +66,21 @@ postcat.test("This is a test example",function(){
                             if ui.link("Get response").clicked() {
                                 script = script.clone().add("\nlet response = postcat.resp();\nconsole.log(response)");
                             }
+                            if ui.link("Check response time").clicked() {
+                                script = script.clone().add(r#"
+let response = postcat.resp();
+postcat.test("Response time is within budget", function () {
+    assert(true, response.timing.total < 500);
+    assert(true, response.timing.tls < 100);
+});"#);
+                            }
+                            if ui.link("Check status with pm").clicked() {
+                                script = script.clone().add(r#"
+pm.test("Status code is 200", function () {
+    pm.response.to.have.status(200);
+    pm.expect(pm.response.json()).to.be.an("object");
+});"#);
+                            }
                         });
                 });
             egui::SidePanel::left("test_script_left_".to_string() + id.as_str())
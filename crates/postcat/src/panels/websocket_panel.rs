@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use egui::ahash::HashSet;
+use egui::{Button, Color32, RichText, Ui, Widget};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::data::http::HttpRecord;
+use crate::data::websocket::{WebSocketDirection, WebSocketMessage, WebSocketMessageType};
+use crate::data::workspace_data::WorkspaceData;
+use crate::operation::operation::Operation;
+use crate::operation::websocket_client::{WebSocketSession, WebSocketStatus};
+use crate::panels::request_headers_panel::RequestHeadersPanel;
+use crate::panels::request_params_panel::RequestParamsPanel;
+use crate::panels::request_pre_script_panel::RequestPreScriptPanel;
+use crate::panels::rest_panel::RestPanel;
+use crate::panels::test_script_panel::TestScriptPanel;
+use crate::panels::{HORIZONTAL_GAP, VERTICAL_GAP};
+use crate::script::script::ScriptScope;
+use crate::utils;
+use crate::widgets::highlight_template::HighlightTemplateSinglelineBuilder;
+use crate::windows::save_crt_windows::SaveCRTWindows;
+
+#[derive(Default)]
+pub struct WebSocketPanel {
+    open_panel_enum: WebSocketPanelEnum,
+    request_params_panel: RequestParamsPanel,
+    request_headers_panel: RequestHeadersPanel,
+    request_pre_script_panel: RequestPreScriptPanel,
+    message_script_panel: TestScriptPanel,
+    sessions: HashMap<String, WebSocketSession>,
+    cookies_saved: HashSet<String>,
+    compose_type: WebSocketMessageType,
+    compose_content: String,
+}
+
+#[derive(Clone, EnumIter, EnumString, Display, PartialEq)]
+enum WebSocketPanelEnum {
+    Message,
+    Params,
+    Headers,
+    PreRequestScript,
+    MessageScript,
+}
+
+impl Default for WebSocketPanelEnum {
+    fn default() -> Self {
+        WebSocketPanelEnum::Message
+    }
+}
+
+impl WebSocketPanel {
+    pub fn set_and_render(
+        &mut self,
+        ui: &mut Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        self.sessions
+            .retain(|id, _| workspace_data.contains_crt_id(id.clone()));
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(HORIZONTAL_GAP);
+                RestPanel::render_name_label(workspace_data, crt_id.clone(), ui);
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                self.render_editor_right_panel(operation, workspace_data, crt_id.clone(), ui);
+                self.render_editor_left_panel(workspace_data, crt_id.clone(), ui);
+            });
+            ui.separator();
+            let crt = workspace_data.must_get_crt(crt_id.clone());
+            ui.horizontal(|ui| {
+                for x in WebSocketPanelEnum::iter() {
+                    ui.selectable_value(
+                        &mut self.open_panel_enum,
+                        x.clone(),
+                        utils::build_with_count_ui_header(
+                            x.to_string(),
+                            Self::get_count(&crt.rest, x),
+                            ui,
+                        ),
+                    );
+                }
+            });
+            ui.separator();
+        });
+        self.render_open_panel(ui, operation, workspace_data, crt_id.clone());
+        ui.separator();
+        self.render_message_log(ui, crt_id);
+    }
+
+    fn get_count(hr: &HttpRecord, panel_enum: WebSocketPanelEnum) -> usize {
+        match panel_enum {
+            WebSocketPanelEnum::Message => hr.websocket.messages.len(),
+            WebSocketPanelEnum::Params => hr.request.params.iter().filter(|i| i.enable).count(),
+            WebSocketPanelEnum::Headers => hr.request.headers.iter().filter(|i| i.enable).count(),
+            WebSocketPanelEnum::PreRequestScript => {
+                if hr.pre_request_script != "" {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
+            WebSocketPanelEnum::MessageScript => {
+                if hr.websocket.message_script != "" {
+                    usize::MAX
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    fn render_editor_right_panel(
+        &mut self,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        ui: &mut Ui,
+    ) {
+        let (mut pre_request_parent_script_scopes, _) =
+            workspace_data.get_crt_parent_scripts(crt_id.clone());
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let parent_auth = workspace_data.get_crt_parent_auth(crt_id.clone());
+        let status = self.sessions.get(crt_id.as_str()).map(|s| s.get_status());
+        egui::SidePanel::right("websocket_editor_right_panel")
+            .resizable(false)
+            .show_inside(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(HORIZONTAL_GAP);
+                    match status {
+                        Some(WebSocketStatus::Connecting) => {
+                            ui.add_enabled(false, Button::new("Connecting"));
+                        }
+                        Some(WebSocketStatus::Connected) => {
+                            if ui.button("Disconnect").clicked() {
+                                if let Some(session) = self.sessions.get(crt_id.as_str()) {
+                                    session.close();
+                                }
+                            }
+                        }
+                        _ => {
+                            if ui.button("Connect").clicked() {
+                                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                                    crt.rest.request.clear_lock_with();
+                                    crt.rest.sync(envs.clone(), parent_auth.clone());
+                                });
+                                let crt = workspace_data.must_get_crt(crt_id.clone());
+                                if crt.rest.pre_request_script.clone() != "" {
+                                    pre_request_parent_script_scopes.push(ScriptScope {
+                                        scope: "request".to_string(),
+                                        script: crt.rest.pre_request_script.clone(),
+                                    });
+                                }
+                                let mut message_scripts = vec![];
+                                if crt.rest.websocket.message_script.clone() != "" {
+                                    message_scripts.push(ScriptScope {
+                                        scope: "message".to_string(),
+                                        script: crt.rest.websocket.message_script.clone(),
+                                    });
+                                }
+                                self.cookies_saved.remove(crt_id.as_str());
+                                self.sessions.insert(
+                                    crt_id.clone(),
+                                    WebSocketSession::connect(
+                                        crt.rest.request.clone(),
+                                        envs.clone(),
+                                        parent_auth.clone(),
+                                        workspace_data
+                                            .get_client_config(workspace_data.get_env_select()),
+                                        pre_request_parent_script_scopes,
+                                        message_scripts,
+                                        workspace_data.get_cookie_store(),
+                                    ),
+                                );
+                                workspace_data.history_record(crt.rest.clone(), None);
+                            }
+                        }
+                    }
+                    if ui.button("Save").clicked() {
+                        let crt = workspace_data.must_get_crt(crt_id.clone());
+                        match &crt.collection_path {
+                            None => {
+                                operation.add_window(Box::new(
+                                    SaveCRTWindows::default().with(crt.id.clone()),
+                                ));
+                            }
+                            Some(collection_path) => {
+                                workspace_data.save_crt(
+                                    crt.id.clone(),
+                                    collection_path.clone(),
+                                    |_| {},
+                                );
+                                operation.add_success_toast("Save success.");
+                                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                                    crt.set_baseline();
+                                });
+                            }
+                        }
+                    }
+                });
+            });
+        if status == Some(WebSocketStatus::Connected) && self.cookies_saved.insert(crt_id.clone()) {
+            workspace_data.save_cookies();
+        }
+    }
+
+    fn render_editor_left_panel(
+        &self,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+        ui: &mut Ui,
+    ) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            egui::SidePanel::left("websocket_editor_left_panel")
+                .min_width(ui.available_width() - HORIZONTAL_GAP)
+                .show_separator_line(false)
+                .resizable(false)
+                .show_inside(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        utils::record_method_combo(ui, &mut crt.rest);
+                        let mut filter: HashSet<String> = HashSet::default();
+                        filter.insert(" ".to_string());
+                        ui.centered_and_justified(|ui| {
+                            HighlightTemplateSinglelineBuilder::default()
+                                .filter(filter)
+                                .envs(envs.clone())
+                                .all_space(false)
+                                .build(crt_id.clone() + "url", &mut crt.rest.request.base_url)
+                                .ui(ui);
+                        });
+                    });
+                });
+        });
+    }
+
+    fn render_open_panel(
+        &mut self,
+        ui: &mut Ui,
+        operation: &Operation,
+        workspace_data: &mut WorkspaceData,
+        crt_id: String,
+    ) {
+        let crt = workspace_data.must_get_crt(crt_id.clone());
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        match self.open_panel_enum {
+            WebSocketPanelEnum::Message => self.render_message(ui, workspace_data, crt_id),
+            WebSocketPanelEnum::Params => {
+                self.request_params_panel
+                    .set_and_render(ui, workspace_data, crt_id)
+            }
+            WebSocketPanelEnum::Headers => {
+                self.request_headers_panel
+                    .set_and_render(ui, workspace_data, crt_id)
+            }
+            WebSocketPanelEnum::PreRequestScript => {
+                let (pre_request_parent_script_scopes, _) =
+                    workspace_data.get_crt_parent_scripts(crt_id.clone());
+                let script = self.request_pre_script_panel.set_and_render(
+                    ui,
+                    operation,
+                    crt.rest.pre_request_script.clone(),
+                    pre_request_parent_script_scopes,
+                    crt.rest.request.clone(),
+                    envs,
+                    workspace_data.get_proxy_config(workspace_data.get_env_select()),
+                    "websocket".to_string(),
+                );
+                workspace_data.must_get_mut_crt(crt_id, |crt| {
+                    crt.rest.pre_request_script = script;
+                });
+            }
+            WebSocketPanelEnum::MessageScript => {
+                let script = self.message_script_panel.set_and_render(
+                    ui,
+                    crt.rest.websocket.message_script.clone(),
+                    "websocket".to_string(),
+                );
+                workspace_data.must_get_mut_crt(crt_id, |crt| {
+                    crt.rest.websocket.message_script = script;
+                });
+            }
+        }
+    }
+
+    fn render_message(&mut self, ui: &mut Ui, workspace_data: &mut WorkspaceData, crt_id: String) {
+        let envs = workspace_data.get_crt_envs(crt_id.clone());
+        let session = self
+            .sessions
+            .get(crt_id.as_str())
+            .filter(|s| s.get_status() == WebSocketStatus::Connected);
+        ui.horizontal(|ui| {
+            for x in WebSocketMessageType::iter() {
+                utils::selectable_check(ui, &mut self.compose_type, x.clone(), x.to_string());
+            }
+            if ui
+                .add_enabled(session.is_some(), Button::new("Send"))
+                .clicked()
+            {
+                if let Some(session) = session {
+                    session.send(
+                        self.compose_type.clone(),
+                        utils::replace_variable(self.compose_content.clone(), envs.clone()),
+                    );
+                }
+            }
+            if ui.button("Save Message").clicked() {
+                workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+                    let names = crt
+                        .rest
+                        .websocket
+                        .messages
+                        .iter()
+                        .map(|m| m.name.clone())
+                        .collect();
+                    crt.rest.websocket.messages.push(WebSocketMessage {
+                        name: utils::build_copy_name("Message".to_string(), names),
+                        message_type: self.compose_type.clone(),
+                        content: self.compose_content.clone(),
+                    });
+                });
+            }
+        });
+        if self.compose_type == WebSocketMessageType::Binary {
+            ui.label("Binary messages are written as base64.");
+        }
+        ui.push_id("websocket_compose", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(120.0)
+                .show(ui, |ui| {
+                    HighlightTemplateSinglelineBuilder::default()
+                        .multiline()
+                        .envs(envs.clone())
+                        .all_space(true)
+                        .build("websocket_compose".to_string(), &mut self.compose_content)
+                        .ui(ui);
+                });
+        });
+        ui.add_space(VERTICAL_GAP);
+        let mut remove_index = None;
+        workspace_data.must_get_mut_crt(crt_id.clone(), |crt| {
+            for (index, message) in crt.rest.websocket.messages.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                    if ui.button("Load").clicked() {
+                        self.compose_type = message.message_type.clone();
+                        self.compose_content = message.content.clone();
+                    }
+                    ui.label(message.message_type.to_string());
+                    utils::text_edit_singleline_justify(ui, &mut message.name);
+                });
+            }
+            if let Some(index) = remove_index {
+                crt.rest.websocket.messages.remove(index);
+            }
+        });
+    }
+
+    fn render_message_log(&mut self, ui: &mut Ui, crt_id: String) {
+        let session = match self.sessions.get(crt_id.as_str()) {
+            None => {
+                ui.label("Connect to start a WebSocket session.");
+                return;
+            }
+            Some(session) => session,
+        };
+        let status = session.get_status();
+        ui.horizontal(|ui| {
+            ui.strong(format!("{:?}", status));
+            if ui.button("Clear").clicked() {
+                session.clear_logs();
+            }
+        });
+        if status != WebSocketStatus::Closed {
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+        ui.push_id("websocket_log", |ui| {
+            egui::ScrollArea::vertical()
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for log in session.get_logs().iter() {
+                        let color = match log.direction {
+                            WebSocketDirection::Inbound => ui.visuals().text_color(),
+                            WebSocketDirection::Outbound => ui.visuals().hyperlink_color,
+                            WebSocketDirection::Info => ui.visuals().weak_text_color(),
+                            WebSocketDirection::Error => Color32::RED,
+                        };
+                        ui.label(RichText::new(log.show()).monospace().color(color));
+                    }
+                });
+        });
+    }
+}
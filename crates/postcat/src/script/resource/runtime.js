@@ -33,7 +33,30 @@
             core.ops.op_error(argsToMessage(...args));
         },
     };
+    function variables(scope) {
+        return {
+            get: (key) => {
+                return core.ops.op_has_variable(scope, key) ? core.ops.op_get_variable(scope, key) : undefined
+            },
+            has: (key) => {
+                return core.ops.op_has_variable(scope, key)
+            },
+            set: (key, value) => {
+                if (typeof value !== "string") {
+                    value = JSON.stringify(value)
+                }
+                return core.ops.op_set_variable(scope, key, value)
+            },
+            unset: (key) => {
+                return core.ops.op_unset_variable(scope, key)
+            },
+        }
+    }
+
     globalThis.postcat = {
+        environment: variables("Environment"),
+        collectionVariables: variables("Collection"),
+        globals: variables("Globals"),
         set_env: (key, value) => {
             return core.ops.op_set_env(key, value)
         },
@@ -62,6 +85,20 @@
             }
             return response
         },
+        message: () => {
+            let message = core.ops.op_message();
+            try {
+                message.json = JSON.parse(message.data);
+            } catch (e) {
+            }
+            return message
+        },
+        send_message: (data) => {
+            if (typeof data !== "string") {
+                data = JSON.stringify(data)
+            }
+            return core.ops.op_send_message(data)
+        },
         test: (name, func) => {
             core.ops.op_open_test(name);
             func();
@@ -69,4 +106,707 @@
         }
     }
 
+    class AssertionError extends Error {
+        constructor(message) {
+            super(message);
+            this.name = "AssertionError";
+        }
+    }
+
+    function inspect(value) {
+        if (typeof value === "function") {
+            return "[Function]";
+        }
+        if (typeof value === "number" || value === undefined) {
+            return String(value);
+        }
+        try {
+            return JSON.stringify(value);
+        } catch (e) {
+            return String(value);
+        }
+    }
+
+    function typeOf(value) {
+        if (value === null) {
+            return "null";
+        }
+        if (Array.isArray(value)) {
+            return "array";
+        }
+        if (value instanceof RegExp) {
+            return "regexp";
+        }
+        if (value instanceof Date) {
+            return "date";
+        }
+        return typeof value;
+    }
+
+    function deepEqual(a, b) {
+        if (a === b || (Number.isNaN(a) && Number.isNaN(b))) {
+            return true;
+        }
+        if (typeof a !== "object" || typeof b !== "object" || a === null || b === null) {
+            return false;
+        }
+        if (Array.isArray(a) !== Array.isArray(b)) {
+            return false;
+        }
+        if (a instanceof Date && b instanceof Date) {
+            return a.getTime() === b.getTime();
+        }
+        const aKeys = Object.keys(a);
+        const bKeys = Object.keys(b);
+        return aKeys.length === bKeys.length && aKeys.every((key) => {
+            return Object.prototype.hasOwnProperty.call(b, key) && deepEqual(a[key], b[key]);
+        });
+    }
+
+    function sizeOf(value) {
+        if (value instanceof Map || value instanceof Set) {
+            return value.size;
+        }
+        if (typeof value === "string" || Array.isArray(value)) {
+            return value.length;
+        }
+        if (value !== null && typeof value === "object") {
+            return Object.keys(value).length;
+        }
+        return undefined;
+    }
+
+    // A subset of the Chai BDD interface, enough for the assertions Postman scripts use.
+    class Assertion {
+        constructor(actual, message) {
+            this._actual = actual;
+            this._message = message;
+            this._flags = {};
+        }
+
+        _assert(expr, message, negateMessage) {
+            if (this._flags.not ? expr : !expr) {
+                const text = this._flags.not ? negateMessage : message;
+                throw new AssertionError(this._message ? `${this._message}: ${text}` : text);
+            }
+            return this;
+        }
+
+        _equals(a, b) {
+            return this._flags.deep ? deepEqual(a, b) : a === b || (Number.isNaN(a) && Number.isNaN(b));
+        }
+
+        _compare(name, expected, compare) {
+            const actual = this._flags.length ? sizeOf(this._actual) : this._actual;
+            const subject = this._flags.length ? `${inspect(this._actual)} to have a length` : inspect(this._actual);
+            return this._assert(
+                compare(actual, expected),
+                `expected ${subject} to be ${name} ${inspect(expected)}`,
+                `expected ${subject} to not be ${name} ${inspect(expected)}`,
+            );
+        }
+
+        equal(expected) {
+            return this._assert(
+                this._equals(this._actual, expected),
+                `expected ${inspect(this._actual)} to ${this._flags.deep ? "deeply " : ""}equal ${inspect(expected)}`,
+                `expected ${inspect(this._actual)} to not ${this._flags.deep ? "deeply " : ""}equal ${inspect(expected)}`,
+            );
+        }
+
+        eql(expected) {
+            this._flags.deep = true;
+            return this.equal(expected);
+        }
+
+        above(expected) {
+            return this._compare("above", expected, (a, e) => a > e);
+        }
+
+        least(expected) {
+            return this._compare("at least", expected, (a, e) => a >= e);
+        }
+
+        below(expected) {
+            return this._compare("below", expected, (a, e) => a < e);
+        }
+
+        most(expected) {
+            return this._compare("at most", expected, (a, e) => a <= e);
+        }
+
+        within(start, end) {
+            return this._compare("within", `${start}..${end}`, (a) => a >= start && a <= end);
+        }
+
+        instanceof(constructor) {
+            return this._assert(
+                this._actual instanceof constructor,
+                `expected ${inspect(this._actual)} to be an instance of ${constructor.name}`,
+                `expected ${inspect(this._actual)} to not be an instance of ${constructor.name}`,
+            );
+        }
+
+        property(name, ...value) {
+            const actual = this._actual;
+            const has = actual !== null && actual !== undefined && (this._flags.own
+                ? Object.prototype.hasOwnProperty.call(actual, name)
+                : name in Object(actual));
+            if (value.length === 0) {
+                this._assert(
+                    has,
+                    `expected ${inspect(actual)} to have property ${inspect(name)}`,
+                    `expected ${inspect(actual)} to not have property ${inspect(name)}`,
+                );
+            } else {
+                this._assert(
+                    has && this._equals(actual[name], value[0]),
+                    `expected ${inspect(actual)} to have property ${inspect(name)} of ${inspect(value[0])}`,
+                    `expected ${inspect(actual)} to not have property ${inspect(name)} of ${inspect(value[0])}`,
+                );
+            }
+            if (has) {
+                this._actual = actual[name];
+            }
+            return this;
+        }
+
+        ownProperty(name, ...value) {
+            this._flags.own = true;
+            return this.property(name, ...value);
+        }
+
+        lengthOf(expected) {
+            const size = sizeOf(this._actual);
+            return this._assert(
+                size === expected,
+                `expected ${inspect(this._actual)} to have a length of ${expected} but got ${size}`,
+                `expected ${inspect(this._actual)} to not have a length of ${expected}`,
+            );
+        }
+
+        match(regexp) {
+            return this._assert(
+                regexp.test(this._actual),
+                `expected ${inspect(this._actual)} to match ${regexp}`,
+                `expected ${inspect(this._actual)} not to match ${regexp}`,
+            );
+        }
+
+        string(expected) {
+            return this._assert(
+                typeof this._actual === "string" && this._actual.indexOf(expected) !== -1,
+                `expected ${inspect(this._actual)} to contain ${inspect(expected)}`,
+                `expected ${inspect(this._actual)} to not contain ${inspect(expected)}`,
+            );
+        }
+
+        keys(...keys) {
+            if (keys.length === 1 && Array.isArray(keys[0])) {
+                keys = keys[0];
+            } else if (keys.length === 1 && typeOf(keys[0]) === "object") {
+                keys = Object.keys(keys[0]);
+            }
+            const actual = this._actual instanceof Map ? Array.from(this._actual.keys()) : Object.keys(Object(this._actual));
+            let result;
+            if (this._flags.any) {
+                result = keys.some((key) => actual.includes(key));
+            } else {
+                result = keys.every((key) => actual.includes(key));
+                if (!this._flags.contains) {
+                    result = result && keys.length === actual.length;
+                }
+            }
+            return this._assert(
+                result,
+                `expected ${inspect(this._actual)} to have ${this._flags.any ? "any" : "all"} keys ${inspect(keys)}`,
+                `expected ${inspect(this._actual)} to not have ${this._flags.any ? "any" : "all"} keys ${inspect(keys)}`,
+            );
+        }
+
+        oneOf(list) {
+            return this._assert(
+                list.some((item) => this._equals(this._actual, item)),
+                `expected ${inspect(this._actual)} to be one of ${inspect(list)}`,
+                `expected ${inspect(this._actual)} to not be one of ${inspect(list)}`,
+            );
+        }
+
+        members(expected) {
+            const actual = Array.from(this._actual || []);
+            const contains = (list, item) => list.some((value) => this._equals(value, item));
+            let result = expected.every((item) => contains(actual, item));
+            if (!this._flags.contains) {
+                result = result && actual.length === expected.length && actual.every((item) => contains(expected, item));
+            }
+            return this._assert(
+                result,
+                `expected ${inspect(actual)} to have the same members as ${inspect(expected)}`,
+                `expected ${inspect(actual)} to not have the same members as ${inspect(expected)}`,
+            );
+        }
+
+        satisfy(matcher) {
+            return this._assert(
+                matcher(this._actual),
+                `expected ${inspect(this._actual)} to satisfy ${matcher}`,
+                `expected ${inspect(this._actual)} to not satisfy ${matcher}`,
+            );
+        }
+
+        throw(expected) {
+            let error;
+            try {
+                this._actual();
+            } catch (e) {
+                error = e;
+            }
+            let result = error !== undefined;
+            if (result && expected !== undefined) {
+                const message = error && error.message !== undefined ? error.message : String(error);
+                if (expected instanceof RegExp) {
+                    result = expected.test(message);
+                } else if (typeof expected === "string") {
+                    result = message.indexOf(expected) !== -1;
+                } else if (typeof expected === "function") {
+                    result = error instanceof expected;
+                }
+            }
+            return this._assert(
+                result,
+                `expected [Function] to throw ${expected === undefined ? "an error" : inspect(expected)}`,
+                `expected [Function] to not throw ${expected === undefined ? "an error" : inspect(expected)}`,
+            );
+        }
+
+        _type(type) {
+            const article = /^[aeiou]/.test(type) ? "an" : "a";
+            return this._assert(
+                typeOf(this._actual) === type.toLowerCase(),
+                `expected ${inspect(this._actual)} to be ${article} ${type}`,
+                `expected ${inspect(this._actual)} not to be ${article} ${type}`,
+            );
+        }
+
+        _include(expected) {
+            const actual = this._actual;
+            let result;
+            if (typeof actual === "string") {
+                result = actual.indexOf(expected) !== -1;
+            } else if (Array.isArray(actual)) {
+                result = actual.some((item) => this._equals(item, expected));
+            } else if (actual instanceof Set) {
+                result = Array.from(actual).some((item) => this._equals(item, expected));
+            } else if (actual instanceof Map) {
+                result = Array.from(actual.values()).some((item) => this._equals(item, expected));
+            } else if (actual !== null && typeof actual === "object" && typeOf(expected) === "object") {
+                result = Object.keys(expected).every((key) => key in actual && this._equals(actual[key], expected[key]));
+            } else {
+                result = false;
+            }
+            return this._assert(
+                result,
+                `expected ${inspect(actual)} to include ${inspect(expected)}`,
+                `expected ${inspect(actual)} to not include ${inspect(expected)}`,
+            );
+        }
+    }
+
+    function defineGetter(target, names, getter) {
+        for (const name of names) {
+            Object.defineProperty(target.prototype, name, { get: getter, configurable: true });
+        }
+    }
+
+    // Returns a function that runs `method` when called and keeps chaining when it is not.
+    function defineChainableMethod(names, method, chain) {
+        defineGetter(Assertion, names, function () {
+            chain.call(this);
+            const assertion = this;
+            const callable = function (...args) {
+                return method.apply(assertion, args);
+            };
+            delete callable.length;
+            delete callable.name;
+            Object.setPrototypeOf(callable, assertion);
+            return callable;
+        });
+    }
+
+    for (const [alias, name] of [
+        ["equals", "equal"], ["eq", "equal"], ["eqls", "eql"],
+        ["gt", "above"], ["greaterThan", "above"], ["gte", "least"],
+        ["lt", "below"], ["lessThan", "below"], ["lte", "most"],
+        ["instanceOf", "instanceof"], ["haveOwnProperty", "ownProperty"],
+        ["matches", "match"], ["key", "keys"], ["throws", "throw"], ["Throw", "throw"],
+        ["satisfies", "satisfy"],
+    ]) {
+        Assertion.prototype[alias] = Assertion.prototype[name];
+    }
+    defineGetter(Assertion, [
+        "to", "be", "been", "is", "that", "which", "and", "has", "have",
+        "with", "at", "of", "same", "but", "does", "still", "also",
+    ], function () {
+        return this;
+    });
+    for (const flag of ["not", "deep", "own", "any", "all"]) {
+        defineGetter(Assertion, [flag], function () {
+            this._flags[flag] = true;
+            if (flag === "any") {
+                this._flags.all = false;
+            }
+            if (flag === "all") {
+                this._flags.any = false;
+            }
+            return this;
+        });
+    }
+    for (const [name, check, text] of [
+        ["ok", (v) => !!v, "truthy"],
+        ["true", (v) => v === true, "true"],
+        ["false", (v) => v === false, "false"],
+        ["null", (v) => v === null, "null"],
+        ["undefined", (v) => v === undefined, "undefined"],
+        ["NaN", (v) => Number.isNaN(v), "NaN"],
+        ["exist", (v) => v !== null && v !== undefined, "exist"],
+        ["empty", (v) => sizeOf(v) === 0, "empty"],
+    ]) {
+        defineGetter(Assertion, [name], function () {
+            const verb = name === "exist" ? "" : "be ";
+            return this._assert(
+                check(this._actual),
+                `expected ${inspect(this._actual)} to ${verb}${text}`,
+                `expected ${inspect(this._actual)} to not ${verb}${text}`,
+            );
+        });
+    }
+    defineChainableMethod(["a", "an"], Assertion.prototype._type, function () {
+    });
+    defineChainableMethod(["include", "includes", "contain", "contains"], Assertion.prototype._include, function () {
+        this._flags.contains = true;
+    });
+    defineChainableMethod(["length"], Assertion.prototype.lengthOf, function () {
+        this._flags.length = true;
+    });
+
+    class HeaderList {
+        constructor(headers) {
+            this._headers = headers.map((h) => ({ key: h.name, value: h.value }));
+        }
+
+        _find(name) {
+            const lower = String(name).toLowerCase();
+            return this._headers.filter((h) => h.key.toLowerCase() === lower);
+        }
+
+        get(name) {
+            const found = this._find(name);
+            return found.length > 0 ? found[0].value : undefined;
+        }
+
+        has(name, ...value) {
+            const found = this._find(name);
+            return value.length === 0 ? found.length > 0 : found.some((h) => h.value === value[0]);
+        }
+
+        all() {
+            return this._headers.slice();
+        }
+
+        each(callback) {
+            this._headers.forEach(callback);
+        }
+
+        count() {
+            return this._headers.length;
+        }
+
+        toObject() {
+            const result = {};
+            this._headers.forEach((h) => {
+                result[h.key] = h.value;
+            });
+            return result;
+        }
+    }
+
+    // Header edits are applied to the request that is about to be sent.
+    class RequestHeaderList extends HeaderList {
+        add(header) {
+            this._headers.push({ key: header.key, value: String(header.value) });
+            core.ops.op_add_header(header.key, String(header.value));
+        }
+
+        upsert(header) {
+            this.remove(header.key);
+            this.add(header);
+        }
+
+        remove(name) {
+            const lower = String(name).toLowerCase();
+            this._headers = this._headers.filter((h) => h.key.toLowerCase() !== lower);
+            core.ops.op_remove_header(name);
+        }
+    }
+
+    class ResponseAssertion extends Assertion {
+        status(expected) {
+            const response = this._actual;
+            const actual = typeof expected === "number" ? response.code : response.status;
+            return this._assert(
+                actual === expected,
+                `expected response to have status ${inspect(expected)} but got ${inspect(actual)}`,
+                `expected response to not have status ${inspect(expected)}`,
+            );
+        }
+
+        header(name, ...value) {
+            return this._assert(
+                this._actual.headers.has(name, ...value),
+                `expected response to have header ${inspect(name)}${value.length ? ` with value ${inspect(value[0])}` : ""}`,
+                `expected response to not have header ${inspect(name)}`,
+            );
+        }
+
+        body(...expected) {
+            const text = this._actual.text();
+            let result;
+            if (expected.length === 0) {
+                result = text.length > 0;
+            } else if (typeof expected[0] === "string") {
+                result = text === expected[0];
+            } else {
+                try {
+                    result = deepEqual(JSON.parse(text), expected[0]);
+                } catch (e) {
+                    result = false;
+                }
+            }
+            return this._assert(
+                result,
+                `expected response to have body ${expected.length ? inspect(expected[0]) : ""}`,
+                `expected response to not have body ${expected.length ? inspect(expected[0]) : ""}`,
+            );
+        }
+
+        jsonBody(...path) {
+            let json;
+            let result = true;
+            try {
+                json = JSON.parse(this._actual.text());
+            } catch (e) {
+                result = false;
+            }
+            if (result && path.length > 0) {
+                let value = json;
+                for (const key of String(path[0]).split(".")) {
+                    if (value === null || typeof value !== "object" || !(key in value)) {
+                        result = false;
+                        break;
+                    }
+                    value = value[key];
+                }
+                if (result && path.length > 1) {
+                    result = deepEqual(value, path[1]);
+                }
+            }
+            return this._assert(
+                result,
+                `expected response to have a json body ${path.length ? `with ${inspect(path[0])}` : ""}`,
+                `expected response to not have a json body ${path.length ? `with ${inspect(path[0])}` : ""}`,
+            );
+        }
+    }
+
+    for (const [name, check] of [
+        ["ok", (code) => code === 200],
+        ["info", (code) => code >= 100 && code < 200],
+        ["success", (code) => code >= 200 && code < 300],
+        ["redirection", (code) => code >= 300 && code < 400],
+        ["clientError", (code) => code >= 400 && code < 500],
+        ["serverError", (code) => code >= 500 && code < 600],
+        ["error", (code) => code >= 400 && code < 600],
+        ["accepted", (code) => code === 202],
+        ["badRequest", (code) => code === 400],
+        ["unauthorized", (code) => code === 401],
+        ["forbidden", (code) => code === 403],
+        ["notFound", (code) => code === 404],
+        ["rateLimited", (code) => code === 429],
+    ]) {
+        defineGetter(ResponseAssertion, [name], function () {
+            return this._assert(
+                check(this._actual.code),
+                `expected response to be ${name} but got ${this._actual.code}`,
+                `expected response to not be ${name} but got ${this._actual.code}`,
+            );
+        });
+    }
+    defineGetter(ResponseAssertion, ["json"], function () {
+        return this.jsonBody();
+    });
+
+    function cookieList(headers) {
+        const cookies = headers
+            .filter((h) => h.name.toLowerCase() === "set-cookie")
+            .map((h) => {
+                const pair = h.value.split(";")[0];
+                const index = pair.indexOf("=");
+                return {
+                    name: pair.slice(0, index).trim(),
+                    value: pair.slice(index + 1).trim(),
+                };
+            });
+        return {
+            get: (name) => {
+                const cookie = cookies.find((c) => c.name === name);
+                return cookie ? cookie.value : undefined;
+            },
+            has: (name) => cookies.some((c) => c.name === name),
+            all: () => cookies.slice(),
+            each: (callback) => cookies.forEach(callback),
+            count: () => cookies.length,
+            toObject: () => Object.fromEntries(cookies.map((c) => [c.name, c.value])),
+        };
+    }
+
+    function postmanResponse(response) {
+        return {
+            code: response.status,
+            status: response.status_text.replace(/^\d+\s*/, ""),
+            headers: new HeaderList(response.headers),
+            responseTime: response.timing.total,
+            responseSize: response.size.response_body,
+            text: () => response.text,
+            json: () => JSON.parse(response.text),
+            get to() {
+                return new ResponseAssertion(this);
+            },
+        };
+    }
+
+    function postmanRequest(request) {
+        return {
+            method: request.method,
+            url: {
+                toString: () => request.url,
+                addQueryParams: (params) => {
+                    params.forEach((p) => core.ops.op_add_params(p.key, String(p.value)));
+                },
+                query: {
+                    add: (param) => core.ops.op_add_params(param.key, String(param.value)),
+                },
+            },
+            headers: new RequestHeaderList(request.headers),
+            body: {
+                mode: "raw",
+                raw: request.body,
+                toString: () => request.body,
+            },
+        };
+    }
+
+    function fetchRequest(request) {
+        if (typeof request === "string") {
+            return { method: "GET", url: request, headers: [], body: "" };
+        }
+        let headers = [];
+        const header = request.header || request.headers || [];
+        if (Array.isArray(header)) {
+            headers = header.filter((h) => !h.disabled).map((h) => ({ name: h.key, value: String(h.value) }));
+        } else {
+            headers = Object.entries(header).map(([name, value]) => ({ name, value: String(value) }));
+        }
+        let body = "";
+        const source = request.body;
+        if (typeof source === "string") {
+            body = source;
+        } else if (source && source.mode === "raw") {
+            body = source.raw || "";
+        } else if (source && source.mode === "urlencoded") {
+            body = (source.urlencoded || [])
+                .filter((p) => !p.disabled)
+                .map((p) => `${encodeURIComponent(p.key)}=${encodeURIComponent(p.value)}`)
+                .join("&");
+            if (!headers.some((h) => h.name.toLowerCase() === "content-type")) {
+                headers.push({ name: "Content-Type", value: "application/x-www-form-urlencoded" });
+            }
+        } else if (source && source.mode === "graphql") {
+            body = JSON.stringify(source.graphql);
+        }
+        return {
+            method: (request.method || "GET").toUpperCase(),
+            url: String(request.url),
+            headers,
+            body,
+        };
+    }
+
+    function test(name, func) {
+        core.ops.op_open_test(name);
+        try {
+            func();
+        } catch (e) {
+            core.ops.op_append_assert(false, e && e.message !== undefined ? e.message : String(e));
+        }
+        core.ops.op_close_test(name);
+    }
+
+    test.skip = (name) => {
+        core.ops.op_log(`skip test: ${name}`);
+    };
+
+    globalThis.pm = {
+        get request() {
+            return postmanRequest(core.ops.op_request());
+        },
+        get response() {
+            return postmanResponse(core.ops.op_response());
+        },
+        get cookies() {
+            return cookieList(core.ops.op_response().headers);
+        },
+        environment: variables("Environment"),
+        collectionVariables: variables("Collection"),
+        globals: variables("Globals"),
+        variables: {
+            get: (key) => {
+                return core.ops.op_has_env(key) ? core.ops.op_get_env(key) : undefined
+            },
+            has: (key) => {
+                return core.ops.op_has_env(key)
+            },
+            set: (key, value) => {
+                if (typeof value !== "string") {
+                    value = JSON.stringify(value)
+                }
+                return core.ops.op_set_env(key, value)
+            },
+            replaceIn: (template) => {
+                return String(template).replace(/\{\{([^{}]+)}}/g, (found, key) => {
+                    return core.ops.op_has_env(key) ? core.ops.op_get_env(key) : found
+                })
+            },
+        },
+        test,
+        expect: (value, message) => {
+            return new Assertion(value, message)
+        },
+        sendRequest: (request, callback) => {
+            const promise = globalThis.fetch(fetchRequest(request)).then(postmanResponse);
+            if (callback) {
+                return promise.then((response) => callback(null, response), (error) => callback(error, null));
+            }
+            return promise
+        },
+    };
+
+    globalThis.postman = {
+        setEnvironmentVariable: (key, value) => pm.environment.set(key, value),
+        getEnvironmentVariable: (key) => pm.environment.get(key),
+        clearEnvironmentVariable: (key) => pm.environment.unset(key),
+        setGlobalVariable: (key, value) => pm.globals.set(key, value),
+        getGlobalVariable: (key) => pm.globals.get(key),
+        clearGlobalVariable: (key) => pm.globals.unset(key),
+    };
+
 })(globalThis);
\ No newline at end of file
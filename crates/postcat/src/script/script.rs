@@ -1,7 +1,12 @@
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use deno_core::anyhow::Error;
 use deno_core::error::AnyError;
@@ -13,11 +18,15 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::{Client, Method};
 use serde::{Deserialize, Serialize};
 
-use crate::data::environment::{EnvironmentItemValue, EnvironmentValueType};
+use crate::data::environment::{
+    EnvironmentItemValue, EnvironmentValueType, VariableChange, VariableScope,
+};
 use crate::data::http;
-use crate::data::http::{Header, LockWith, QueryParam, Request};
+use crate::data::http::{BodyType, Header, LockWith, QueryParam, Request};
 use crate::data::logger::Logger;
+use crate::data::network_settings::ProxyConfig;
 use crate::data::test::TestResult;
+use crate::operation::rest_sender::RestSender;
 
 #[derive(Default, Clone)]
 pub struct ScriptRuntime {}
@@ -31,6 +40,11 @@ pub struct Context {
     pub shared_map: BTreeMap<String, String>,
     pub logger: Logger,
     pub test_result: TestResult,
+    pub message: JsWebSocketMessage,
+    pub outbox: Vec<String>,
+    pub proxy: ProxyConfig,
+    pub cancel: Arc<AtomicBool>,
+    pub variable_changes: Vec<VariableChange>,
 }
 
 #[derive(Default, Clone)]
@@ -47,48 +61,72 @@ impl ScriptRuntime {
         Promise::spawn_thread("script", || ScriptRuntime::run_block_many(scripts, context))
     }
 
-    pub fn run_block_many(
+    pub fn run_block_many(scripts: Vec<ScriptScope>, context: Context) -> Result<Context, Error> {
+        let (context, result) = ScriptRuntime::run_block_many_partial(scripts, context);
+        result.map(|_| context)
+    }
+
+    /// Also returns the context when a script fails, with everything the scripts did
+    /// before the failure, so variable changes made so far can still be saved.
+    pub fn run_block_many_partial(
         scripts: Vec<ScriptScope>,
         mut context: Context,
-    ) -> Result<Context, Error> {
+    ) -> (Context, Result<(), Error>) {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         for script_scope in scripts.iter() {
+            if context.cancel.load(Ordering::Relaxed) {
+                return (context, Err(Error::msg("script cancelled")));
+            }
             context.scope_name = script_scope.scope.clone();
             context
                 .logger
                 .add_info(script_scope.scope.clone(), "execute script.".to_string());
-            let step_context = runtime.block_on(async {
+            let (step_context, result) = runtime.block_on(async {
                 ScriptRuntime::run_js(script_scope.script.clone(), context.clone()).await
-            })?;
+            });
             context.envs = step_context.envs.clone();
             context.request = step_context.request.clone();
             context.logger = step_context.logger.clone();
             context.shared_map = step_context.shared_map.clone();
             context.test_result = step_context.test_result.clone();
+            context.outbox = step_context.outbox.clone();
+            context.variable_changes = step_context.variable_changes.clone();
+            if result.is_err() {
+                return (context, result);
+            }
         }
-        Ok(context)
+        (context, Ok(()))
     }
 
-    async fn run_js(js: String, context: Context) -> Result<Context, Error> {
+    async fn run_js(js: String, context: Context) -> (Context, Result<(), Error>) {
         let runjs_extension = ExtensionBuilder::default()
             .ops(vec![
                 op_set_env::DECL,
                 op_get_env::DECL,
+                op_has_env::DECL,
+                op_get_variable::DECL,
+                op_has_variable::DECL,
+                op_set_variable::DECL,
+                op_unset_variable::DECL,
                 op_add_params::DECL,
                 op_add_header::DECL,
+                op_remove_header::DECL,
                 op_log::DECL,
                 op_error::DECL,
                 op_warn::DECL,
                 op_http_fetch::DECL,
                 op_get_shared::DECL,
                 op_set_shared::DECL,
+                op_request::DECL,
                 op_response::DECL,
                 op_open_test::DECL,
                 op_close_test::DECL,
                 op_append_assert::DECL,
+                op_message::DECL,
+                op_send_message::DECL,
             ])
             .build();
         let mut js_runtime = deno_core::JsRuntime::new(deno_core::RuntimeOptions {
@@ -96,11 +134,32 @@ impl ScriptRuntime {
             extensions: vec![runjs_extension],
             ..Default::default()
         });
-        js_runtime.op_state().borrow_mut().put(context);
+        let cancel = context.cancel.clone();
+        js_runtime.op_state().borrow_mut().put(context.clone());
         let runtime_init_code = include_str!("./resource/runtime.js");
         js_runtime
             .execute_script_static("[runjs:runtime.js]", runtime_init_code)
             .unwrap();
+        let finished = Arc::new(AtomicBool::new(false));
+        ScriptRuntime::watch_cancel(
+            js_runtime.v8_isolate().thread_safe_handle(),
+            cancel.clone(),
+            finished.clone(),
+        );
+        let result = ScriptRuntime::evaluate(&mut js_runtime, js).await;
+        finished.store(true, Ordering::Relaxed);
+        let op_state = js_runtime.op_state();
+        let new_context = match op_state.borrow().try_borrow::<Context>() {
+            None => return (context, Err(Error::msg("get context error"))),
+            Some(new_context) => new_context.clone(),
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return (new_context, Err(Error::msg("script cancelled")));
+        }
+        (new_context, result)
+    }
+
+    async fn evaluate(js_runtime: &mut deno_core::JsRuntime, js: String) -> Result<(), Error> {
         let temp = Url::from_file_path(Path::new("/postcat/pre-request-script.js")).unwrap();
         let mod_id = js_runtime
             .load_main_module(&temp, Some(ModuleCode::from(js)))
@@ -109,14 +168,23 @@ impl ScriptRuntime {
         js_runtime
             .run_event_loop(PollEventLoopOptions::default())
             .await?;
-        result.await?;
-        let op_state = js_runtime.op_state();
-        let new_context = op_state
-            .borrow()
-            .try_borrow::<Context>()
-            .ok_or(Error::msg("get context error"))?
-            .clone();
-        Ok(new_context)
+        result.await
+    }
+
+    fn watch_cancel(
+        isolate: deno_core::v8::IsolateHandle,
+        cancel: Arc<AtomicBool>,
+        finished: Arc<AtomicBool>,
+    ) {
+        thread::spawn(move || {
+            while !finished.load(Ordering::Relaxed) {
+                if cancel.load(Ordering::Relaxed) {
+                    isolate.terminate_execution();
+                    return;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
     }
 }
 
@@ -174,6 +242,68 @@ fn op_set_env(state: &mut OpState, #[string] key: String, #[string] value: Strin
     }
 }
 
+#[op2(fast)]
+fn op_set_variable(
+    state: &mut OpState,
+    #[string] scope: String,
+    #[string] key: String,
+    #[string] value: String,
+) {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => {}
+        Some(c) => match VariableScope::from_str(scope.as_str()) {
+            Err(_) => c
+                .logger
+                .add_error(c.scope_name.clone(), format!("unknown scope `{}`", scope)),
+            Ok(variable_scope) => {
+                c.envs.insert(
+                    key.clone(),
+                    EnvironmentItemValue {
+                        value: value.clone(),
+                        scope: variable_scope.to_string(),
+                        value_type: EnvironmentValueType::String,
+                    },
+                );
+                c.logger.add_info(
+                    c.scope_name.clone(),
+                    format!("set {} variable: `{}` as `{}`", scope, key, value),
+                );
+                c.variable_changes.push(VariableChange {
+                    scope: variable_scope,
+                    key,
+                    value: Some(value),
+                });
+            }
+        },
+    }
+}
+
+#[op2(fast)]
+fn op_unset_variable(state: &mut OpState, #[string] scope: String, #[string] key: String) {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => {}
+        Some(c) => match VariableScope::from_str(scope.as_str()) {
+            Err(_) => c
+                .logger
+                .add_error(c.scope_name.clone(), format!("unknown scope `{}`", scope)),
+            Ok(variable_scope) => {
+                c.envs.remove(key.as_str());
+                c.logger.add_info(
+                    c.scope_name.clone(),
+                    format!("unset {} variable: `{}`", scope, key),
+                );
+                c.variable_changes.push(VariableChange {
+                    scope: variable_scope,
+                    key,
+                    value: None,
+                });
+            }
+        },
+    }
+}
+
 #[op2]
 #[string]
 fn op_get_env(state: &mut OpState, #[string] key: String) -> String {
@@ -191,6 +321,39 @@ fn op_get_env(state: &mut OpState, #[string] key: String) -> String {
     }
 }
 
+#[op2(fast)]
+fn op_has_env(state: &mut OpState, #[string] key: String) -> bool {
+    state
+        .try_borrow::<Context>()
+        .map(|c| c.envs.contains_key(key.as_str()))
+        .unwrap_or(false)
+}
+
+fn scoped_variable(c: &Context, scope: &str, key: &str) -> Option<String> {
+    let variable_scope = VariableScope::from_str(scope).ok()?;
+    c.envs
+        .get(key)
+        .filter(|v| variable_scope.contains(v.scope.as_str()))
+        .map(|v| v.value.clone())
+}
+
+#[op2]
+#[string]
+fn op_get_variable(state: &mut OpState, #[string] scope: String, #[string] key: String) -> String {
+    state
+        .try_borrow::<Context>()
+        .and_then(|c| scoped_variable(c, scope.as_str(), key.as_str()))
+        .unwrap_or_default()
+}
+
+#[op2(fast)]
+fn op_has_variable(state: &mut OpState, #[string] scope: String, #[string] key: String) -> bool {
+    state
+        .try_borrow::<Context>()
+        .and_then(|c| scoped_variable(c, scope.as_str(), key.as_str()))
+        .is_some()
+}
+
 #[op2(fast)]
 fn op_add_header(state: &mut OpState, #[string] key: String, #[string] value: String) {
     let context = state.try_borrow_mut::<Context>();
@@ -213,6 +376,21 @@ fn op_add_header(state: &mut OpState, #[string] key: String, #[string] value: St
     }
 }
 
+#[op2(fast)]
+fn op_remove_header(state: &mut OpState, #[string] key: String) {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => {}
+        Some(c) => {
+            c.request
+                .headers
+                .retain(|h| !h.key.eq_ignore_ascii_case(key.as_str()));
+            c.logger
+                .add_info(c.scope_name.clone(), format!("remove header: `{}`", key));
+        }
+    }
+}
+
 #[op2(fast)]
 fn op_add_params(state: &mut OpState, #[string] key: String, #[string] value: String) {
     let context = state.try_borrow_mut::<Context>();
@@ -271,6 +449,29 @@ pub struct JsRequest {
     body: String,
 }
 
+impl JsRequest {
+    pub fn from_data_request(request: Request) -> Self {
+        Self {
+            method: request.method.to_string(),
+            url: RestSender::build_url(&request),
+            headers: request
+                .headers
+                .iter()
+                .filter(|h| h.enable)
+                .map(|h| JsHeader {
+                    name: h.key.clone(),
+                    value: h.value.clone(),
+                })
+                .collect(),
+            body: match request.body.body_type {
+                BodyType::RAW => request.body.body_str.clone(),
+                BodyType::X_WWW_FROM_URLENCODED => request.body.to_form_urlencoded(),
+                _ => "".to_string(),
+            },
+        }
+    }
+}
+
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct JsHeader {
     name: String,
@@ -280,14 +481,29 @@ pub struct JsHeader {
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct JsResponse {
     status: u16,
+    status_text: String,
     headers: Vec<JsHeader>,
     text: String,
+    timing: JsTiming,
+    size: http::TransferSize,
+}
+
+/// Phase durations in milliseconds.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct JsTiming {
+    dns: f64,
+    tcp: f64,
+    tls: f64,
+    ttfb: f64,
+    download: f64,
+    total: f64,
 }
 
 impl JsResponse {
     pub fn from_data_response(response: http::Response) -> Self {
         Self {
             status: response.status,
+            status_text: response.status_text.clone(),
             headers: response
                 .headers
                 .iter()
@@ -297,28 +513,61 @@ impl JsResponse {
                 })
                 .collect(),
             text: String::from_utf8(response.body.to_vec()).unwrap_or("".to_string()),
+            timing: JsTiming {
+                dns: response.timing.dns as f64 / 1000.0,
+                tcp: response.timing.tcp as f64 / 1000.0,
+                tls: response.timing.tls as f64 / 1000.0,
+                ttfb: response.timing.ttfb as f64 / 1000.0,
+                download: response.timing.download as f64 / 1000.0,
+                total: response.timing.total as f64 / 1000.0,
+            },
+            size: response.size,
         }
     }
 }
 #[op2(async)]
 #[serde]
-async fn op_http_fetch(#[serde] request: JsRequest) -> Result<JsResponse, AnyError> {
+async fn op_http_fetch(
+    state: Rc<RefCell<OpState>>,
+    #[serde] request: JsRequest,
+) -> Result<JsResponse, AnyError> {
+    let (proxy, cancel) = state
+        .borrow()
+        .try_borrow::<Context>()
+        .map(|c| (c.proxy.clone(), c.cancel.clone()))
+        .unwrap_or_default();
     let method_enum = Method::from_str(request.method.to_uppercase().as_str())?;
     let mut request_headers = HeaderMap::new();
     for header in request.headers.iter() {
         request_headers.insert(
-            HeaderName::from_str(header.value.as_str())?,
+            HeaderName::from_str(header.name.as_str())?,
             HeaderValue::from_str(header.value.as_str())?,
         );
     }
-    let response = Client::builder()
+    let mut builder = Client::builder();
+    if let Some(proxies) = proxy.build().map_err(Error::msg)? {
+        builder = proxies
+            .into_iter()
+            .fold(builder.no_proxy(), |builder, proxy| builder.proxy(proxy));
+    }
+    let send = builder
         .build()?
         .request(method_enum, request.url)
         .headers(request_headers)
         .body(request.body)
-        .send()
-        .await?;
-    let status = response.status().as_u16();
+        .send();
+    tokio::pin!(send);
+    let response = loop {
+        tokio::select! {
+            response = &mut send => break response?,
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(Error::msg("request cancelled"));
+                }
+            }
+        }
+    };
+    let status = response.status();
     let mut response_headers: Vec<JsHeader> = vec![];
     for (header_name, header_value) in response.headers().iter() {
         response_headers.push(JsHeader {
@@ -328,13 +577,57 @@ async fn op_http_fetch(#[serde] request: JsRequest) -> Result<JsResponse, AnyErr
     }
     let text = response.text().await?.clone();
     let result = JsResponse {
-        status,
+        status: status.as_u16(),
+        status_text: status.to_string(),
         text,
         headers: response_headers,
+        ..Default::default()
     };
     Ok(result)
 }
 
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct JsWebSocketMessage {
+    pub message_type: String,
+    pub data: String,
+}
+
+#[op2]
+#[serde]
+fn op_message(state: &mut OpState) -> JsWebSocketMessage {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => JsWebSocketMessage::default(),
+        Some(c) => c.message.clone(),
+    }
+}
+
+#[op2(fast)]
+fn op_send_message(state: &mut OpState, #[string] data: String) {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => {}
+        Some(c) => {
+            c.outbox.push(data.clone());
+            c.logger
+                .add_info(c.scope_name.clone(), format!("send message: `{}`", data));
+        }
+    }
+}
+
+#[op2]
+#[serde]
+fn op_request(state: &mut OpState) -> JsRequest {
+    let context = state.try_borrow_mut::<Context>();
+    match context {
+        None => JsRequest::default(),
+        Some(c) => JsRequest::from_data_request(RestSender::build_request(
+            c.request.clone(),
+            c.envs.clone(),
+        )),
+    }
+}
+
 #[op2]
 #[serde]
 fn op_response(state: &mut OpState) -> JsResponse {
@@ -371,3 +664,197 @@ fn op_append_assert(state: &mut OpState, result: bool, #[string] msg: String) {
         Some(c) => c.test_result.append(result, msg),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use crate::data::environment::{EnvironmentItemValue, EnvironmentValueType};
+    use crate::data::http;
+    use crate::data::http::{Header, HttpBody};
+    use crate::data::test::{TestInfo, TestStatus};
+
+    use super::{Context, JsResponse, ScriptRuntime, ScriptScope};
+
+    fn variable(value: &str, scope: &str) -> EnvironmentItemValue {
+        EnvironmentItemValue {
+            value: value.to_string(),
+            scope: scope.to_string(),
+            value_type: EnvironmentValueType::String,
+        }
+    }
+
+    fn run(script: &str, context: Context) -> Context {
+        let (context, result) = ScriptRuntime::run_block_many_partial(
+            vec![ScriptScope {
+                script: script.to_string(),
+                scope: "request".to_string(),
+            }],
+            context,
+        );
+        result.unwrap();
+        context
+    }
+
+    #[test]
+    fn scoped_variables_only_read_their_own_scope() {
+        let mut envs = BTreeMap::new();
+        envs.insert("host".to_string(), variable("dev.example.com", "dev"));
+        envs.insert("token".to_string(), variable("abc", "__Globals__"));
+        envs.insert("page".to_string(), variable("2", "Users Collection"));
+        let context = run(
+            r#"
+            postcat.set_shared("result", [
+                pm.environment.get("host"),
+                pm.environment.has("token"),
+                pm.globals.get("token"),
+                pm.globals.get("host") === undefined,
+                pm.collectionVariables.get("page"),
+                pm.collectionVariables.has("host"),
+                pm.variables.get("host"),
+                pm.variables.has("page"),
+            ])
+            "#,
+            Context {
+                envs,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            context.shared_map.get("result").unwrap(),
+            r#"["dev.example.com",false,"abc",true,"2",false,"dev.example.com",true]"#
+        );
+    }
+
+    #[test]
+    fn scoped_variables_see_values_set_by_the_script() {
+        let context = run(
+            r#"
+            pm.collectionVariables.set("id", 7)
+            postcat.set_shared("result", [
+                pm.collectionVariables.get("id"),
+                pm.environment.has("id"),
+            ])
+            "#,
+            Context::default(),
+        );
+        assert_eq!(context.shared_map.get("result").unwrap(), r#"["7",false]"#);
+    }
+
+    fn response_context() -> Context {
+        Context {
+            response: JsResponse::from_data_response(http::Response {
+                body: Arc::new(HttpBody::new(
+                    br#"{"id":7,"name":"postcat","tags":["rest","grpc"],"owner":{"id":1}}"#
+                        .to_vec(),
+                )),
+                headers: vec![Header {
+                    key: "Content-Type".to_string(),
+                    value: "application/json".to_string(),
+                    enable: true,
+                    ..Default::default()
+                }],
+                status: 200,
+                status_text: "OK".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn test_info<'a>(context: &'a Context, name: &str) -> &'a TestInfo {
+        context
+            .test_result
+            .test_info_list
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn postman_assertions_pass() {
+        let context = run(
+            r#"
+            const body = pm.response.json()
+            pm.test("type", () => {
+                pm.expect(body).to.be.an("object")
+                pm.expect(body.tags).to.be.an("array")
+            })
+            pm.test("property", () => {
+                pm.expect(body).to.have.property("name", "postcat")
+                pm.expect(body).to.have.property("owner")
+            })
+            pm.test("deep equal", () => {
+                pm.expect(body.owner).to.deep.equal({ id: 1 })
+                pm.expect(body.tags).to.deep.equal(["rest", "grpc"])
+            })
+            pm.test("include", () => {
+                pm.expect(body.tags).to.include("grpc")
+                pm.expect(body.name).to.include("cat")
+            })
+            pm.test("status", () => {
+                pm.response.to.have.status(200)
+                pm.response.to.have.status("OK")
+                pm.response.to.be.ok
+            })
+            pm.test("not", () => {
+                pm.expect(body).to.not.have.property("missing")
+                pm.expect(body.tags).to.not.include("soap")
+                pm.expect(body.owner).to.not.equal({ id: 1 })
+                pm.response.to.not.have.status(404)
+            })
+            "#,
+            response_context(),
+        );
+        assert_eq!(context.test_result.status, TestStatus::PASS);
+        for name in ["type", "property", "deep equal", "include", "status", "not"] {
+            assert_eq!(
+                test_info(&context, name).status,
+                TestStatus::PASS,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn failing_assertion_reports_its_message() {
+        let context = run(
+            r#"
+            pm.test("status is created", () => {
+                pm.response.to.have.status(201)
+            })
+            pm.test("name", () => {
+                pm.expect(pm.response.json().name, "name field").to.equal("postman")
+            })
+            pm.test("negated", () => {
+                pm.expect([1, 2]).to.not.include(2)
+            })
+            pm.test("id", () => {
+                pm.expect(pm.response.json().id).to.equal(7)
+            })
+            "#,
+            response_context(),
+        );
+        assert_eq!(context.test_result.status, TestStatus::FAIL);
+        let failures = [
+            (
+                "status is created",
+                "expected response to have status 201 but got 200",
+            ),
+            (
+                "name",
+                r#"name field: expected "postcat" to equal "postman""#,
+            ),
+            ("negated", "expected [1,2] to not include 2"),
+        ];
+        for (name, message) in failures {
+            let info = test_info(&context, name);
+            assert_eq!(info.status, TestStatus::FAIL);
+            assert_eq!(info.results[0].assert_result, TestStatus::FAIL);
+            assert_eq!(info.results[0].msg, message);
+        }
+        assert_eq!(test_info(&context, "id").status, TestStatus::PASS);
+    }
+}
@@ -10,10 +10,11 @@ use egui::{
     TextBuffer, Ui, WidgetText,
 };
 use regex::Regex;
+use strum::IntoEnumIterator;
 
 use crate::data::environment::{EnvironmentItemValue, EnvironmentValueType};
 use crate::data::environment_function::{get_env_result, EnvFunction};
-use crate::data::http::HttpRecord;
+use crate::data::http::{HttpRecord, Method, RecordType};
 use crate::panels::HORIZONTAL_GAP;
 
 pub fn build_rest_ui_header(hr: HttpRecord, max_char: Option<usize>, ui: &Ui) -> LayoutJob {
@@ -38,9 +39,16 @@ pub fn build_rest_ui_header(hr: HttpRecord, max_char: Option<usize>, ui: &Ui) ->
         .trim()
         .trim_start_matches("http://")
         .trim_start_matches("https://")
+        .trim_start_matches("ws://")
+        .trim_start_matches("wss://")
         .to_string();
+    let method = match hr.record_type {
+        RecordType::HTTP => hr.request.method.to_string(),
+        RecordType::WebSocket => "WS".to_string(),
+        RecordType::GRPC => "gRPC".to_string(),
+    };
     if base_url != "" {
-        RichText::new(hr.request.method.to_string() + " ")
+        RichText::new(method + " ")
             .color(ui.visuals().warn_fg_color)
             .strong()
             .append_to(&mut lb, &style, FontSelection::Default, Align::Center);
@@ -218,19 +226,23 @@ pub fn text_edit_singleline_justify<S: TextBuffer>(ui: &mut Ui, text: &mut S) ->
     .inner
 }
 
-pub fn text_edit_singleline_filter_justify<S: TextBuffer>(ui: &mut Ui, text: &mut S) -> Response {
-    text.replace(
-        text.as_str()
-            .replace("/", "_")
-            .as_str()
-            .replace(" ", "_")
-            .as_str(),
-    );
-    let filtered_string: String = text
-        .as_str()
+pub fn filter_name(name: &str) -> String {
+    name.replace("/", "_")
+        .replace(" ", "_")
         .chars()
         .filter(|&c| c.is_ascii_alphabetic() || c.is_alphabetic() || c.is_numeric() || c == '_')
-        .collect();
+        .collect()
+}
+
+pub fn url_decode(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(value.as_str())
+        .map(|v| v.to_string())
+        .unwrap_or(value)
+}
+
+pub fn text_edit_singleline_filter_justify<S: TextBuffer>(ui: &mut Ui, text: &mut S) -> Response {
+    let filtered_string = filter_name(text.as_str());
     text.replace(filtered_string.as_str());
     ui.with_layout(
         Layout::top_down(Align::LEFT).with_cross_justify(true),
@@ -240,18 +252,7 @@ pub fn text_edit_singleline_filter_justify<S: TextBuffer>(ui: &mut Ui, text: &mu
 }
 
 pub fn text_edit_singleline_filter<S: TextBuffer>(ui: &mut Ui, text: &mut S) -> Response {
-    text.replace(
-        text.as_str()
-            .replace("/", "_")
-            .as_str()
-            .replace(" ", "_")
-            .as_str(),
-    );
-    let filtered_string: String = text
-        .as_str()
-        .chars()
-        .filter(|&c| c.is_ascii_alphabetic() || c.is_alphabetic() || c.is_numeric() || c == '_')
-        .collect();
+    let filtered_string = filter_name(text.as_str());
     text.replace(filtered_string.as_str());
     ui.text_edit_singleline(text)
 }
@@ -293,3 +294,33 @@ pub fn selectable_check<Value: PartialEq>(
     }
     response
 }
+
+pub fn record_method_combo(ui: &mut Ui, rest: &mut HttpRecord) {
+    let selected_text = match rest.record_type {
+        RecordType::HTTP => rest.request.method.to_string(),
+        RecordType::WebSocket => "WS".to_string(),
+        RecordType::GRPC => "gRPC".to_string(),
+    };
+    egui::ComboBox::from_id_source("method")
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.style_mut().wrap = Some(false);
+            ui.set_min_width(60.0);
+            for x in Method::iter() {
+                let selected = rest.record_type == RecordType::HTTP && rest.request.method == x;
+                if ui.selectable_label(selected, x.to_string()).clicked() {
+                    rest.record_type = RecordType::HTTP;
+                    rest.request.method = x;
+                }
+            }
+            ui.separator();
+            let selected = rest.record_type == RecordType::WebSocket;
+            if ui.selectable_label(selected, "WS").clicked() {
+                rest.record_type = RecordType::WebSocket;
+            }
+            let selected = rest.record_type == RecordType::GRPC;
+            if ui.selectable_label(selected, "gRPC").clicked() {
+                rest.record_type = RecordType::GRPC;
+            }
+        });
+}
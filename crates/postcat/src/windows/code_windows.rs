@@ -0,0 +1,89 @@
+use egui::Ui;
+use strum::IntoEnumIterator;
+
+use crate::data::config_data::ConfigData;
+use crate::data::workspace_data::WorkspaceData;
+use crate::operation::code_generator::{CodeGenerator, CodeTarget};
+use crate::operation::operation::Operation;
+use crate::operation::windows::{Window, WindowSetting};
+use crate::panels::VERTICAL_GAP;
+
+#[derive(Default)]
+pub struct CodeWindows {
+    code_windows_open: bool,
+    crt_id: String,
+    select_target: CodeTarget,
+    keep_variables: bool,
+}
+
+impl Window for CodeWindows {
+    fn window_setting(&self) -> WindowSetting {
+        WindowSetting::new("GENERATE CODE")
+            .modal(true)
+            .max_width(600.0)
+            .min_height(400.0)
+            .max_height(400.0)
+            .collapsible(false)
+            .resizable(true)
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.code_windows_open = open;
+    }
+
+    fn get_open(&self) -> bool {
+        self.code_windows_open
+    }
+
+    fn render(
+        &mut self,
+        ui: &mut Ui,
+        _: &mut ConfigData,
+        workspace_data: &mut WorkspaceData,
+        _: Operation,
+    ) {
+        if !workspace_data.contains_crt_id(self.crt_id.clone()) {
+            ui.label("The request has been closed.");
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for target in CodeTarget::iter() {
+                ui.selectable_value(&mut self.select_target, target.clone(), target.to_string());
+            }
+        });
+        ui.separator();
+        ui.checkbox(&mut self.keep_variables, "Keep {{variables}} unresolved");
+        ui.add_space(VERTICAL_GAP);
+        let crt = workspace_data.must_get_crt(self.crt_id.clone());
+        let code = CodeGenerator::build(
+            crt.rest.request.clone(),
+            workspace_data.get_crt_envs(self.crt_id.clone()),
+            workspace_data.get_crt_parent_auth(self.crt_id.clone()),
+            self.keep_variables,
+        )
+        .generate(&self.select_target);
+        let tooltip = "Click to copy the code";
+        if ui.button("📋").on_hover_text(tooltip).clicked() {
+            ui.output_mut(|o| o.copied_text = code.clone());
+        }
+        ui.push_id("generate_code", |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut code.as_str())
+                        .font(egui::TextStyle::Monospace)
+                        .code_editor()
+                        .desired_rows(10)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+        });
+    }
+}
+
+impl CodeWindows {
+    pub fn with(mut self, crt_id: String) -> Self {
+        self.code_windows_open = true;
+        self.crt_id = crt_id;
+        self
+    }
+}
@@ -1,13 +1,16 @@
 use egui::{Align, Button, Checkbox, Layout, ScrollArea, TextEdit, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
+use strum::IntoEnumIterator;
 
 use crate::data::config_data::ConfigData;
 use crate::data::environment::{EnvironmentConfig, EnvironmentItem, ENVIRONMENT_GLOBALS};
+use crate::data::network_settings::SslVerification;
 use crate::data::workspace_data::WorkspaceData;
 use crate::operation::operation::Operation;
 use crate::operation::windows::{Window, WindowSetting};
 use crate::panels::{HORIZONTAL_GAP, VERTICAL_GAP};
 use crate::utils;
+use crate::windows::network_settings_windows::NetworkSettingsWindows;
 
 #[derive(Default)]
 pub struct EnvironmentWindows {
@@ -98,6 +101,29 @@ impl EnvironmentWindows {
             TextEdit::singleline(&mut self.select_env_name)
                 .desired_width(f32::INFINITY)
                 .ui(ui);
+            ui.add_space(VERTICAL_GAP);
+            ui.horizontal(|ui| {
+                ui.strong("SSL Certificate Verification");
+                egui::ComboBox::from_id_source("env_ssl_verification")
+                    .selected_text(self.select_env_config.ssl_verification.to_string())
+                    .show_ui(ui, |ui| {
+                        for x in SslVerification::iter() {
+                            ui.selectable_value(
+                                &mut self.select_env_config.ssl_verification,
+                                x.clone(),
+                                x.to_string(),
+                            );
+                        }
+                    });
+            });
+            ui.collapsing("Proxy", |ui| {
+                NetworkSettingsWindows::render_proxy(
+                    ui,
+                    "env_proxy",
+                    &mut self.select_env_config.proxy,
+                    true,
+                );
+            });
         }
         ui.add_space(VERTICAL_GAP * 2.0);
         let mut delete_index = None;
@@ -4,11 +4,20 @@ use std::path::PathBuf;
 
 use deno_core::error::AnyError;
 use egui::{Direction, Layout, Ui};
+use log::warn;
+use serde_json::Value;
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
+use uuid::Uuid;
 
+use crate::data::central_request_data::CentralRequestItem;
 use crate::data::config_data::ConfigData;
+use crate::data::curl::CurlImport;
 use crate::data::export::{Export, ExportType};
+use crate::data::har::HarImport;
+use crate::data::http::HttpRecord;
+use crate::data::openapi::OpenApiImport;
+use crate::data::postman::PostmanImport;
 use crate::data::workspace_data::WorkspaceData;
 use crate::operation::operation::Operation;
 use crate::operation::windows::{Window, WindowSetting};
@@ -20,6 +29,7 @@ pub struct ImportWindows {
     select_type: ImportType,
     raw: String,
     picked_path: Option<PathBuf>,
+    warnings: Vec<String>,
 }
 
 #[derive(EnumIter, EnumString, Display, PartialEq, Clone)]
@@ -66,6 +76,7 @@ impl Window for ImportWindows {
             }
         });
         ui.separator();
+        self.render_warnings(ui);
         match self.select_type {
             ImportType::File => {
                 ui.with_layout(Layout::centered_and_justified(Direction::TopDown), |ui| {
@@ -135,8 +146,69 @@ impl ImportWindows {
         workspace_data: &mut WorkspaceData,
         operation: &Operation,
     ) -> Result<(), AnyError> {
-        let export_result: Result<Export, serde_json::Error> =
-            serde_json::from_str(content.as_str());
+        self.warnings.clear();
+        if CurlImport::is_match(content.as_str()) {
+            return match CurlImport::parse(content.as_str()) {
+                Ok(curl_import) => {
+                    workspace_data.add_crt(CentralRequestItem {
+                        id: Uuid::new_v4().to_string(),
+                        collection_path: None,
+                        rest: HttpRecord {
+                            request: curl_import.request,
+                            settings: curl_import.settings,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    });
+                    operation.add_success_toast("Import cURL request success.");
+                    self.set_warnings(curl_import.warnings);
+                    Ok(())
+                }
+                Err(e) => {
+                    operation.add_error_toast(format!("Error while importing cURL: {}", e));
+                    Ok(())
+                }
+            };
+        }
+        let value: Value = match serde_json::from_str(content.as_str())
+            .ok()
+            .or_else(|| serde_yaml::from_str(content.as_str()).ok())
+        {
+            Some(value) => value,
+            None => {
+                operation.add_error_toast("Error while importing: format not recognized");
+                return Ok(());
+            }
+        };
+        if PostmanImport::is_match(&value) {
+            let postman_import = PostmanImport::from_value(&value);
+            let new_name = workspace_data.import_collection(postman_import.collection);
+            operation.add_success_toast(format!("Import collections `{}` success.", new_name));
+            self.set_warnings(postman_import.warnings);
+            return Ok(());
+        }
+        if HarImport::is_match(&value) {
+            match HarImport::from_value(&value) {
+                Ok(har_import) => {
+                    let new_name = workspace_data.import_collection(har_import.collection);
+                    operation
+                        .add_success_toast(format!("Import collections `{}` success.", new_name));
+                    self.set_warnings(har_import.warnings);
+                }
+                Err(e) => {
+                    operation.add_error_toast(format!("Error while importing HAR: {}", e));
+                }
+            }
+            return Ok(());
+        }
+        if OpenApiImport::is_match(&value) {
+            let openapi_import = OpenApiImport::from_value(&value);
+            let new_name = workspace_data.import_collection(openapi_import.collection);
+            operation.add_success_toast(format!("Import collections `{}` success.", new_name));
+            self.set_warnings(openapi_import.warnings);
+            return Ok(());
+        }
+        let export_result: Result<Export, serde_json::Error> = serde_json::from_value(value);
         match export_result {
             Ok(export) => match export.export_type {
                 ExportType::Collection => {
@@ -160,6 +232,30 @@ impl ImportWindows {
         }
         Ok(())
     }
+    fn set_warnings(&mut self, warnings: Vec<String>) {
+        for warning in warnings.iter() {
+            warn!("import warning: {}", warning);
+        }
+        self.warnings = warnings;
+    }
+
+    fn render_warnings(&mut self, ui: &mut Ui) {
+        if self.warnings.is_empty() {
+            return;
+        }
+        ui.strong(format!("Imported with {} warnings:", self.warnings.len()));
+        ui.push_id("import_warnings", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for warning in self.warnings.iter() {
+                        ui.label(warning);
+                    }
+                });
+        });
+        ui.separator();
+    }
+
     fn process_file(
         &mut self,
         workspace_data: &mut WorkspaceData,
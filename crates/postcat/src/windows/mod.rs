@@ -1,6 +1,8 @@
+pub mod code_windows;
 pub mod cookies_windows;
 pub mod environment_windows;
 pub mod import_windows;
+pub mod network_settings_windows;
 pub mod new_collection_windows;
 pub mod request_close_windows;
 pub mod save_crt_windows;
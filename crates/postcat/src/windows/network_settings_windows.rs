@@ -0,0 +1,281 @@
+use egui::{Align, Layout, ScrollArea, TextEdit, Ui, Widget};
+use strum::IntoEnumIterator;
+
+use crate::data::config_data::ConfigData;
+use crate::data::network_settings::{
+    CaCertificate, CertificateType, ClientCertificate, NetworkConfig, ProxyConfig, ProxyMode,
+};
+use crate::data::workspace_data::WorkspaceData;
+use crate::operation::operation::Operation;
+use crate::operation::windows::{Window, WindowSetting};
+use crate::panels::VERTICAL_GAP;
+
+#[derive(Default)]
+pub struct NetworkSettingsWindows {
+    windows_open: bool,
+    config: Option<NetworkConfig>,
+}
+
+impl Window for NetworkSettingsWindows {
+    fn window_setting(&self) -> WindowSetting {
+        WindowSetting::new("NETWORK SETTINGS")
+            .modal(true)
+            .default_width(600.0)
+            .default_height(400.0)
+            .collapsible(false)
+            .resizable(true)
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.windows_open = open;
+    }
+
+    fn get_open(&self) -> bool {
+        self.windows_open
+    }
+
+    fn render(
+        &mut self,
+        ui: &mut Ui,
+        _: &mut ConfigData,
+        workspace_data: &mut WorkspaceData,
+        operation: Operation,
+    ) {
+        let mut config = self
+            .config
+            .take()
+            .unwrap_or_else(|| workspace_data.get_network_config());
+        self.render_bottom(ui, workspace_data, &operation, &config);
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.checkbox(
+                &mut config.disable_ssl_verification,
+                "Disable SSL certificate verification",
+            )
+            .on_hover_text("Can be overridden by the environment or the request settings.");
+            ui.add_space(VERTICAL_GAP * 2.0);
+            ui.separator();
+            Self::render_ca_certificates(ui, &mut config.ca_certificates);
+            ui.add_space(VERTICAL_GAP * 2.0);
+            ui.separator();
+            Self::render_client_certificates(ui, &mut config.client_certificates);
+            ui.add_space(VERTICAL_GAP * 2.0);
+            ui.separator();
+            ui.strong("Proxy");
+            Self::render_proxy(ui, "workspace_proxy", &mut config.proxy, false);
+        });
+        if self.windows_open {
+            self.config = Some(config);
+        }
+    }
+}
+
+impl NetworkSettingsWindows {
+    fn render_bottom(
+        &mut self,
+        ui: &mut Ui,
+        workspace_data: &mut WorkspaceData,
+        operation: &Operation,
+        config: &NetworkConfig,
+    ) {
+        egui::TopBottomPanel::bottom("network_settings_bottom_panel")
+            .resizable(false)
+            .min_height(0.0)
+            .show_inside(ui, |ui| {
+                ui.add_space(VERTICAL_GAP);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("Save").clicked() {
+                        workspace_data.set_network_config(config.clone());
+                        operation.add_success_toast("Network settings saved.");
+                        self.windows_open = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.windows_open = false;
+                    }
+                });
+            });
+    }
+
+    fn render_ca_certificates(ui: &mut Ui, ca_certificates: &mut Vec<CaCertificate>) {
+        ui.strong("CA Certificates");
+        ui.label("Custom root certificates (PEM or DER) trusted in addition to the system store.");
+        let mut delete_index = None;
+        for (index, ca) in ca_certificates.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut ca.enable, "");
+                if ui.button("x").clicked() {
+                    delete_index = Some(index);
+                }
+                Self::render_file(ui, &mut ca.path);
+            });
+        }
+        if let Some(index) = delete_index {
+            ca_certificates.remove(index);
+        }
+        if ui.button("Add CA Certificate").clicked() {
+            ca_certificates.push(CaCertificate {
+                enable: true,
+                ..Default::default()
+            });
+        }
+    }
+
+    fn render_client_certificates(ui: &mut Ui, client_certificates: &mut Vec<ClientCertificate>) {
+        ui.strong("Client Certificates");
+        ui.label("The first enabled certificate whose host matches the request is sent. Host supports `*`, `*.example.com` and an optional `:port`.");
+        let mut delete_index = None;
+        for (index, certificate) in client_certificates.iter_mut().enumerate() {
+            ui.add_space(VERTICAL_GAP);
+            ui.push_id(format!("client_certificate_{}", index), |ui| {
+                egui::Grid::new("client_certificate_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut certificate.enable, "");
+                            if ui.button("x").clicked() {
+                                delete_index = Some(index);
+                            }
+                        });
+                        ui.end_row();
+                        ui.label("Host:");
+                        TextEdit::singleline(&mut certificate.host)
+                            .hint_text("example.com:443")
+                            .desired_width(f32::INFINITY)
+                            .ui(ui);
+                        ui.end_row();
+                        ui.label("Type:");
+                        egui::ComboBox::from_id_source("client_certificate_type")
+                            .selected_text(certificate.cert_type.to_string())
+                            .show_ui(ui, |ui| {
+                                for x in CertificateType::iter() {
+                                    ui.selectable_value(
+                                        &mut certificate.cert_type,
+                                        x.clone(),
+                                        x.to_string(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                        match certificate.cert_type {
+                            CertificateType::PEM => {
+                                ui.label("Certificate:");
+                                ui.horizontal(|ui| {
+                                    Self::render_file(ui, &mut certificate.cert_file)
+                                });
+                                ui.end_row();
+                                ui.label("Key:");
+                                ui.horizontal(|ui| {
+                                    Self::render_file(ui, &mut certificate.key_file)
+                                });
+                                ui.end_row();
+                            }
+                            CertificateType::PKCS12 => {
+                                ui.label("PFX File:");
+                                ui.horizontal(|ui| {
+                                    Self::render_file(ui, &mut certificate.cert_file)
+                                });
+                                ui.end_row();
+                                ui.label("Password:");
+                                TextEdit::singleline(&mut certificate.password)
+                                    .password(true)
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+            ui.separator();
+        }
+        if let Some(index) = delete_index {
+            client_certificates.remove(index);
+        }
+        if ui.button("Add Client Certificate").clicked() {
+            client_certificates.push(ClientCertificate {
+                enable: true,
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn render_proxy(ui: &mut Ui, id: &str, proxy: &mut ProxyConfig, inherit: bool) {
+        ui.horizontal(|ui| {
+            ui.label("Mode:");
+            egui::ComboBox::from_id_source(id)
+                .selected_text(match (&proxy.mode, inherit) {
+                    (ProxyMode::Inherit, false) => ProxyMode::System.to_string(),
+                    (mode, _) => mode.to_string(),
+                })
+                .show_ui(ui, |ui| {
+                    for x in ProxyMode::iter() {
+                        if x == ProxyMode::Inherit && !inherit {
+                            continue;
+                        }
+                        ui.selectable_value(&mut proxy.mode, x.clone(), x.to_string());
+                    }
+                });
+        });
+        match proxy.mode {
+            ProxyMode::Inherit if inherit => {
+                ui.label("Use the workspace proxy settings.");
+            }
+            ProxyMode::Inherit | ProxyMode::System => {
+                ui.label("Use the HTTP_PROXY, HTTPS_PROXY, ALL_PROXY and NO_PROXY environment variables.");
+            }
+            ProxyMode::NoProxy => {
+                ui.label("Connect directly without any proxy.");
+            }
+            ProxyMode::Custom => {
+                ui.push_id(id, |ui| {
+                    egui::Grid::new("proxy_grid").num_columns(2).show(ui, |ui| {
+                        for (label, hint, value) in [
+                            (
+                                "HTTP Proxy:",
+                                "http://proxy.example.com:8080",
+                                &mut proxy.http_proxy,
+                            ),
+                            (
+                                "HTTPS Proxy:",
+                                "http://proxy.example.com:8080",
+                                &mut proxy.https_proxy,
+                            ),
+                            (
+                                "SOCKS5 Proxy:",
+                                "socks5://127.0.0.1:1080",
+                                &mut proxy.socks_proxy,
+                            ),
+                            ("Username:", "", &mut proxy.username),
+                        ] {
+                            ui.label(label);
+                            TextEdit::singleline(value)
+                                .hint_text(hint)
+                                .desired_width(f32::INFINITY)
+                                .ui(ui);
+                            ui.end_row();
+                        }
+                        ui.label("Password:");
+                        TextEdit::singleline(&mut proxy.password)
+                            .password(true)
+                            .desired_width(f32::INFINITY)
+                            .ui(ui);
+                        ui.end_row();
+                        ui.label("Bypass:");
+                        TextEdit::singleline(&mut proxy.no_proxy)
+                            .hint_text("localhost, 127.0.0.1, .internal.example.com")
+                            .desired_width(f32::INFINITY)
+                            .ui(ui);
+                        ui.end_row();
+                    });
+                });
+            }
+        }
+    }
+
+    fn render_file(ui: &mut Ui, path: &mut String) {
+        TextEdit::singleline(path).desired_width(300.0).ui(ui);
+        if ui.button("Browse").clicked() {
+            if let Some(file) = rfd::FileDialog::new().pick_file() {
+                *path = file.to_string_lossy().to_string();
+            }
+        }
+    }
+}
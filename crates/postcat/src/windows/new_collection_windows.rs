@@ -98,6 +98,9 @@ impl Window for NewCollectionWindows {
                 self.build_desc(ui);
             }
             NewCollectionContentType::Authorization => {
+                self.auth_panel.set_client_config(
+                    workspace_data.get_client_config(workspace_data.get_env_select()),
+                );
                 self.build_auth(ui);
             }
             NewCollectionContentType::Variables => {
@@ -124,6 +127,7 @@ impl Window for NewCollectionWindows {
                         Vec::new(),
                         Request::default(),
                         env,
+                        workspace_data.get_proxy_config(workspace_data.get_env_select()),
                         "collection".to_string(),
                     );
             }
@@ -186,6 +190,7 @@ impl NewCollectionWindows {
                     folders: cf.borrow().folders.clone(),
                     pre_request_script: cf.borrow().pre_request_script.clone(),
                     test_script: cf.borrow().test_script.clone(),
+                    order: cf.borrow().order,
                 }));
             }
         }
@@ -370,7 +375,12 @@ impl NewCollectionWindows {
                                 }
                                 Some(parent_folder) => {
                                     match &self.old_folder_name {
-                                        None => {}
+                                        None => {
+                                            let order = parent_folder
+                                                .borrow()
+                                                .order_of(self.folder.borrow().name.as_str(), true);
+                                            self.folder.borrow_mut().order = order;
+                                        }
                                         Some(old_name) => {
                                             workspace_data.remove_folder(
                                                 parent_folder.clone(),
@@ -193,6 +193,7 @@ impl SaveCRTWindows {
                                         folders: Default::default(),
                                         pre_request_script: "".to_string(),
                                         test_script: "".to_string(),
+                                        order: 0,
                                     })),
                                 );
                             }
@@ -226,6 +227,7 @@ impl SaveCRTWindows {
                             folders: BTreeMap::default(),
                             pre_request_script: "".to_string(),
                             test_script: "".to_string(),
+                            order: 0,
                         })),
                         ..Default::default()
                     });
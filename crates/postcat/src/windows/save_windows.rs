@@ -224,6 +224,7 @@ impl SaveWindows {
                                         folders: Default::default(),
                                         pre_request_script: "".to_string(),
                                         test_script: "".to_string(),
+                                        order: 0,
                                     })),
                                 );
                             }
@@ -257,6 +258,7 @@ impl SaveWindows {
                             folders: BTreeMap::default(),
                             pre_request_script: "".to_string(),
                             test_script: "".to_string(),
+                            order: 0,
                         })),
                         ..Default::default()
                     });